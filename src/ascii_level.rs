@@ -0,0 +1,161 @@
+//! ASCII-art levels for tests and quick prototyping
+//!
+//! Each line is a row of tiles:
+//!
+//! - `#`: solid tile
+//! - `P`: player spawn (exactly one)
+//! - `^`: hazard
+//! - `-`: one-way platform
+//! - `.` or space: empty
+//!
+//! ```ignore
+//! let level = parse_ascii_level(
+//!     "
+//!     #......#
+//!     #.P....#
+//!     #...--.#
+//!     ########
+//!     ",
+//! )?;
+//! ```
+//!
+//! Common indentation and blank lines around the grid are ignored, and short rows are padded
+//! with empty tiles.
+
+use std::fmt;
+
+use crate::level::{AreaData, LevelData};
+
+/// Tile value of a solid square in `LevelData::tiles`
+const SOLID_TILE: u32 = 1;
+/// Thickness (tiles) of the one-way platform at the top of a `-` cell
+const ONE_WAY_THICKNESS: f32 = 0.25;
+
+/// A parsed ASCII-art level
+pub struct AsciiLevel {
+    /// Tile grid and spawn
+    pub data: LevelData,
+    /// Tile positions (cell centers) of `^` cells (also added to `LevelData::hazards`)
+    pub hazards: Vec<[f32; 2]>,
+    /// Tile positions (cell centers) of `-` cells (also added to
+    /// `LevelData::one_way_platforms`)
+    pub one_way_platforms: Vec<[f32; 2]>,
+}
+
+/// Why an ASCII-art level can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsciiLevelError {
+    /// The text has no rows
+    Empty,
+    /// A character that isn't a tile
+    UnknownTile {
+        row: usize,
+        column: usize,
+        character: char,
+    },
+    /// No `P` cell
+    MissingSpawn,
+    /// More than one `P` cell
+    MultipleSpawns,
+}
+
+impl fmt::Display for AsciiLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the level has no rows"),
+            Self::UnknownTile {
+                row,
+                column,
+                character,
+            } => write!(
+                f,
+                "unknown tile {character:?} at row {row}, column {column}"
+            ),
+            Self::MissingSpawn => write!(f, "the level has no spawn (P)"),
+            Self::MultipleSpawns => write!(f, "the level has more than one spawn (P)"),
+        }
+    }
+}
+
+impl std::error::Error for AsciiLevelError {}
+
+/// Parses an ASCII-art level
+pub fn parse_ascii_level(text: &str) -> Result<AsciiLevel, AsciiLevelError> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return Err(AsciiLevelError::Empty);
+    };
+    let lines = &lines[first..=last];
+
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let rows: Vec<Vec<char>> = lines
+        .iter()
+        .map(|line| line.chars().skip(indent).collect())
+        .collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+
+    let mut tiles = vec![vec![0; width]; rows.len()];
+    let mut spawn = None;
+    let mut hazards = Vec::new();
+    let mut one_way_platforms = Vec::new();
+
+    for (row, characters) in rows.iter().enumerate() {
+        for (column, &character) in characters.iter().enumerate() {
+            let center = [column as f32 + 0.5, row as f32 + 0.5];
+            match character {
+                '#' => tiles[row][column] = SOLID_TILE,
+                'P' if spawn.is_some() => return Err(AsciiLevelError::MultipleSpawns),
+                'P' => spawn = Some(center),
+                '^' => hazards.push(center),
+                '-' => one_way_platforms.push(center),
+                '.' | ' ' => {}
+                _ => {
+                    return Err(AsciiLevelError::UnknownTile {
+                        row,
+                        column,
+                        character,
+                    })
+                }
+            }
+        }
+    }
+
+    let spawn = spawn.ok_or(AsciiLevelError::MissingSpawn)?;
+
+    // One-way platforms cover the top of their cell
+    let one_way_areas = one_way_platforms
+        .iter()
+        .map(|&[x, y]| AreaData {
+            min: [x - 0.5, y - 0.5],
+            max: [x + 0.5, y - 0.5 + ONE_WAY_THICKNESS],
+        })
+        .collect();
+
+    // Hazards fill their cell
+    let hazard_areas = hazards
+        .iter()
+        .map(|&[x, y]| AreaData {
+            min: [x - 0.5, y - 0.5],
+            max: [x + 0.5, y + 0.5],
+        })
+        .collect();
+
+    Ok(AsciiLevel {
+        data: LevelData {
+            tiles,
+            spawn: Some(spawn),
+            one_way_platforms: one_way_areas,
+            hazards: hazard_areas,
+            ..Default::default()
+        },
+        hazards,
+        one_way_platforms,
+    })
+}
@@ -0,0 +1,195 @@
+use bevy::{input::ButtonInput, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{progression::ProgressSave, schedule::ControllerPhase};
+
+// Assist menu constants
+const ASSIST_MENU_MARGIN: f32 = 24.0;
+const ASSIST_MENU_PADDING: f32 = 12.0;
+// Game speed range and step (percent)
+const MIN_GAME_SPEED: u32 = 50;
+const MAX_GAME_SPEED: u32 = 100;
+const GAME_SPEED_STEP: u32 = 10;
+const MAX_EXTRA_AIR_JUMPS: u32 = 3;
+const ASSIST_MENU_ROWS: usize = 4;
+
+/// Assist mode: slower game speed, extra air jumps and invulnerability
+///
+/// F4 opens the assist menu. Level attempts played with assist enabled are flagged as assisted
+/// in their run records.
+pub struct AssistPlugin;
+
+impl Plugin for AssistPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssistMode>()
+            .init_resource::<AssistMenu>()
+            .add_systems(Update, s_assist_menu.before(ControllerPhase::Input));
+    }
+}
+
+/// Assist mode settings
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct AssistMode {
+    pub enabled: bool,
+    /// Simulation speed (percent) while assist is enabled
+    pub game_speed: u32,
+    /// Air jumps added on top of `ControllerConfig::air_jumps`
+    pub extra_air_jumps: u32,
+    /// Whether hazards should ignore the player
+    pub invulnerable: bool,
+}
+
+impl Default for AssistMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            game_speed: MAX_GAME_SPEED,
+            extra_air_jumps: 0,
+            invulnerable: false,
+        }
+    }
+}
+
+impl AssistMode {
+    /// Simulation speed multiplier (1.0 when assist is disabled)
+    pub fn speed(&self) -> f32 {
+        if self.enabled {
+            self.game_speed as f32 / 100.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Extra air jumps granted (0 when assist is disabled)
+    pub fn air_jumps(&self) -> u32 {
+        if self.enabled {
+            self.extra_air_jumps
+        } else {
+            0
+        }
+    }
+
+    /// Whether the player currently ignores hazards
+    pub fn is_invulnerable(&self) -> bool {
+        self.enabled && self.invulnerable
+    }
+}
+
+/// Currently open assist menu, if any
+#[derive(Resource, Default)]
+pub struct AssistMenu(pub Option<AssistMenuState>);
+
+pub struct AssistMenuState {
+    selected: usize,
+    /// Root UI node of the menu
+    menu: Entity,
+}
+
+/// Marker for the assist menu text
+#[derive(Component)]
+pub struct AssistText;
+
+/// F4 toggles the assist menu; Up/Down selects a setting and Left/Right changes it
+///
+/// The game is paused while the menu is open and resumes at the assist game speed.
+/// Keys used here are consumed so they don't also move the player. Closing the menu saves the
+/// settings to the current profile.
+pub fn s_assist_menu(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut assist_menu: ResMut<AssistMenu>,
+    mut assist: ResMut<AssistMode>,
+    mut save: ProgressSave,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut text_query: Query<&mut Text, With<AssistText>>,
+) {
+    let toggle = keyboard_input.clear_just_pressed(KeyCode::F4);
+
+    if assist_menu.0.is_none() {
+        if toggle {
+            virtual_time.pause();
+
+            let menu = commands
+                .spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(ASSIST_MENU_MARGIN),
+                        top: Val::Px(ASSIST_MENU_MARGIN),
+                        padding: UiRect::all(Val::Px(ASSIST_MENU_PADDING)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new(""), AssistText));
+                })
+                .id();
+
+            assist_menu.0 = Some(AssistMenuState { selected: 0, menu });
+        }
+        return;
+    }
+    let Some(menu_state) = &mut assist_menu.0 else {
+        return;
+    };
+
+    if toggle | keyboard_input.clear_just_pressed(KeyCode::Tab) {
+        commands.entity(menu_state.menu).despawn();
+        virtual_time.unpause();
+        virtual_time.set_relative_speed(assist.speed());
+        assist_menu.0 = None;
+
+        save.progress.0.settings.assist = Some(assist.clone());
+        save.store();
+        return;
+    }
+
+    if keyboard_input.clear_just_pressed(KeyCode::ArrowUp) && menu_state.selected > 0 {
+        menu_state.selected -= 1;
+    }
+    if keyboard_input.clear_just_pressed(KeyCode::ArrowDown)
+        && menu_state.selected + 1 < ASSIST_MENU_ROWS
+    {
+        menu_state.selected += 1;
+    }
+
+    let decrease = keyboard_input.clear_just_pressed(KeyCode::ArrowLeft);
+    let increase = keyboard_input.clear_just_pressed(KeyCode::ArrowRight);
+    if decrease || increase {
+        match menu_state.selected {
+            0 => assist.enabled = !assist.enabled,
+            1 if increase => {
+                assist.game_speed = (assist.game_speed + GAME_SPEED_STEP).min(MAX_GAME_SPEED)
+            }
+            1 => {
+                assist.game_speed = assist
+                    .game_speed
+                    .saturating_sub(GAME_SPEED_STEP)
+                    .max(MIN_GAME_SPEED)
+            }
+            2 if increase => {
+                assist.extra_air_jumps = (assist.extra_air_jumps + 1).min(MAX_EXTRA_AIR_JUMPS)
+            }
+            2 => assist.extra_air_jumps = assist.extra_air_jumps.saturating_sub(1),
+            _ => assist.invulnerable = !assist.invulnerable,
+        }
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        let on_off = |value: bool| if value { "on" } else { "off" };
+        let rows = [
+            format!("Assist mode: {}", on_off(assist.enabled)),
+            format!("Game speed: {}%", assist.game_speed),
+            format!("Extra air jumps: {}", assist.extra_air_jumps),
+            format!("Invulnerable: {}", on_off(assist.invulnerable)),
+        ];
+
+        let mut menu_text = "Assist (runs are marked as assisted)\n".to_string();
+        for (i, row) in rows.iter().enumerate() {
+            let cursor = if i == menu_state.selected { ">" } else { " " };
+            menu_text.push_str(&format!("{} {}\n", cursor, row));
+        }
+        menu_text.push_str("Up/Down: select, Left/Right: change, F4/Tab: close");
+        text.0 = menu_text;
+    }
+}
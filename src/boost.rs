@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{gravity::Gravity, s_render, schedule::ControllerPhase, Physics, Player};
+
+// Booster constants
+const BOOSTER_RADIUS: f32 = 12.0;
+const BOOSTER_COLOR: Color = Color::srgb(1.0, 0.5, 0.1);
+
+/// Boost rings / dash pads: overlapping one sets the player's velocity along its orientation
+pub struct BoostPlugin;
+
+impl Plugin for BoostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            s_boosters
+                .after(ControllerPhase::Input)
+                .before(ControllerPhase::Movement),
+        )
+        .add_systems(Update, s_render_boosters.after(s_render));
+    }
+}
+
+/// Ring or pad that sends the player along `direction` at `speed`
+#[derive(Component)]
+pub struct Booster {
+    /// Boost direction (normalized, world space)
+    pub direction: Vec2,
+    /// Speed the player is set to (pixels/second)
+    pub speed: f32,
+    /// Time (seconds) the player can't steer after the boost (0.0 for none)
+    pub lockout: f32,
+}
+
+/// Boosts the player when they start overlapping a booster
+///
+/// Staying inside doesn't boost again; the player has to leave and re-enter. Boosting away
+/// from the ground leaves it, so no coyote jump follows.
+pub fn s_boosters(
+    gravity: Res<Gravity>,
+    booster_query: Query<(Entity, &Transform, &Booster)>,
+    mut player_query: Query<(&Transform, &mut Physics, &mut Player)>,
+    mut overlapped: Local<Option<Entity>>,
+) {
+    let Ok((player_transform, mut player_physics, mut player_data)) = player_query.single_mut()
+    else {
+        return;
+    };
+
+    let player_pos = player_transform.translation.xy();
+    let overlapping = booster_query.iter().find(|(_, booster_transform, _)| {
+        booster_transform.translation.xy().distance(player_pos)
+            <= player_physics.radius + BOOSTER_RADIUS
+    });
+
+    let Some((entity, _, booster)) = overlapping else {
+        *overlapped = None;
+        return;
+    };
+    if *overlapped == Some(entity) {
+        return;
+    }
+    *overlapped = Some(entity);
+
+    player_physics.velocity = booster.direction * booster.speed;
+    player_data.control_lock_timer = player_data.control_lock_timer.max(booster.lockout);
+    player_data.jumping = false;
+
+    let frame = gravity.frame_at(player_pos);
+    if frame.to_local(booster.direction).y > 0.0 {
+        player_data.grounded_timer = 0.0;
+    }
+}
+
+/// Draws boosters with their direction
+pub fn s_render_boosters(mut gizmos: Gizmos, booster_query: Query<(&Transform, &Booster)>) {
+    for (booster_transform, booster) in &booster_query {
+        let booster_pos = booster_transform.translation.xy();
+        gizmos.circle_2d(booster_pos, BOOSTER_RADIUS, BOOSTER_COLOR);
+        gizmos.arrow_2d(
+            booster_pos - booster.direction * BOOSTER_RADIUS,
+            booster_pos + booster.direction * BOOSTER_RADIUS,
+            BOOSTER_COLOR,
+        );
+    }
+}
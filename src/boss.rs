@@ -0,0 +1,276 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::LandingContact,
+    death::{s_respawn, PlayerRespawned},
+    feedback::{FeedbackCue, FeedbackKind},
+    gravity::Gravity,
+    levels::{GameState, LevelEntity},
+    moving_platform::MovingPlatform,
+    schedule::ControllerPhase,
+    Level, Physics, Player,
+};
+
+pub const BOSS_COLOR: Color = Color::srgb(0.8, 0.3, 0.6);
+pub const BOSS_WALL_COLOR: Color = Color::srgb(0.6, 0.2, 0.4);
+// Upward speed (pixels/second) the player bounces off the boss with after a stomp
+const STOMP_BOUNCE_VELOCITY: f32 = 420.0;
+// Time (seconds) after a hit during which the boss can't be hit again
+const BOSS_HIT_COOLDOWN: f32 = 0.5;
+
+// HP bar constants
+const HP_BAR_WIDTH: f32 = 400.0;
+const HP_BAR_HEIGHT: f32 = 16.0;
+const HP_BAR_MARGIN: f32 = 24.0;
+const HP_BAR_FILL_COLOR: Color = Color::srgb(0.9, 0.2, 0.3);
+
+/// Boss fights: a moving platform body hit by stomping on it, with HP thresholds switching
+/// phases that change its speed and the arena walls, and an HP bar while the fight is on
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            s_start_boss_fights
+                .before(ControllerPhase::Input)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(Update, s_boss_damage.after(ControllerPhase::Collision))
+        .add_systems(Update, s_reset_bosses.after(s_respawn))
+        .add_systems(Update, s_boss_hp_bar.after(s_boss_damage));
+    }
+}
+
+/// Where a boss is in its fight
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BossState {
+    /// Waiting for the player to enter the arena
+    Dormant,
+    /// Fighting in `Boss::phase`
+    Fighting,
+    /// Out of HP; stays defeated across respawns
+    Defeated,
+}
+
+/// Stage of a boss fight
+pub struct BossPhase {
+    /// The phase starts once the boss's HP drops to this
+    pub hp: u32,
+    /// Speed of the boss's body along its path (pixels/second)
+    pub speed: f32,
+    /// Polygons in `Level::polygons` that are solid during this phase
+    pub walls: Vec<usize>,
+}
+
+/// Boss state machine, on the same entity as the `MovingPlatform` of its body
+#[derive(Component)]
+pub struct Boss {
+    pub name: String,
+    /// Entering this area starts the fight
+    pub arena: Rect,
+    pub max_hp: u32,
+    pub hp: u32,
+    /// Phases in order of decreasing HP thresholds
+    pub phases: Vec<BossPhase>,
+    /// Index of the current phase in `phases`
+    pub phase: usize,
+    pub state: BossState,
+    /// Time (seconds) left before the boss can be hit again
+    pub hit_timer: f32,
+}
+
+impl Boss {
+    /// Phase for the current HP: the last one whose threshold was reached
+    pub fn phase_for_hp(&self) -> usize {
+        self.phases
+            .iter()
+            .rposition(|phase| self.hp <= phase.hp)
+            .unwrap_or(0)
+    }
+
+    /// Makes the arena match the state: only the current phase's walls are solid mid-fight
+    pub fn apply_arena(&self, level: &mut Level) {
+        for (index, phase) in self.phases.iter().enumerate() {
+            let solid = self.state == BossState::Fighting && index == self.phase;
+            for &wall in &phase.walls {
+                level.polygons[wall].enabled = solid;
+            }
+        }
+    }
+}
+
+/// Starts the fight of dormant bosses whose arena the player entered
+pub fn s_start_boss_fights(
+    mut level: ResMut<Level>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<(&mut Boss, &mut MovingPlatform)>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.xy();
+
+    for (mut boss, mut platform) in &mut boss_query {
+        if boss.state == BossState::Dormant && boss.arena.contains(player_pos) {
+            boss.state = BossState::Fighting;
+            boss.phase = boss.phase_for_hp();
+            platform.speed = boss.phases.get(boss.phase).map_or(0.0, |phase| phase.speed);
+            boss.apply_arena(&mut level);
+        }
+    }
+}
+
+/// Landing on a fighting boss costs it a hit point and bounces the player off; crossing an HP
+/// threshold moves the fight to the next phase, and the last hit defeats the boss
+pub fn s_boss_damage(
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    mut landing_reader: MessageReader<LandingContact>,
+    mut level: ResMut<Level>,
+    mut boss_query: Query<(&mut Boss, &mut MovingPlatform)>,
+    mut player_query: Query<(&Transform, &mut Physics, &mut Player)>,
+    mut cue_writer: MessageWriter<FeedbackCue>,
+) {
+    let dt = time.delta_secs();
+    for (mut boss, _) in &mut boss_query {
+        boss.hit_timer = (boss.hit_timer - dt).max(0.0);
+    }
+
+    for landing in landing_reader.read() {
+        for (mut boss, mut platform) in &mut boss_query {
+            if Some(platform.polygon) != landing.polygon
+                || boss.state != BossState::Fighting
+                || boss.hit_timer > 0.0
+            {
+                continue;
+            }
+
+            boss.hp = boss.hp.saturating_sub(1);
+            boss.hit_timer = BOSS_HIT_COOLDOWN;
+            cue_writer.write(FeedbackCue {
+                kind: FeedbackKind::Hit,
+                intensity: 1.0 - boss.hp as f32 / boss.max_hp.max(1) as f32,
+            });
+
+            if boss.hp == 0 {
+                boss.state = BossState::Defeated;
+                platform.speed = 0.0;
+                level.polygons[platform.polygon].enabled = false;
+            } else {
+                boss.phase = boss.phase_for_hp();
+                platform.speed = boss.phases.get(boss.phase).map_or(0.0, |phase| phase.speed);
+            }
+            boss.apply_arena(&mut level);
+
+            // Bounce off the boss
+            if let Ok((player_transform, mut player_physics, mut player_data)) =
+                player_query.single_mut()
+            {
+                let frame = gravity.frame_at(player_transform.translation.xy());
+                let mut velocity = frame.to_local(player_physics.velocity);
+                velocity.y = STOMP_BOUNCE_VELOCITY;
+                player_physics.velocity = frame.to_world(velocity);
+                player_data.jump_timer = 0.0;
+                player_data.grounded_timer = 0.0;
+            }
+        }
+    }
+}
+
+/// Restarts unfinished fights when the player respawns
+pub fn s_reset_bosses(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    mut level: ResMut<Level>,
+    mut boss_query: Query<(&mut Boss, &mut MovingPlatform)>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    for (mut boss, mut platform) in &mut boss_query {
+        if boss.state == BossState::Defeated {
+            continue;
+        }
+
+        boss.state = BossState::Dormant;
+        boss.hp = boss.max_hp;
+        boss.phase = 0;
+        boss.hit_timer = 0.0;
+        platform.speed = 0.0;
+        boss.apply_arena(&mut level);
+    }
+}
+
+/// Root node of the boss HP bar
+#[derive(Component)]
+pub struct BossHpBar;
+
+/// Filled part of the boss HP bar
+#[derive(Component)]
+pub struct BossHpFill;
+
+/// Shows an HP bar for the boss being fought, and removes it when the fight ends
+pub fn s_boss_hp_bar(
+    mut commands: Commands,
+    boss_query: Query<&Boss>,
+    bar_query: Query<Entity, With<BossHpBar>>,
+    mut fill_query: Query<&mut Node, With<BossHpFill>>,
+) {
+    let boss = boss_query
+        .iter()
+        .find(|boss| boss.state == BossState::Fighting);
+
+    let Some(boss) = boss else {
+        for bar_entity in &bar_query {
+            commands.entity(bar_entity).despawn();
+        }
+        return;
+    };
+
+    let fraction = boss.hp as f32 / boss.max_hp.max(1) as f32;
+    if let Ok(mut fill_node) = fill_query.single_mut() {
+        fill_node.width = Val::Percent(fraction * 100.0);
+        return;
+    }
+    if !bar_query.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn((
+            LevelEntity,
+            BossHpBar,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(HP_BAR_MARGIN),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(Text::new(boss.name.clone()));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(HP_BAR_WIDTH),
+                        height: Val::Px(HP_BAR_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        BossHpFill,
+                        Node {
+                            width: Val::Percent(fraction * 100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(HP_BAR_FILL_COLOR),
+                    ));
+                });
+        });
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::LandingContact,
+    noise::{NoiseConfig, NoiseEvent, NoiseKind},
+    schedule::ControllerPhase,
+    Level,
+};
+
+pub const BREAKABLE_COLOR: Color = Color::srgb(0.6, 0.9, 1.0);
+
+/// Break-on-land tiles (glass bridges): removed from the collision world the first time the
+/// player lands on them hard enough
+pub struct BreakablePlugin;
+
+impl Plugin for BreakablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, s_break_on_land.after(ControllerPhase::Collision));
+    }
+}
+
+/// Polygon in `Level` that breaks under a hard landing
+#[derive(Component)]
+pub struct Breakable {
+    /// Index of the polygon in `Level::polygons`
+    pub polygon: usize,
+    /// Minimum landing speed (pixels/second) that breaks it
+    pub impact_threshold: f32,
+}
+
+/// Disables the polygons of breakables the player landed on too hard, with a noise where they
+/// shatter
+///
+/// The player stays grounded for the rest of the frame and falls through on the next one, with
+/// the usual coyote time to jump off the pieces.
+pub fn s_break_on_land(
+    mut commands: Commands,
+    mut landing_reader: MessageReader<LandingContact>,
+    mut level: ResMut<Level>,
+    noise_config: Res<NoiseConfig>,
+    mut noise_writer: MessageWriter<NoiseEvent>,
+    breakable_query: Query<(Entity, &Breakable)>,
+) {
+    for landing in landing_reader.read() {
+        for (entity, breakable) in &breakable_query {
+            if Some(breakable.polygon) == landing.polygon
+                && landing.impact_speed >= breakable.impact_threshold
+            {
+                let polygon = &mut level.polygons[breakable.polygon];
+                polygon.enabled = false;
+                noise_writer.write(NoiseEvent {
+                    position: (polygon.aabb.min + polygon.aabb.max) / 2.0,
+                    radius: noise_config.break_radius,
+                    loudness: noise_config.break_loudness,
+                    kind: NoiseKind::BreakableBroken,
+                });
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
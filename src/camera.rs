@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+
+use crate::{
+    death::{s_respawn, PlayerKilled, PlayerRespawned, SpawnPoint},
+    levels::GameState,
+    s_render,
+    schedule::ControllerSystems,
+    Player,
+};
+
+const AUTOSCROLL_COLOR: Color = Color::srgb(0.9, 0.9, 0.4);
+// Speed (pixels/second) the camera moves at between the level view and a scroll path
+const CAMERA_TRANSITION_SPEED: f32 = 600.0;
+
+/// Camera modes: the camera normally frames the level, and autoscroll segments take it over
+/// to scroll between two markers, killing the player if they fall off screen
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraMode>()
+            .add_systems(OnEnter(GameState::Playing), s_reset_camera)
+            .add_systems(
+                Update,
+                s_autoscroll
+                    .after(ControllerSystems)
+                    .before(s_respawn)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, s_reset_autoscroll.after(s_respawn))
+            .add_systems(Update, s_render_autoscroll.after(s_render));
+    }
+}
+
+/// What drives the camera
+#[derive(Resource, Default, Clone, Copy, PartialEq, Debug)]
+pub enum CameraMode {
+    /// Framing the level (the camera rests at the origin)
+    #[default]
+    Follow,
+    /// Scrolling along a segment's path
+    Autoscroll {
+        segment: Entity,
+        /// Distance (pixels) scrolled along the path
+        distance: f32,
+        /// Whether the camera reached the path; scrolling and the off-screen rule start then
+        locked: bool,
+    },
+}
+
+/// Level segment where the camera scrolls from `start` to `end` once the player reaches the
+/// checkpoint at `trigger`
+#[derive(Component)]
+pub struct AutoscrollSegment {
+    /// Entering this area starts the segment and moves the spawn point there
+    pub trigger: Rect,
+    /// Camera center at the start of the scroll (world space)
+    pub start: Vec2,
+    /// Camera center at the end of the scroll (world space)
+    pub end: Vec2,
+    /// Scroll speed (pixels/second)
+    pub speed: f32,
+    /// Whether the segment was scrolled to the end
+    pub done: bool,
+}
+
+/// Puts the camera back on the level view when a level starts
+pub fn s_reset_camera(
+    mut mode: ResMut<CameraMode>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    *mode = CameraMode::Follow;
+    for mut camera_transform in &mut camera_query {
+        camera_transform.translation = Vec3::ZERO;
+    }
+}
+
+/// Starts autoscroll segments at their checkpoint, moves the camera for the current mode and
+/// kills the player when they leave the view during a segment
+pub fn s_autoscroll(
+    time: Res<Time>,
+    mut mode: ResMut<CameraMode>,
+    mut spawn_point: ResMut<SpawnPoint>,
+    mut segment_query: Query<(Entity, &mut AutoscrollSegment)>,
+    player_query: Query<&Transform, With<Player>>,
+    mut camera_query: Query<(&Camera, &mut Transform), (With<Camera2d>, Without<Player>)>,
+    mut killed_writer: MessageWriter<PlayerKilled>,
+) {
+    let Ok((camera, mut camera_transform)) = camera_query.single_mut() else {
+        return;
+    };
+    let dt = time.delta_secs();
+    let player_pos = player_query
+        .single()
+        .ok()
+        .map(|player_transform| player_transform.translation.xy());
+    let camera_pos = camera_transform.translation.xy();
+    let transition_step = CAMERA_TRANSITION_SPEED * dt;
+
+    match *mode {
+        CameraMode::Follow => {
+            // Reaching a segment's checkpoint hands the camera over to it
+            if let Some(player_pos) = player_pos {
+                if let Some((segment_entity, _)) = segment_query
+                    .iter()
+                    .find(|(_, segment)| !segment.done && segment.trigger.contains(player_pos))
+                {
+                    *spawn_point = SpawnPoint::at(player_pos);
+                    *mode = CameraMode::Autoscroll {
+                        segment: segment_entity,
+                        distance: 0.0,
+                        locked: false,
+                    };
+                }
+            }
+
+            let position = camera_pos.move_towards(Vec2::ZERO, transition_step);
+            camera_transform.translation = position.extend(camera_transform.translation.z);
+        }
+        CameraMode::Autoscroll {
+            segment: segment_entity,
+            mut distance,
+            mut locked,
+        } => {
+            let Ok((_, mut segment)) = segment_query.get_mut(segment_entity) else {
+                *mode = CameraMode::Follow;
+                return;
+            };
+
+            let length = segment.start.distance(segment.end);
+            if locked {
+                distance = (distance + segment.speed * dt).min(length);
+            }
+            let target = segment.start.move_towards(segment.end, distance);
+            let position = camera_pos.move_towards(target, transition_step);
+            locked |= position == target;
+            camera_transform.translation = position.extend(camera_transform.translation.z);
+
+            if locked && distance >= length {
+                segment.done = true;
+                *mode = CameraMode::Follow;
+                return;
+            }
+            *mode = CameraMode::Autoscroll {
+                segment: segment_entity,
+                distance,
+                locked,
+            };
+
+            // Falling behind the scroll kills
+            let view_size = camera.logical_viewport_size();
+            if let Some((player_pos, view_size)) = player_pos.zip(view_size) {
+                let view = Rect::from_center_size(position, view_size);
+                if locked && !view.contains(player_pos) {
+                    killed_writer.write(PlayerKilled {
+                        position: player_pos,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Ends the current segment when the player respawns, so its checkpoint starts it again
+pub fn s_reset_autoscroll(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    mut mode: ResMut<CameraMode>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    *mode = CameraMode::Follow;
+}
+
+/// Debug view: checkpoint areas and scroll paths
+pub fn s_render_autoscroll(mut gizmos: Gizmos, segment_query: Query<&AutoscrollSegment>) {
+    for segment in &segment_query {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(segment.trigger.center()),
+            segment.trigger.size(),
+            AUTOSCROLL_COLOR,
+        );
+        gizmos.arrow_2d(segment.start, segment.end, AUTOSCROLL_COLOR);
+    }
+}
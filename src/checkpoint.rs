@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use crate::{
+    death::{s_respawn, PlayerRespawned, SpawnPoint},
+    levels::GameState,
+    moving_platform::{s_reset_moving_platforms, MovingPlatform},
+    s_render,
+    schedule::ControllerSystems,
+    Physics, Player,
+};
+
+// Radius (pixels) of a checkpoint marker; the player activates it by touching it
+const CHECKPOINT_RADIUS: f32 = 8.0;
+const CHECKPOINT_COLOR: Color = Color::srgb(0.4, 0.6, 1.0);
+const ACTIVE_CHECKPOINT_COLOR: Color = Color::srgb(0.4, 1.0, 0.6);
+
+/// Checkpoints: touching one moves the spawn point to it. Checkpoints attached to a moving
+/// platform follow it, and respawning there puts the player where the platform is now, moving
+/// with it
+pub struct CheckpointPlugin;
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            s_checkpoints
+                .after(ControllerSystems)
+                .before(s_respawn)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            s_respawn_on_platforms.after(s_reset_moving_platforms),
+        )
+        .add_systems(Update, s_render_checkpoints.after(s_render));
+    }
+}
+
+/// Point that becomes the spawn point when the player touches it
+#[derive(Component)]
+pub struct Checkpoint {
+    /// World position, or the position in the platform's start frame for attached checkpoints
+    pub position: Vec2,
+    /// Moving platform entity the checkpoint rides
+    pub platform: Option<Entity>,
+}
+
+impl Checkpoint {
+    /// Current world position
+    pub fn world_position(&self, platform_query: &Query<&MovingPlatform>) -> Vec2 {
+        self.platform
+            .and_then(|entity| platform_query.get(entity).ok())
+            .map_or(self.position, |platform| {
+                platform.transform().transform_point2(self.position)
+            })
+    }
+}
+
+/// Moves the spawn point to checkpoints the player touches
+pub fn s_checkpoints(
+    mut spawn_point: ResMut<SpawnPoint>,
+    checkpoint_query: Query<&Checkpoint>,
+    platform_query: Query<&MovingPlatform>,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+) {
+    let Ok((player_transform, player_physics)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.xy();
+
+    for checkpoint in &checkpoint_query {
+        let position = checkpoint.world_position(&platform_query);
+        if position.distance(player_pos) <= CHECKPOINT_RADIUS + player_physics.radius {
+            *spawn_point = SpawnPoint {
+                position,
+                platform: checkpoint
+                    .platform
+                    .map(|entity| (entity, checkpoint.position)),
+            };
+        }
+    }
+}
+
+/// Moves a player respawned at a platform's spawn point to where the platform is now and gives
+/// them its velocity
+pub fn s_respawn_on_platforms(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    spawn_point: Res<SpawnPoint>,
+    platform_query: Query<&MovingPlatform>,
+    mut player_query: Query<(&mut Transform, &mut Physics), With<Player>>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+    let Some((platform, point)) = spawn_point.platform.and_then(|(entity, point)| {
+        platform_query
+            .get(entity)
+            .ok()
+            .map(|platform| (platform, point))
+    }) else {
+        return;
+    };
+    let Ok((mut player_transform, mut player_physics)) = player_query.single_mut() else {
+        return;
+    };
+
+    let position = platform.transform().transform_point2(point);
+    player_transform.translation = position.extend(player_transform.translation.z);
+    player_physics.prev_position = position;
+    player_physics.velocity = platform.velocity_at(point);
+}
+
+/// Checkpoint markers, highlighting the one holding the spawn point
+pub fn s_render_checkpoints(
+    mut gizmos: Gizmos,
+    spawn_point: Res<SpawnPoint>,
+    checkpoint_query: Query<&Checkpoint>,
+    platform_query: Query<&MovingPlatform>,
+) {
+    for checkpoint in &checkpoint_query {
+        let position = checkpoint.world_position(&platform_query);
+        let active = match (spawn_point.platform, checkpoint.platform) {
+            (Some((entity, point)), Some(platform)) => {
+                entity == platform && point == checkpoint.position
+            }
+            (None, None) => spawn_point.position == checkpoint.position,
+            _ => false,
+        };
+        let color = if active {
+            ACTIVE_CHECKPOINT_COLOR
+        } else {
+            CHECKPOINT_COLOR
+        };
+        gizmos.circle_2d(position, CHECKPOINT_RADIUS, color);
+    }
+}
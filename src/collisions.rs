@@ -1,176 +1,875 @@
+use std::time::Duration;
+
 use bevy::{
     app::{App, Plugin, Update},
     color::Color,
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
     ecs::{
+        component::Component,
+        entity::Entity,
+        message::{Message, MessageWriter},
+        resource::Resource,
         schedule::IntoScheduleConfigs,
-        system::{Query, Res},
+        system::{Query, Res, ResMut, SystemParam},
     },
     gizmos::gizmos::Gizmos,
     math::{Vec2, Vec3Swizzles},
+    platform::time::Instant,
+    time::Time,
     transform::components::Transform,
 };
 
 use crate::{
-    s_movement, Aabb, Level, Physics, Player, CEILING_NORMAL_Y_THRESHOLD,
+    config::ControllerConfig,
+    geometry::{closest_point_on_segment, find_projection, line_intersect, side_of_line_detection},
+    gravity::{Gravity, GravityFrame},
+    schedule::ControllerPhase,
+    Aabb, Level, MovementState, Physics, Player, Polygon, CEILING_NORMAL_Y_THRESHOLD, EPSILON,
     GROUND_NORMAL_Y_THRESHOLD, MAX_GROUNDED_TIMER, MAX_WALLED_TIMER, NORMAL_DOT_THRESHOLD,
 };
 
 // Collision detection constants
-const RAYCAST_DIRECTION_SCALE: f32 = 10000.0;
-const RAYCAST_DIRECTION: Vec2 = Vec2::new(2.0, 1.0);
-const TOUCH_THRESHOLD: f32 = 0.5;
+pub(crate) const TOUCH_THRESHOLD: f32 = 0.5;
 const DEBUG_NORMAL_LINE_LENGTH: f32 = 12.0;
-const DISTANCE_CALCULATION_RADIUS_MULTIPLIER: f32 = 2.0;
+const STEP_UP_CLEARANCE: f32 = 0.1;
+// Impacts slower than this (pixels/second) don't bounce, so bounces on springy surfaces settle
+const MIN_BOUNCE_SPEED: f32 = 60.0;
+// Pushes count as opposing (squeezing the player) when their directions' dot product is below this
+const CRUSH_NORMAL_DOT: f32 = -0.7;
+// Penetration (fraction of the player radius) both opposing pushes must reach to crush the player
+const CRUSH_DEPTH_RATIO: f32 = 0.125;
+// Frame motion (fraction of the player radius) above which the swept-circle test runs, so slow
+// movement keeps sliding along surfaces through the discrete pass
+pub(crate) const CCD_MIN_MOTION_RATIO: f32 = 0.5;
+// Gap (pixels) kept between a box body and the geometry it rests against, so resting contacts
+// don't count as overlaps
+const BOX_SKIN: f32 = 0.5;
+// Bisection steps used to find how far a box can move before hitting geometry
+const SWEEP_ITERATIONS: usize = 8;
+// Penetration (pixels) the solver leaves unresolved
+const SOLVER_TOLERANCE: f32 = 0.01;
+// Contact normals closer than this (dot product) count as the same surface
+const NORMAL_MERGE_DOT: f32 = 0.995;
+// Smallest weight a touching contact's normal gets (pixels of overlap)
+const MIN_NORMAL_WEIGHT: f32 = 0.05;
+
+/// Collision detection and resolution
+pub struct CollisionPlugin {
+    /// Steps the player's motion is resolved in each tick (1 resolves only the final position)
+    pub substeps: u32,
+}
 
-pub struct CollisionPlugin;
+impl Default for CollisionPlugin {
+    fn default() -> Self {
+        Self { substeps: 1 }
+    }
+}
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, s_collision.after(s_movement));
+        app.insert_resource(CollisionSubsteps(self.substeps.max(1)))
+            .add_message::<LandingContact>()
+            .add_message::<CrushedEvent>()
+            .add_message::<GroundPoundLanding>()
+            .add_message::<ContactEvent>()
+            .init_resource::<ContactsThisTick>()
+            .init_resource::<CollisionDiagnostics>()
+            .register_diagnostic(Diagnostic::new(CollisionDiagnostics::EDGES_TESTED))
+            .register_diagnostic(Diagnostic::new(CollisionDiagnostics::CONTACTS_FOUND))
+            .register_diagnostic(Diagnostic::new(CollisionDiagnostics::SOLVER_ITERATIONS))
+            .register_diagnostic(
+                Diagnostic::new(CollisionDiagnostics::RESOLUTION_TIME).with_suffix("ms"),
+            )
+            .add_systems(Update, s_debug_collision.after(ControllerPhase::Collision))
+            .add_systems(
+                Update,
+                s_collision_diagnostics.after(ControllerPhase::Collision),
+            );
     }
 }
 
+/// Which bodies and geometry collide: two sides interact when each is a member of a layer the
+/// other filters for
+///
+/// Level polygons, arcs and bodies default to the `DEFAULT` layer and collide with every layer.
+/// For example, walls only enemies bump into filter for `ENEMY`, projectiles that pass through
+/// one-way platforms leave `ONE_WAY` out of their filters, and a ghost replay uses `none()`.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CollisionLayers {
+    pub memberships: u32,
+    pub filters: u32,
+}
+
+impl CollisionLayers {
+    /// Level geometry and bodies without a more specific layer
+    pub const DEFAULT: u32 = 1 << 0;
+    pub const PLAYER: u32 = 1 << 1;
+    pub const ENEMY: u32 = 1 << 2;
+    pub const PROJECTILE: u32 = 1 << 3;
+    /// One-way platforms (always members, whatever their `layers`)
+    pub const ONE_WAY: u32 = 1 << 4;
+    /// Every layer
+    pub const ALL: u32 = u32::MAX;
+
+    pub fn new(memberships: u32, filters: u32) -> Self {
+        Self {
+            memberships,
+            filters,
+        }
+    }
+
+    /// Collides with nothing
+    pub fn none() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Whether the two sides collide
+    pub fn interacts_with(&self, other: &CollisionLayers) -> bool {
+        self.memberships & other.filters != 0 && other.memberships & self.filters != 0
+    }
+
+    /// The same layers, no longer filtering for `layers`
+    pub fn without_filter(self, layers: u32) -> Self {
+        Self::new(self.memberships, self.filters & !layers)
+    }
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT, Self::ALL)
+    }
+}
+
+/// Steps `s_collision` splits the player's motion into each tick: the intermediate positions are
+/// pushed out of the level before the full pass at the end, which keeps high speeds and dense
+/// corner geometry stable
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct CollisionSubsteps(pub u32);
+
+impl Default for CollisionSubsteps {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+/// Edge of a level polygon: the segment from `points[edge]` to `points[edge + 1]` of
+/// `Level::polygons[polygon]`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EdgeId {
+    pub polygon: usize,
+    pub edge: usize,
+}
+
+/// The player landed on a polygon this frame
+#[derive(Message, Clone, Copy)]
+pub struct LandingContact {
+    /// Index of the polygon in `Level::polygons`, `None` for ground that isn't a level polygon
+    /// (avian colliders)
+    pub polygon: Option<usize>,
+    /// Speed (pixels/second) the player was moving into the ground, along gravity
+    pub impact_speed: f32,
+}
+
+/// A ground pound hit the ground this frame
+#[derive(Message, Clone, Copy)]
+pub struct GroundPoundLanding {
+    /// Index of the polygon in `Level::polygons`, `None` for ground that isn't a level polygon
+    /// (avian colliders)
+    pub polygon: Option<usize>,
+    /// Where the player landed
+    pub position: Vec2,
+}
+
+/// The player is squeezed between two polygons pushing in opposite directions, at least one of
+/// them moving (an elevator against a ceiling, a door closing on a wall)
+///
+/// The collision pass leaves the player where they are that tick rather than resolving both
+/// pushes; the crush response (kill, stop the platform) is up to the reader.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct CrushedEvent {
+    pub entity: Entity,
+    /// Indices of the squeezing polygons in `Level::polygons`
+    pub polygons: [usize; 2],
+    /// Unit push directions out of each polygon (world space)
+    pub normals: [Vec2; 2],
+    /// Penetration (pixels) of the shallower push
+    pub depth: f32,
+    /// Where the player was
+    pub position: Vec2,
+}
+
+/// Contact between an entity and a level edge during a physics tick
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub entity: Entity,
+    /// The polygon edge, `None` for arc edges
+    pub edge: Option<EdgeId>,
+    /// Closest point of the edge to the entity (world space)
+    pub point: Vec2,
+    /// Unit direction from the edge toward the entity (world space)
+    pub normal: Vec2,
+    /// Penetration (pixels); negative while only touching within the touch threshold
+    pub depth: f32,
+    /// Entity velocity minus the edge velocity at `point`, before resolution (world space)
+    pub relative_velocity: Vec2,
+}
+
+/// A contact the collision pass pushed an entity out of this tick, one per penetrating edge
+/// (rather than the combined surface normal), for landing effects or damage from specific
+/// edges
+#[derive(Message, Clone, Copy, Debug)]
+pub struct ContactEvent {
+    pub entity: Entity,
+    /// Closest point of the edge to the entity (world space)
+    pub point: Vec2,
+    /// Unit direction from the edge toward the entity (world space)
+    pub normal: Vec2,
+    /// Depth (pixels) the entity was pushed out by
+    pub penetration: f32,
+    /// The polygon edge, `None` for arc edges
+    pub edge_id: Option<EdgeId>,
+}
+
+/// Contacts found by the last collision pass, for analytics, sound, decals and mods to read
+/// without querying the geometry again
+#[derive(Resource, Default, Debug)]
+pub struct ContactsThisTick(pub Vec<Contact>);
+
+/// Work done by the last collision pass, for spotting performance regressions and checking how
+/// much the broad phase prunes
+///
+/// Also published to Bevy's `DiagnosticsStore` under the `collision/` paths.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct CollisionDiagnostics {
+    /// Edge tests the pass ran: the broad-phase candidates once for the swept (CCD) test, once
+    /// per intermediate substep and once for the final pass
+    pub edges_tested: usize,
+    /// Polygon edges the player touched (`ContactsThisTick`)
+    pub contacts_found: usize,
+    /// Relaxation passes `solve_penetrations` ran, over the substeps and the final pass
+    pub solver_iterations: u32,
+    /// Wall-clock time of the pass
+    pub resolution_time: Duration,
+}
+
+impl CollisionDiagnostics {
+    pub const EDGES_TESTED: DiagnosticPath = DiagnosticPath::const_new("collision/edges_tested");
+    pub const CONTACTS_FOUND: DiagnosticPath =
+        DiagnosticPath::const_new("collision/contacts_found");
+    pub const SOLVER_ITERATIONS: DiagnosticPath =
+        DiagnosticPath::const_new("collision/solver_iterations");
+    pub const RESOLUTION_TIME: DiagnosticPath =
+        DiagnosticPath::const_new("collision/resolution_time");
+}
+
+/// What the collision pass reports to the rest of the game
+#[derive(SystemParam)]
+pub struct CollisionOutputs<'w> {
+    pub(crate) landing_writer: MessageWriter<'w, LandingContact>,
+    pub(crate) crushed_writer: MessageWriter<'w, CrushedEvent>,
+    pub(crate) pound_writer: MessageWriter<'w, GroundPoundLanding>,
+    pub(crate) contact_writer: MessageWriter<'w, ContactEvent>,
+    pub(crate) contacts: ResMut<'w, ContactsThisTick>,
+    pub(crate) diagnostics: ResMut<'w, CollisionDiagnostics>,
+}
+
+/// Re-indexes the polygons that moved or were added since last frame before collision reads
+/// the edge grid, and rebuilds the edge BVH when polygons were added or removed
+pub fn s_refresh_broad_phase(mut level: ResMut<Level>) {
+    level.refresh_edge_grid();
+    level.refresh_edge_bvh();
+}
+
 pub fn s_collision(
-    mut player_query: Query<(&mut Transform, &mut Physics, &mut Player)>,
+    mut player_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut Physics,
+        &mut Player,
+        Option<&CollisionLayers>,
+    )>,
     level: Res<Level>,
+    config: Res<ControllerConfig>,
+    gravity: Res<Gravity>,
+    time: Res<Time>,
+    substeps: Res<CollisionSubsteps>,
+    mut outputs: CollisionOutputs,
 ) {
-    if let Ok((mut player_transform, mut player_physics, mut player_data)) =
+    let started = Instant::now();
+    outputs.contacts.0.clear();
+    *outputs.diagnostics = CollisionDiagnostics::default();
+
+    if let Ok((entity, mut player_transform, mut player_physics, mut player_data, layers)) =
         player_query.single_mut()
     {
-        let mut adjustment = Vec2::ZERO;
-        let mut new_player_normal = Vec2::ZERO;
+        let layers = layers.copied().unwrap_or_default();
+        // Surfaces are classified relative to gravity; `penetrations` and `velocity` are in its
+        // frame
+        let frame = gravity.frame_at(player_transform.translation.xy());
+        let mut penetrations: Vec<Vec2> = Vec::new();
+        // Normals of the touched surfaces with their overlap (pixels), for the player's normal
+        let mut contact_normals: Vec<(Vec2, f32)> = Vec::new();
+        let mut velocity = frame.to_local(player_physics.velocity);
+        let impact_speed = (-velocity.y).max(0.0);
+        let entity_velocity = player_physics.velocity;
+        let dt = time.delta_secs();
 
-        // Pre-compute player AABB for broad-phase collision detection
-        let player_pos = player_transform.translation.xy();
-        let player_aabb = Aabb::from_point_radius(player_pos, player_physics.radius);
-        // Expand AABB slightly to account for movement
-        let expanded_player_aabb = player_aabb.expand(player_physics.radius * 0.5);
+        // Pre-compute the swept player AABB for broad-phase collision detection
+        let mut player_pos = player_transform.translation.xy();
+        let swept_player_aabb = Aabb::from_corners(player_physics.prev_position, player_pos)
+            .expand(player_physics.radius * 1.5);
 
         // Pre-compute radius squared to avoid repeated calculations
         let radius_sq = player_physics.radius.powi(2);
         let touch_threshold_sq = (player_physics.radius + TOUCH_THRESHOLD).powi(2);
 
-        for polygon in &level.polygons {
-            // Broad-phase: AABB pre-check to skip polygons far from player
-            if !expanded_player_aabb.overlaps(&polygon.aabb) {
-                continue;
-            }
+        // Surfaces steeper than the max slope angle don't count as ground
+        let min_ground_normal_y = config.max_slope_angle.to_radians().cos();
+        // Surfaces facing further down than this are ceilings
+        let ceiling_normal_y = config.ceiling_normal_y;
+        let mut ceiling_normals: Vec<(Vec2, f32)> = Vec::new();
 
-            let mut intersect_counter = 0;
-            let mut colliding_with_polygon = false;
+        // Bounciness of the surfaces the player is pressed against
+        let mut restitution: f32 = 0.0;
 
-            // Raycast intersection check for point-in-polygon test
-            for i in 1..polygon.points.len() {
-                let start = polygon.points[i - 1];
-                let end = polygon.points[i];
+        // Penetrations as (push direction, depth, polygon index), for crush detection
+        let mut pushes: Vec<(Vec2, f32, usize)> = Vec::new();
+
+        let had_ground_contact = player_data.ground_contact;
+        player_data.ground_contact = false;
+        player_data.ground_edge = None;
+        player_data.sticky_contact = false;
+        player_data.wall_contact_speed = 0.0;
+        let dropping_through = player_data.drop_through_timer > 0.0;
+        // One-way platforms don't hold a player dropping through them
+        let ground_layers = if dropping_through {
+            layers.without_filter(CollisionLayers::ONE_WAY)
+        } else {
+            layers
+        };
+
+        // Broad-phase: only the edges in the grid cells around the swept player circle
+        let candidates = level.edge_grid.query(&swept_player_aabb);
+        outputs.diagnostics.edges_tested = candidates.len();
+
+        // Continuous collision: a fast player stops where their circle first touches an edge
+        // instead of passing through thin geometry between frames
+        let motion = player_pos - player_physics.prev_position;
+        if motion.length() > player_physics.radius * CCD_MIN_MOTION_RATIO {
+            outputs.diagnostics.edges_tested += candidates.len();
+            let time_of_impact = candidates
+                .iter()
+                .filter_map(|edge_id| {
+                    let polygon = &level.polygons[edge_id.polygon];
+                    if !polygon.collides_with(&ground_layers)
+                        || (polygon.one_way && velocity.y > 0.0)
+                    {
+                        return None;
+                    }
+
+                    let (prev_start, prev_end) = polygon.prev_edge(edge_id.edge + 1);
+                    if side_of_line_detection(prev_start, prev_end, player_physics.prev_position)
+                        != polygon.collision_side()
+                    {
+                        return None;
+                    }
 
-                let intersection = line_intersect(
-                    start,
-                    end,
-                    player_pos,
-                    player_pos + RAYCAST_DIRECTION * RAYCAST_DIRECTION_SCALE,
+                    let (start, end) = level.edge_points(*edge_id);
+                    swept_circle_time_of_impact(
+                        player_physics.prev_position,
+                        motion,
+                        player_physics.radius,
+                        start,
+                        end,
+                    )
+                })
+                .min_by(f32::total_cmp);
+
+            if let Some(time_of_impact) = time_of_impact {
+                player_pos = player_physics.prev_position + motion * time_of_impact;
+                player_transform.translation = player_pos.extend(player_transform.translation.z);
+            }
+        }
+
+        // Substeps: walk the motion in equal steps, pushing out of the level after each, and
+        // leave the last step to the full pass below
+        if substeps.0 > 1 {
+            let step = (player_pos - player_physics.prev_position) / substeps.0 as f32;
+            let substep_layers = if velocity.y > 0.0 {
+                layers.without_filter(CollisionLayers::ONE_WAY)
+            } else {
+                ground_layers
+            };
+            let mut position = player_physics.prev_position;
+            for _ in 1..substeps.0 {
+                let next = position + step;
+                let (push, solver_iterations) = substep_push_out(
+                    &level,
+                    &candidates,
+                    (position, next),
+                    player_physics.radius,
+                    substep_layers,
+                    frame,
+                    config.solver_iterations.max(1),
                 );
+                position = next + push;
+                outputs.diagnostics.edges_tested += candidates.len();
+                outputs.diagnostics.solver_iterations += solver_iterations;
+            }
+            player_pos = position + step;
+            player_transform.translation = player_pos.extend(player_transform.translation.z);
+        }
 
-                if intersection.is_some() {
-                    intersect_counter += 1;
-                }
+        let player_aabb = Aabb::from_point_radius(player_pos, player_physics.radius);
+        // Expand AABB slightly to account for movement
+        let expanded_player_aabb = player_aabb.expand(player_physics.radius * 0.5);
+
+        // Closest ground edge touched on landing: (polygon index, squared distance)
+        let mut landing_ground: Option<(usize, f32)> = None;
+
+        for polygon_edges in candidates.chunk_by(|a, b| a.polygon == b.polygon) {
+            let polygon_index = polygon_edges[0].polygon;
+            let polygon = &level.polygons[polygon_index];
+            if !polygon.collides_with(&ground_layers)
+                || !expanded_player_aabb.overlaps(&polygon.aabb)
+            {
+                continue;
             }
 
+            let mut colliding_with_polygon = false;
+
             // Narrow-phase: detailed collision detection with polygon edges
-            for i in 1..polygon.points.len() {
+            for i in polygon_edges.iter().map(|edge_id| edge_id.edge + 1) {
                 let start = polygon.points[i - 1];
                 let end = polygon.points[i];
 
+                // Moving polygons: compare with the edge where it was, so an edge sweeping
+                // into the player still pushes them instead of letting them through
+                let (prev_start, prev_end) = polygon.prev_edge(i);
                 let previous_side_of_line =
-                    side_of_line_detection(start, end, player_physics.prev_position);
+                    side_of_line_detection(prev_start, prev_end, player_physics.prev_position);
 
-                if previous_side_of_line != polygon.collision_side {
+                if previous_side_of_line != polygon.collision_side() {
                     continue;
                 }
 
-                let (distance_sq, projection) =
-                    find_projection(start, end, player_pos, player_physics.radius);
+                let (distance_sq, projection) = find_projection(start, end, player_pos);
+
+                // One-way platforms only hold a player above the edge who isn't rising or
+                // dropping through
+                if polygon.one_way
+                    && (velocity.y > 0.0
+                        || frame
+                            .to_local((player_pos - projection).normalize_or_zero())
+                            .y
+                            < GROUND_NORMAL_Y_THRESHOLD)
+                {
+                    continue;
+                }
 
                 let colliding_with_line = distance_sq <= radius_sq;
                 colliding_with_polygon = colliding_with_polygon || colliding_with_line;
 
-                let touching_line = distance_sq <= touch_threshold_sq;
+                // Ground is touched through the foot sensor when there is one
+                let touching_line = match &config.foot_sensor {
+                    Some(foot)
+                        if frame
+                            .to_local((player_pos - projection).normalize_or_zero())
+                            .y
+                            > GROUND_NORMAL_Y_THRESHOLD =>
+                    {
+                        let foot_center =
+                            player_pos - frame.up * foot.offset_ratio * player_physics.radius;
+                        colliding_with_line
+                            || closest_point_on_segment(start, end, foot_center)
+                                .distance_squared(foot_center)
+                                <= (foot.radius_ratio * player_physics.radius).powi(2)
+                    }
+                    _ => distance_sq <= touch_threshold_sq,
+                };
+
+                if touching_line {
+                    outputs.contacts.0.push(Contact {
+                        entity,
+                        edge: Some(EdgeId {
+                            polygon: polygon_index,
+                            edge: i - 1,
+                        }),
+                        point: projection,
+                        normal: (player_pos - projection).normalize_or_zero(),
+                        depth: player_physics.radius - distance_sq.sqrt(),
+                        relative_velocity: entity_velocity
+                            - edge_velocity(start, end, prev_start, prev_end, projection, dt),
+                    });
+                }
+
+                // Walking into a low step: lift the player onto it instead of stopping them
+                if colliding_with_line && player_data.is_grounded {
+                    let normal_dir = frame.to_local((player_pos - projection).normalize_or_zero());
+                    let moving_into_step = velocity.x * normal_dir.x < 0.0;
+
+                    if normal_dir.x.abs() >= NORMAL_DOT_THRESHOLD && moving_into_step {
+                        if let Some(lift) = step_up_lift(
+                            (start, end),
+                            player_pos,
+                            player_physics.radius,
+                            config.step_height_ratio * player_physics.radius,
+                            &level,
+                            layers,
+                            frame,
+                        ) {
+                            penetrations.push(Vec2::Y * lift);
+                            continue;
+                        }
+                    }
+                }
 
                 if touching_line {
                     let normal_dir = (player_pos - projection).normalize_or_zero();
+                    let local_normal_dir = frame.to_local(normal_dir);
 
-                    // If the line is not above the player
-                    if normal_dir.y >= CEILING_NORMAL_Y_THRESHOLD {
+                    let edge_id = EdgeId {
+                        polygon: polygon_index,
+                        edge: i - 1,
+                    };
+                    let is_wall = local_normal_dir.x.abs() >= NORMAL_DOT_THRESHOLD;
+
+                    // The wall just jumped off can't be grabbed again yet: it only stops the
+                    // player moving into it
+                    if is_wall
+                        && player_data.wall_regrab_timer > 0.0
+                        && player_data.wall_jump_edge == Some(edge_id)
+                    {
+                        if velocity.x * local_normal_dir.x < 0.0 {
+                            velocity.x = 0.0;
+                        }
+                    }
+                    // If the line is not above the player (sticky surfaces hold the player at
+                    // any angle)
+                    else if local_normal_dir.y >= ceiling_normal_y || polygon.sticky {
                         // Add the normal dir to the players new normal
-                        new_player_normal -= normal_dir;
+                        contact_normals.push((
+                            normal_dir,
+                            player_physics.radius + TOUCH_THRESHOLD - distance_sq.sqrt(),
+                        ));
+                        restitution = restitution.max(polygon.edge_material(i - 1).restitution);
+                        player_data.sticky_contact |= polygon.sticky;
 
                         // If the player is on a wall
-                        if normal_dir.x.abs() >= NORMAL_DOT_THRESHOLD {
+                        if is_wall {
                             player_data.wall_timer = MAX_WALLED_TIMER;
-                            player_data.wall_direction = normal_dir.x.signum();
+                            player_data.wall_direction = local_normal_dir.x.signum();
+                            player_data.wall_edge = Some(edge_id);
                             player_data.last_wall_normal = Some(normal_dir);
                             player_data.has_wall_jumped = false;
+                            player_data.wall_contact_speed = player_data
+                                .wall_contact_speed
+                                .max(-velocity.x * local_normal_dir.x);
                         }
 
-                        // If the player is on the ground
-                        if normal_dir.y > GROUND_NORMAL_Y_THRESHOLD {
+                        // If the player is on walkable ground
+                        if local_normal_dir.y > GROUND_NORMAL_Y_THRESHOLD
+                            && local_normal_dir.y >= min_ground_normal_y
+                        {
+                            if !had_ground_contact
+                                && landing_ground.is_none_or(|(_, closest)| distance_sq < closest)
+                            {
+                                landing_ground = Some((polygon_index, distance_sq));
+                            }
+
+                            // A ground pound ends on the ground, into its recovery
+                            if player_data.state == MovementState::GroundPounding {
+                                outputs.pound_writer.write(GroundPoundLanding {
+                                    polygon: Some(polygon_index),
+                                    position: player_pos,
+                                });
+                                player_data.state = MovementState::Normal;
+                                player_data.pound_recovery_timer = config.ground_pound.recovery;
+                            }
+
                             player_data.grounded_timer = MAX_GROUNDED_TIMER;
                             player_data.is_grounded = true;
+                            player_data.ground_contact = true;
+                            player_data.ground_edge = Some(edge_id);
                             player_data.wall_timer = 0.0;
                             player_data.wall_direction = 0.0;
+                            player_data.wall_edge = None;
                             player_data.has_wall_jumped = false;
                         }
                     }
+                    // The line is overhead
+                    else {
+                        ceiling_normals.push((
+                            normal_dir,
+                            player_physics.radius + TOUCH_THRESHOLD - distance_sq.sqrt(),
+                        ));
+                    }
                 }
 
                 if colliding_with_line {
-                    let mut delta = (player_pos - projection).normalize_or_zero();
+                    pushes.push((
+                        (player_pos - projection).normalize_or_zero(),
+                        player_physics.radius - distance_sq.sqrt(),
+                        polygon_index,
+                    ));
+                    outputs.contact_writer.write(ContactEvent {
+                        entity,
+                        point: projection,
+                        normal: (player_pos - projection).normalize_or_zero(),
+                        penetration: player_physics.radius - distance_sq.sqrt(),
+                        edge_id: Some(EdgeId {
+                            polygon: polygon_index,
+                            edge: i - 1,
+                        }),
+                    });
 
-                    if delta.y < CEILING_NORMAL_Y_THRESHOLD {
-                        player_physics.velocity.y = 0.0;
-                    }
+                    let mut delta = frame.to_local((player_pos - projection).normalize_or_zero());
 
-                    // Use squared distance calculation, only compute sqrt when needed
-                    let distance = distance_sq.sqrt();
-                    delta *= player_physics.radius - distance;
+                    // Rising into the corner of an overhead polygon: try to slip around it
+                    let corner_shift = if delta.y <= -NORMAL_DOT_THRESHOLD
+                        && velocity.y > 0.0
+                        && !polygon.sticky
+                    {
+                        corner_correction_shift(
+                            frame.to_local(start),
+                            frame.to_local(end),
+                            frame.to_local(player_pos),
+                            player_physics.radius,
+                            config.corner_correction_distance,
+                        )
+                    } else {
+                        None
+                    };
 
-                    if delta.x.abs() > adjustment.x.abs() {
-                        adjustment.x = delta.x;
-                    }
-                    if delta.y.abs() > adjustment.y.abs() {
-                        adjustment.y = delta.y;
+                    if let Some(shift) = corner_shift {
+                        // Nudge horizontally and keep the upward velocity
+                        delta = Vec2::new(shift, 0.0);
+                    } else {
+                        // Sticky ceilings are part of the normal, which removes the velocity into them
+                        if delta.y < ceiling_normal_y && !polygon.sticky {
+                            // Stop at the ceiling, or bounce off a springy one
+                            velocity.y = if velocity.y >= MIN_BOUNCE_SPEED {
+                                -velocity.y * polygon.edge_material(i - 1).restitution
+                            } else {
+                                0.0
+                            };
+                        }
+
+                        // Use squared distance calculation, only compute sqrt when needed
+                        let distance = distance_sq.sqrt();
+                        delta *= player_physics.radius - distance;
                     }
+
+                    penetrations.push(delta);
                 }
             }
 
-            // Point-in-polygon check: if inside polygon and raycast intersects odd number of times
-            if colliding_with_polygon && intersect_counter % 2 == 1 {
+            // Tunnelled into the solid part of the polygon: back to where the player was
+            if colliding_with_polygon && level.inside_solid(polygon_index, player_pos) {
                 player_transform.translation = player_physics.prev_position.extend(0.0);
             }
         }
 
-        // Update the players normal
-        new_player_normal = new_player_normal.normalize_or_zero();
+        // One landing per touchdown, on the deepest ground edge (a tile corner touches two)
+        if let Some((polygon_index, _)) = landing_ground {
+            outputs.landing_writer.write(LandingContact {
+                polygon: Some(polygon_index),
+                impact_speed,
+            });
+        }
+
+        // Curved edges: the closest point of the arc stands in for the projection on an edge
+        for arc in &level.arcs {
+            if !arc.layers.interacts_with(&layers)
+                || !expanded_player_aabb.overlaps(&arc.aabb())
+                || !arc.is_open_side(player_physics.prev_position)
+            {
+                continue;
+            }
+
+            let projection = arc.closest_point(player_pos);
+            let distance_sq = projection.distance_squared(player_pos);
+            if distance_sq > touch_threshold_sq {
+                continue;
+            }
+
+            let normal_dir = (player_pos - projection).normalize_or_zero();
+            let local_normal_dir = frame.to_local(normal_dir);
+
+            if local_normal_dir.y < ceiling_normal_y {
+                ceiling_normals.push((
+                    normal_dir,
+                    player_physics.radius + TOUCH_THRESHOLD - distance_sq.sqrt(),
+                ));
+            } else {
+                contact_normals.push((
+                    normal_dir,
+                    player_physics.radius + TOUCH_THRESHOLD - distance_sq.sqrt(),
+                ));
+
+                if local_normal_dir.x.abs() >= NORMAL_DOT_THRESHOLD {
+                    player_data.wall_timer = MAX_WALLED_TIMER;
+                    player_data.wall_direction = local_normal_dir.x.signum();
+                    player_data.wall_edge = None;
+                    player_data.last_wall_normal = Some(normal_dir);
+                    player_data.has_wall_jumped = false;
+                }
+
+                if local_normal_dir.y > GROUND_NORMAL_Y_THRESHOLD
+                    && local_normal_dir.y >= min_ground_normal_y
+                {
+                    if player_data.state == MovementState::GroundPounding {
+                        player_data.state = MovementState::Normal;
+                        player_data.pound_recovery_timer = config.ground_pound.recovery;
+                    }
+
+                    player_data.grounded_timer = MAX_GROUNDED_TIMER;
+                    player_data.is_grounded = true;
+                    player_data.ground_contact = true;
+                    player_data.wall_timer = 0.0;
+                    player_data.wall_direction = 0.0;
+                    player_data.wall_edge = None;
+                    player_data.has_wall_jumped = false;
+                }
+            }
+
+            if distance_sq <= radius_sq {
+                outputs.contact_writer.write(ContactEvent {
+                    entity,
+                    point: projection,
+                    normal: normal_dir,
+                    penetration: player_physics.radius - distance_sq.sqrt(),
+                    edge_id: None,
+                });
+
+                if local_normal_dir.y < ceiling_normal_y {
+                    velocity.y = velocity.y.min(0.0);
+                }
+
+                penetrations.push(local_normal_dir * (player_physics.radius - distance_sq.sqrt()));
+            }
+        }
+
+        // Two-sided penetration: opposing pushes that each go deep enough, with one of the
+        // polygons moving, crush the player
+        let crush_depth = CRUSH_DEPTH_RATIO * player_physics.radius;
+        let crush = pushes.iter().enumerate().find_map(|(i, a)| {
+            pushes[i + 1..].iter().find_map(|b| {
+                let opposing = a.0.dot(b.0) < CRUSH_NORMAL_DOT;
+                let moving = !level.polygons[a.2].prev_points.is_empty()
+                    || !level.polygons[b.2].prev_points.is_empty();
+                (opposing && moving && a.1.min(b.1) >= crush_depth).then_some((a, b))
+            })
+        });
+        if let Some((a, b)) = crush {
+            outputs.crushed_writer.write(CrushedEvent {
+                entity,
+                polygons: [a.2, b.2],
+                normals: [a.0, b.0],
+                depth: a.1.min(b.1),
+                position: player_pos,
+            });
+        }
+
+        // Resolve every penetration together rather than the deepest per axis, which jitters in
+        // acute corners and V-shaped pits; a crushed player stays put for the crush response
+        // instead of being shot out between the surfaces
+        let (mut adjustment, solver_iterations) = if crush.is_some() {
+            (Vec2::ZERO, 0)
+        } else {
+            solve_penetrations(&penetrations, config.solver_iterations.max(1))
+        };
+        outputs.diagnostics.solver_iterations += solver_iterations;
+
+        // Ground snapping: stay glued to the ground when running downhill or over convex corners
+        if had_ground_contact
+            && !player_data.ground_contact
+            && velocity.y <= 0.0
+            && config.ground_snap_distance > 0.0
+        {
+            if let Some((distance, normal_dir, edge_id)) = ground_snap(
+                &level,
+                player_pos,
+                player_physics.radius,
+                config.ground_snap_distance,
+                min_ground_normal_y,
+                ground_layers,
+                frame,
+            ) {
+                adjustment.y -= distance;
+                contact_normals.push((normal_dir, player_physics.radius));
+
+                player_data.grounded_timer = MAX_GROUNDED_TIMER;
+                player_data.is_grounded = true;
+                player_data.ground_contact = true;
+                player_data.ground_edge = Some(edge_id);
+                player_data.wall_timer = 0.0;
+                player_data.wall_direction = 0.0;
+                player_data.wall_edge = None;
+                player_data.has_wall_jumped = false;
+            }
+        }
+
+        // Ceilings overhead, for head bumps, corner correction and hanging
+        player_data.touching_ceiling = !ceiling_normals.is_empty();
+        player_data.ceiling_normal = player_data
+            .touching_ceiling
+            .then(|| combine_contact_normals(&ceiling_normals));
+
+        // Update the players normal (pointing into the surfaces)
+        let new_player_normal = -combine_contact_normals(&contact_normals);
         player_physics.normal = new_player_normal;
 
-        // Remove the players velocity in the direction of the normal
-        let velocity_adjustment =
-            player_physics.velocity.dot(new_player_normal) * new_player_normal;
+        // Remove the players velocity in the direction of the normal, reflecting part of it
+        // off springy surfaces
+        player_physics.velocity = frame.to_world(velocity);
+        let normal_speed = player_physics.velocity.dot(new_player_normal);
+        let bounce = if normal_speed >= MIN_BOUNCE_SPEED {
+            1.0 + restitution
+        } else {
+            1.0
+        };
+        let velocity_adjustment = normal_speed * bounce * new_player_normal;
 
         player_physics.velocity -= velocity_adjustment;
 
         // Update the players position
-        player_transform.translation += adjustment.extend(0.0);
+        player_transform.translation += frame.to_world(adjustment).extend(0.0);
     }
+
+    outputs.diagnostics.contacts_found = outputs.contacts.0.len();
+    outputs.diagnostics.resolution_time = started.elapsed();
+}
+
+/// Publishes the last collision pass's `CollisionDiagnostics` to Bevy's diagnostics
+pub fn s_collision_diagnostics(
+    collision_diagnostics: Res<CollisionDiagnostics>,
+    mut diagnostics: Diagnostics,
+) {
+    let stats = *collision_diagnostics;
+    diagnostics.add_measurement(&CollisionDiagnostics::EDGES_TESTED, || {
+        stats.edges_tested as f64
+    });
+    diagnostics.add_measurement(&CollisionDiagnostics::CONTACTS_FOUND, || {
+        stats.contacts_found as f64
+    });
+    diagnostics.add_measurement(&CollisionDiagnostics::SOLVER_ITERATIONS, || {
+        stats.solver_iterations as f64
+    });
+    diagnostics.add_measurement(&CollisionDiagnostics::RESOLUTION_TIME, || {
+        stats.resolution_time.as_secs_f64() * 1000.0
+    });
 }
 
 /// Debug rendering system for collision visualization (optional, runs after collision)
 pub fn s_debug_collision(
     player_query: Query<(&Transform, &Physics, &Player)>,
     level: Res<Level>,
+    gravity: Res<Gravity>,
     mut gizmos: Gizmos,
 ) {
     if let Ok((player_transform, player_physics, _player_data)) = player_query.single() {
+        let frame = gravity.frame_at(player_transform.translation.xy());
         let player_pos = player_transform.translation.xy();
         let touch_threshold_sq = (player_physics.radius + TOUCH_THRESHOLD).powi(2);
 
@@ -179,8 +878,8 @@ pub fn s_debug_collision(
         let expanded_player_aabb = player_aabb.expand(player_physics.radius * 0.5);
 
         for polygon in &level.polygons {
-            // Skip polygons far from player
-            if !expanded_player_aabb.overlaps(&polygon.aabb) {
+            // Skip disabled polygons and polygons far from player
+            if !polygon.enabled || !expanded_player_aabb.overlaps(&polygon.aabb) {
                 continue;
             }
 
@@ -189,8 +888,7 @@ pub fn s_debug_collision(
                 let start = polygon.points[i - 1];
                 let end = polygon.points[i];
 
-                let (distance_sq, projection) =
-                    find_projection(start, end, player_pos, player_physics.radius);
+                let (distance_sq, projection) = find_projection(start, end, player_pos);
 
                 let touching_line = distance_sq <= touch_threshold_sq;
 
@@ -198,7 +896,7 @@ pub fn s_debug_collision(
                     let normal_dir = (player_pos - projection).normalize_or_zero();
 
                     // If the line is not above the player
-                    if normal_dir.y >= CEILING_NORMAL_Y_THRESHOLD {
+                    if frame.to_local(normal_dir).y >= CEILING_NORMAL_Y_THRESHOLD {
                         gizmos.line_2d(
                             player_pos,
                             player_pos - normal_dir * DEBUG_NORMAL_LINE_LENGTH,
@@ -211,65 +909,743 @@ pub fn s_debug_collision(
     }
 }
 
-pub fn find_projection(start: Vec2, end: Vec2, point: Vec2, radius: f32) -> (f32, Vec2) {
-    let point_vec = point - start;
+/// Velocity of the point of a moving edge at `point`, from where the same point of the edge was
+/// last tick
+fn edge_velocity(
+    start: Vec2,
+    end: Vec2,
+    prev_start: Vec2,
+    prev_end: Vec2,
+    point: Vec2,
+    dt: f32,
+) -> Vec2 {
+    if dt <= 0.0 {
+        return Vec2::ZERO;
+    }
     let line_vec = end - start;
+    let t =
+        ((point - start).dot(line_vec) / line_vec.length_squared().max(EPSILON)).clamp(0.0, 1.0);
+    (start.lerp(end, t) - prev_start.lerp(prev_end, t)) / dt
+}
 
-    let line_vec_normalized = line_vec.normalize();
+/// Push (world space) out of the candidate edges for a circle moved from `from` to `to`,
+/// counting only edges it was outside of at `from` and polygons it collides with (`layers`),
+/// for the intermediate collision substeps, with the solver passes it took
+fn substep_push_out(
+    level: &Level,
+    edges: &[EdgeId],
+    (from, to): (Vec2, Vec2),
+    radius: f32,
+    layers: CollisionLayers,
+    frame: GravityFrame,
+    solver_iterations: u32,
+) -> (Vec2, u32) {
+    let radius_sq = radius.powi(2);
+    let mut penetrations = Vec::new();
 
-    let dot = point_vec.dot(line_vec_normalized);
+    for &edge_id in edges {
+        let polygon = &level.polygons[edge_id.polygon];
+        if !polygon.collides_with(&layers) {
+            continue;
+        }
 
-    let projection_point = line_vec_normalized * dot + start;
+        let (prev_start, prev_end) = polygon.prev_edge(edge_id.edge + 1);
+        if side_of_line_detection(prev_start, prev_end, from) != polygon.collision_side() {
+            continue;
+        }
 
-    if dot < 0.0 {
-        return (
-            point_vec.length_squared() + radius * DISTANCE_CALCULATION_RADIUS_MULTIPLIER,
-            projection_point,
-        );
+        let (start, end) = level.edge_points(edge_id);
+        let (distance_sq, projection) = find_projection(start, end, to);
+        if distance_sq > radius_sq {
+            continue;
+        }
+
+        let normal_dir = (to - projection).normalize_or_zero();
+        if polygon.one_way && frame.to_local(normal_dir).y < GROUND_NORMAL_Y_THRESHOLD {
+            continue;
+        }
+
+        penetrations.push(normal_dir * (radius - distance_sq.sqrt()));
+    }
+
+    solve_penetrations(&penetrations, solver_iterations)
+}
+
+/// Unit normal of several contacts: near-parallel normals count once (the deepest), and the
+/// rest are weighted by how far the circle overlaps them, so a grazing contact barely tilts the
+/// result
+///
+/// `normals` are (unit normal, overlap in pixels) pairs; overlaps are clamped to a small
+/// minimum so every touching contact counts.
+pub fn combine_contact_normals(normals: &[(Vec2, f32)]) -> Vec2 {
+    let mut surfaces: Vec<(Vec2, f32)> = Vec::new();
+    for &(normal, overlap) in normals {
+        let weight = overlap.max(MIN_NORMAL_WEIGHT);
+        match surfaces
+            .iter_mut()
+            .find(|(surface, _)| surface.dot(normal) > NORMAL_MERGE_DOT)
+        {
+            Some(surface) if weight > surface.1 => *surface = (normal, weight),
+            Some(_) => {}
+            None => surfaces.push((normal, weight)),
+        }
+    }
+
+    surfaces
+        .iter()
+        .map(|&(normal, weight)| normal * weight)
+        .sum::<Vec2>()
+        .normalize_or_zero()
+}
+
+/// Smallest-effort correction that resolves every penetration (each the push out of one
+/// contact: direction times depth), by relaxing the contacts one after another for at most
+/// `max_iterations` passes, with the number of passes it took
+///
+/// Parallel contacts resolve to the deepest one, and contacts at an angle (corners, V-shaped
+/// pits) to the point that clears all of them instead of the deepest push per axis.
+pub fn solve_penetrations(penetrations: &[Vec2], max_iterations: u32) -> (Vec2, u32) {
+    let mut correction = Vec2::ZERO;
+    let mut iterations = 0;
+
+    for _ in 0..max_iterations {
+        iterations += 1;
+        let mut resolved = true;
+        for penetration in penetrations {
+            let Some(normal) = penetration.try_normalize() else {
+                continue;
+            };
+            let remaining = penetration.length() - correction.dot(normal);
+            if remaining > SOLVER_TOLERANCE {
+                correction += normal * remaining;
+                resolved = false;
+            }
+        }
+        if resolved {
+            break;
+        }
+    }
+
+    (correction, iterations)
+}
+
+/// Fraction (0.0..=1.0) of `motion` after which a circle starting at `start` first touches the
+/// segment from `edge_start` to `edge_end`, if it does (circles already touching it are left to
+/// the discrete pass)
+pub fn swept_circle_time_of_impact(
+    start: Vec2,
+    motion: Vec2,
+    radius: f32,
+    edge_start: Vec2,
+    edge_end: Vec2,
+) -> Option<f32> {
+    let edge_vec = edge_end - edge_start;
+    let mut hit: Option<f32> = None;
+
+    // Face: the circle reaches the edge's line `radius` away, within the segment
+    let mut normal = edge_vec.perp().normalize_or_zero();
+    if (start - edge_start).dot(normal) < 0.0 {
+        normal = -normal;
+    }
+    let gap = (start - edge_start).dot(normal) - radius;
+    let approach = motion.dot(normal);
+    if gap >= 0.0 && approach < 0.0 {
+        let t = -gap / approach;
+        let contact = start + motion * t - normal * radius;
+        let along = (contact - edge_start).dot(edge_vec) / edge_vec.length_squared();
+        if t <= 1.0 && (0.0..=1.0).contains(&along) {
+            hit = Some(t);
+        }
+    }
+
+    // Corners: the circle reaches an end point
+    let a = motion.length_squared();
+    for corner in [edge_start, edge_end] {
+        let offset = start - corner;
+        let b = offset.dot(motion);
+        let c = offset.length_squared() - radius.powi(2);
+        let discriminant = b.powi(2) - a * c;
+        if c < 0.0 || b >= 0.0 || discriminant < 0.0 {
+            continue;
+        }
+        let t = (-b - discriminant.sqrt()) / a;
+        if t <= 1.0 && hit.is_none_or(|hit| t < hit) {
+            hit = Some(t);
+        }
+    }
+
+    hit
+}
+
+/// Horizontal shift that moves a circle clear of the nearest corner of an overhead edge
+///
+/// Returns `None` if neither corner can be cleared within `max_distance`.
+pub fn corner_correction_shift(
+    start: Vec2,
+    end: Vec2,
+    point: Vec2,
+    radius: f32,
+    max_distance: f32,
+) -> Option<f32> {
+    [(start, end), (end, start)]
+        .into_iter()
+        .filter_map(|(corner, other)| {
+            let outward = corner.x - other.x;
+            if outward.abs() < EPSILON {
+                return None;
+            }
+
+            // Move the circle's edge just past the corner, away from the rest of the edge
+            let shift = corner.x + outward.signum() * radius - point.x;
+            (shift * outward > 0.0 && shift.abs() <= max_distance).then_some(shift)
+        })
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+}
+
+/// Lift (along `frame.up`) that puts a circle on top of a low step edge
+///
+/// Returns `None` if the step is taller than `max_step_height` or the space above it is blocked
+/// for a circle with `layers`.
+pub fn step_up_lift(
+    (start, end): (Vec2, Vec2),
+    point: Vec2,
+    radius: f32,
+    max_step_height: f32,
+    level: &Level,
+    layers: CollisionLayers,
+    frame: GravityFrame,
+) -> Option<f32> {
+    let step_top = start.dot(frame.up).max(end.dot(frame.up));
+    let step_height = step_top - (point.dot(frame.up) - radius);
+    if step_height <= 0.0 || step_height > max_step_height {
+        return None;
     }
 
-    if dot.powi(2) > (end - start).length_squared() {
-        return (
-            (point - end).length_squared() + radius * DISTANCE_CALCULATION_RADIUS_MULTIPLIER,
-            projection_point,
+    // Upward probe: the circle resting on top of the step must be free
+    let lift = step_height + STEP_UP_CLEARANCE;
+    let probe = point + frame.up * lift;
+
+    (!circle_overlaps_level(level, probe, radius, layers)).then_some(lift)
+}
+
+/// Closest walkable ground below a circle (towards gravity) with `layers`, within
+/// `max_distance`
+///
+/// Returns the downward distance to move the circle so it rests on the ground, the ground
+/// normal at the contact and the ground edge.
+pub fn ground_snap(
+    level: &Level,
+    point: Vec2,
+    radius: f32,
+    max_distance: f32,
+    min_ground_normal_y: f32,
+    layers: CollisionLayers,
+    frame: GravityFrame,
+) -> Option<(f32, Vec2, EdgeId)> {
+    let probe_aabb = Aabb::from_point_radius(point, radius).expand(max_distance);
+    let radius_sq = radius.powi(2);
+    let mut closest: Option<(f32, Vec2, EdgeId)> = None;
+
+    // Measured in gravity's frame, so "below" is negative local Y
+    let point = frame.to_local(point);
+
+    let mut consider = |distance: f32, normal_dir: Vec2, edge_id: EdgeId| {
+        if (0.0..=max_distance).contains(&distance)
+            && normal_dir.y >= min_ground_normal_y
+            && closest.is_none_or(|(closest_distance, ..)| distance < closest_distance)
+        {
+            closest = Some((distance, normal_dir, edge_id));
+        }
+    };
+
+    for edge_id in level.edges_in_aabb(&probe_aabb) {
+        let polygon = &level.polygons[edge_id.polygon];
+        if !polygon.collides_with(&layers) {
+            continue;
+        }
+
+        let (start, end) = level.edge_points(edge_id);
+        let (start, end) = (frame.to_local(start), frame.to_local(end));
+
+        // Edge face: drop the circle until it is `radius` away from the edge's line
+        let edge_vec = end - start;
+        let mut normal_dir = edge_vec.perp().normalize_or_zero();
+        if (point - start).dot(normal_dir) < 0.0 {
+            normal_dir = -normal_dir;
+        }
+        if normal_dir.y > EPSILON {
+            let distance = ((point - start).dot(normal_dir) - radius) / normal_dir.y;
+            let contact = point - Vec2::new(0.0, distance) - normal_dir * radius;
+            let t = (contact - start).dot(edge_vec) / edge_vec.length_squared();
+            if (0.0..=1.0).contains(&t) {
+                consider(distance, normal_dir, edge_id);
+            }
+        }
+
+        // Edge corners: drop the circle until it rests on the corner
+        for corner in [start, end] {
+            let dx = point.x - corner.x;
+            if dx.powi(2) < radius_sq {
+                let distance = point.y - corner.y - (radius_sq - dx.powi(2)).sqrt();
+                let rest_position = point - Vec2::new(0.0, distance);
+                consider(distance, (rest_position - corner) / radius, edge_id);
+            }
+        }
+    }
+
+    closest.map(|(distance, normal_dir, edge_id)| (distance, frame.to_world(normal_dir), edge_id))
+}
+
+/// Moves a circle body by its acceleration and velocity over `dt`, then pushes it out of the
+/// enabled level polygons it collides with (`layers`) and slides it along them
+///
+/// The player-independent core of the controller, for other entities (enemies, projectiles,
+/// props) to call from their own systems: it sets `prev_position`, integrates with
+/// semi-implicit Euler, and leaves `normal` pointing into the touched surfaces with the
+/// velocity into them removed. Unlike `s_collision` it knows nothing of gravity, so there is no
+/// ground/wall classification, step climbing or corner correction, and one-way polygons only
+/// hold bodies moving into them. `solver_iterations` caps the penetration solver's passes, like
+/// `ControllerConfig::solver_iterations` for the player.
+pub fn move_and_slide(
+    transform: &mut Transform,
+    physics: &mut Physics,
+    level: &Level,
+    layers: CollisionLayers,
+    solver_iterations: u32,
+    dt: f32,
+) {
+    physics.prev_position = transform.translation.xy();
+    physics.velocity += physics.acceleration * dt;
+    transform.translation += (physics.velocity * dt).extend(0.0);
+
+    let resolution = resolve_circle(
+        level,
+        physics.prev_position,
+        transform.translation.xy(),
+        physics.radius,
+        physics.velocity,
+        layers,
+        solver_iterations,
+    );
+
+    // Tunnelled inside a polygon: back to where the body was
+    if resolution.tunnelled {
+        transform.translation = physics.prev_position.extend(transform.translation.z);
+    }
+
+    physics.normal = resolution.normal.normalize_or_zero();
+    let normal_speed = physics.velocity.dot(physics.normal);
+    if normal_speed > 0.0 {
+        physics.velocity -= normal_speed * physics.normal;
+    }
+
+    transform.translation += resolution.adjustment.extend(0.0);
+}
+
+/// Circle of a `CompoundCollider`, offset from the entity's translation
+#[derive(Clone, Copy, Debug)]
+pub struct ColliderCircle {
+    pub offset: Vec2,
+    pub radius: f32,
+    /// Sensors report overlaps (`CompoundCollider::sensor_touches`) without pushing the body
+    pub sensor: bool,
+}
+
+/// Several circles moved and resolved as one body, e.g. a body circle plus a small foot sensor
+#[derive(Component, Clone, Debug, Default)]
+pub struct CompoundCollider {
+    pub circles: Vec<ColliderCircle>,
+}
+
+impl CompoundCollider {
+    /// Whether the circle at `index` overlaps level geometry with `layers` with the body at
+    /// `center`
+    pub fn sensor_touches(
+        &self,
+        index: usize,
+        center: Vec2,
+        level: &Level,
+        layers: CollisionLayers,
+    ) -> bool {
+        let circle = &self.circles[index];
+        circle_overlaps_level(level, center + circle.offset, circle.radius, layers)
+    }
+}
+
+/// `move_and_slide` for a `CompoundCollider`: every solid circle is resolved against the level
+/// and their pushes are solved together (`solve_penetrations`) to move the whole body
+///
+/// `physics.radius` is unused; `normal` combines the surfaces touched by every solid circle.
+pub fn move_and_slide_compound(
+    transform: &mut Transform,
+    physics: &mut Physics,
+    collider: &CompoundCollider,
+    level: &Level,
+    layers: CollisionLayers,
+    solver_iterations: u32,
+    dt: f32,
+) {
+    physics.prev_position = transform.translation.xy();
+    physics.velocity += physics.acceleration * dt;
+    transform.translation += (physics.velocity * dt).extend(0.0);
+
+    let position = transform.translation.xy();
+    let mut pushes = Vec::new();
+    let mut normal = Vec2::ZERO;
+    let mut tunnelled = false;
+
+    for circle in collider.circles.iter().filter(|circle| !circle.sensor) {
+        let resolution = resolve_circle(
+            level,
+            physics.prev_position + circle.offset,
+            position + circle.offset,
+            circle.radius,
+            physics.velocity,
+            layers,
+            solver_iterations,
         );
+        pushes.push(resolution.adjustment);
+        normal += resolution.normal;
+        tunnelled |= resolution.tunnelled;
+    }
+
+    if tunnelled {
+        transform.translation = physics.prev_position.extend(transform.translation.z);
     }
 
-    let dist = (point - projection_point).length_squared();
+    physics.normal = normal.normalize_or_zero();
+    let normal_speed = physics.velocity.dot(physics.normal);
+    if normal_speed > 0.0 {
+        physics.velocity -= normal_speed * physics.normal;
+    }
+
+    let (adjustment, _) = solve_penetrations(&pushes, solver_iterations);
+    transform.translation += adjustment.extend(0.0);
+}
 
-    (dist, projection_point)
+/// What resolving one circle against the level found
+struct CircleResolution {
+    /// Push out of the touched edges
+    adjustment: Vec2,
+    /// Sum of the directions into the touched edges (not normalized)
+    normal: Vec2,
+    /// The circle ended up inside a polygon it collides with
+    tunnelled: bool,
 }
 
-pub fn side_of_line_detection(line_start: Vec2, line_end: Vec2, point: Vec2) -> f32 {
-    let determinant = (line_end.x - line_start.x) * (point.y - line_start.y)
-        - (line_end.y - line_start.y) * (point.x - line_start.x);
+/// Resolves a circle moved from `prev_position` to `position` against the enabled level
+/// polygons it collides with (`layers`), counting only edges it was outside of at
+/// `prev_position`
+///
+/// One-way polygons only hold circles whose `velocity` moves into them.
+fn resolve_circle(
+    level: &Level,
+    prev_position: Vec2,
+    position: Vec2,
+    radius: f32,
+    velocity: Vec2,
+    layers: CollisionLayers,
+    solver_iterations: u32,
+) -> CircleResolution {
+    let aabb = Aabb::from_point_radius(position, radius).expand(radius * 0.5);
+    let radius_sq = radius.powi(2);
+    let touch_threshold_sq = (radius + TOUCH_THRESHOLD).powi(2);
+    let mut penetrations = Vec::new();
+    let mut resolution = CircleResolution {
+        adjustment: Vec2::ZERO,
+        normal: Vec2::ZERO,
+        tunnelled: false,
+    };
+
+    let candidates = level.edges_in_aabb(&aabb);
+
+    for polygon_edges in candidates.chunk_by(|a, b| a.polygon == b.polygon) {
+        let polygon = &level.polygons[polygon_edges[0].polygon];
+        if !polygon.collides_with(&layers) {
+            continue;
+        }
+
+        let mut colliding_with_polygon = false;
+
+        for i in polygon_edges.iter().map(|edge_id| edge_id.edge + 1) {
+            let start = polygon.points[i - 1];
+            let end = polygon.points[i];
+
+            // Only edges the body was outside of last frame push it
+            let (prev_start, prev_end) = polygon.prev_edge(i);
+            if side_of_line_detection(prev_start, prev_end, prev_position)
+                != polygon.collision_side()
+            {
+                continue;
+            }
+
+            let (distance_sq, projection) = find_projection(start, end, position);
+            let normal_dir = (position - projection).normalize_or_zero();
+            if polygon.one_way && velocity.dot(normal_dir) > 0.0 {
+                continue;
+            }
 
-    determinant.signum()
+            if distance_sq <= touch_threshold_sq {
+                resolution.normal -= normal_dir;
+            }
+
+            if distance_sq <= radius_sq {
+                colliding_with_polygon = true;
+
+                penetrations.push(normal_dir * (radius - distance_sq.sqrt()));
+            }
+        }
+
+        if colliding_with_polygon && level.inside_solid(polygon_edges[0].polygon, position) {
+            resolution.tunnelled = true;
+        }
+    }
+
+    resolution.adjustment = solve_penetrations(&penetrations, solver_iterations).0;
+    resolution
 }
 
-pub fn line_intersect(
-    line_1_start: Vec2,
-    line_1_end: Vec2,
-    line_2_start: Vec2,
-    line_2_end: Vec2,
-) -> Option<Vec2> {
-    let line_1 = line_1_end - line_1_start;
-    let line_2 = line_2_end - line_2_start;
-    let r_cross_s = cross_product(line_1, line_2);
-    let a_to_c = line_2_start - line_1_start;
-    let t = cross_product(a_to_c, line_2) / r_cross_s;
-    let u = cross_product(a_to_c, line_1) / r_cross_s;
+/// Axis-aligned box collider for bodies other than the player (props, platforms, triggers),
+/// centered on the entity's translation
+#[derive(Component, Clone, Copy, Debug)]
+pub struct BoxCollider {
+    pub half_size: Vec2,
+}
 
-    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
-        Some(Vec2::new(
-            line_1_start.x + t * line_1.x,
-            line_1_start.y + t * line_1.y,
-        ))
-    } else {
-        None
+impl BoxCollider {
+    /// The box around a center
+    pub fn aabb(&self, center: Vec2) -> Aabb {
+        Aabb::from_corners(center - self.half_size, center + self.half_size)
     }
 }
 
-pub fn cross_product(a: Vec2, b: Vec2) -> f32 {
-    a.x * b.y - a.y * b.x
+/// `move_and_slide` for box bodies: moves along X, then Y, each axis stopping at the first
+/// enabled polygon with interacting `layers` in the way (one-way platforms only stop boxes
+/// falling onto them)
+///
+/// `physics.radius` is unused; `normal` points into the surfaces hit, with the velocity into
+/// them removed.
+pub fn move_and_slide_box(
+    transform: &mut Transform,
+    physics: &mut Physics,
+    collider: &BoxCollider,
+    level: &Level,
+    layers: CollisionLayers,
+    dt: f32,
+) {
+    physics.prev_position = transform.translation.xy();
+    physics.velocity += physics.acceleration * dt;
+
+    let mut center = physics.prev_position;
+    let mut normal = Vec2::ZERO;
+    for axis in [Vec2::X, Vec2::Y] {
+        let step = axis * physics.velocity.dot(axis) * dt;
+        let aabb = collider.aabb(center).expand(-BOX_SKIN);
+        let fraction = box_sweep_fraction(level, None, &aabb, step, step.y < 0.0, layers);
+        if fraction < 1.0 {
+            normal += step.normalize_or_zero();
+            physics.velocity -= axis * physics.velocity.dot(axis);
+        }
+        center += step * fraction;
+    }
+
+    physics.normal = normal.normalize_or_zero();
+    transform.translation = center.extend(transform.translation.z);
+}
+
+/// Fraction of `step` a box with `layers` can move before overlapping an enabled polygon other
+/// than `skip`
+///
+/// One-way platforms only stop boxes `falling` onto them from above.
+pub fn box_sweep_fraction(
+    level: &Level,
+    skip: Option<usize>,
+    aabb: &Aabb,
+    step: Vec2,
+    falling: bool,
+    layers: CollisionLayers,
+) -> f32 {
+    let blocked = |t: f32| {
+        let moved = Aabb {
+            min: aabb.min + step * t,
+            max: aabb.max + step * t,
+        };
+        level.polygons.iter().enumerate().any(|(index, polygon)| {
+            Some(index) != skip
+                && polygon.collides_with(&layers)
+                && (!polygon.one_way || (falling && !aabb_overlaps_polygon(polygon, aabb)))
+                && aabb_overlaps_polygon(polygon, &moved)
+        })
+    };
+
+    if step == Vec2::ZERO || !blocked(1.0) {
+        return 1.0;
+    }
+
+    let (mut free, mut hit) = (0.0, 1.0);
+    for _ in 0..SWEEP_ITERATIONS {
+        let mid = (free + hit) / 2.0;
+        if blocked(mid) {
+            hit = mid;
+        } else {
+            free = mid;
+        }
+    }
+    free
+}
+
+/// Where a ray hit the level (`Level::raycast`)
+#[derive(Clone, Copy, Debug)]
+pub struct RayHit {
+    /// Hit point (world space)
+    pub point: Vec2,
+    /// Unit normal of the edge, facing the ray's origin
+    pub normal: Vec2,
+    /// The polygon edge, `None` for arc edges
+    pub edge: Option<EdgeId>,
+    /// Distance (pixels) from the origin to `point`
+    pub t: f32,
+}
+
+/// Where a swept circle first touched the level (`Level::shapecast`)
+#[derive(Clone, Copy, Debug)]
+pub struct ShapeHit {
+    /// Center of the circle at the time of impact (world space)
+    pub position: Vec2,
+    /// Contact point on the edge (world space)
+    pub point: Vec2,
+    /// Unit direction from the contact point toward the circle's center
+    pub normal: Vec2,
+    /// The polygon edge, `None` for arc edges
+    pub edge: Option<EdgeId>,
+    /// Distance (pixels) the circle travelled before touching
+    pub t: f32,
+}
+
+/// First hit of a ray against the enabled level polygons it collides with (`layers`), as (hit
+/// point, distance)
+pub fn raycast_level(
+    level: &Level,
+    origin: Vec2,
+    direction: Vec2,
+    max_distance: f32,
+    layers: CollisionLayers,
+) -> Option<(Vec2, f32)> {
+    level
+        .raycast(origin, direction, max_distance, layers)
+        .map(|hit| (hit.point, hit.t))
+}
+
+/// Whether a circle with `layers` overlaps any edge of an enabled level polygon or an arc edge
+/// it collides with
+pub fn circle_overlaps_level(
+    level: &Level,
+    center: Vec2,
+    radius: f32,
+    layers: CollisionLayers,
+) -> bool {
+    let circle_aabb = Aabb::from_point_radius(center, radius);
+    let radius_sq = radius.powi(2);
+
+    level
+        .edges_in_aabb(&circle_aabb)
+        .into_iter()
+        .filter(|edge_id| level.polygons[edge_id.polygon].collides_with(&layers))
+        .any(|edge_id| {
+            let (start, end) = level.edge_points(edge_id);
+            closest_point_on_segment(start, end, center).distance_squared(center) < radius_sq
+        })
+        || level
+            .arcs
+            .iter()
+            .filter(|arc| arc.layers.interacts_with(&layers))
+            .any(|arc| arc.closest_point(center).distance_squared(center) < radius_sq)
+}
+
+/// Whether a circle overlaps a polygon, touching an edge or lying inside it
+pub fn circle_overlaps_polygon(polygon: &Polygon, center: Vec2, radius: f32) -> bool {
+    if !Aabb::from_point_radius(center, radius).overlaps(&polygon.aabb) {
+        return false;
+    }
+
+    let radius_sq = radius.powi(2);
+    point_in_polygon(&polygon.points, center)
+        || polygon.points.windows(2).any(|edge| {
+            closest_point_on_segment(edge[0], edge[1], center).distance_squared(center) < radius_sq
+        })
+}
+
+/// Whether an axis-aligned box overlaps the inside or outline of a polygon
+pub fn aabb_overlaps_polygon(polygon: &Polygon, aabb: &Aabb) -> bool {
+    if !aabb.overlaps(&polygon.aabb) {
+        return false;
+    }
+
+    let corners = [
+        aabb.min,
+        Vec2::new(aabb.max.x, aabb.min.y),
+        aabb.max,
+        Vec2::new(aabb.min.x, aabb.max.y),
+    ];
+    corners
+        .iter()
+        .any(|&corner| point_in_polygon(&polygon.points, corner))
+        || polygon.points.iter().any(|&point| aabb.contains(point))
+        || polygon.points.windows(2).any(|edge| {
+            (0..4).any(|i| {
+                line_intersect(edge[0], edge[1], corners[i], corners[(i + 1) % 4]).is_some()
+            })
+        })
+}
+
+/// Whether a circle overlaps an axis-aligned box
+pub fn aabb_overlaps_circle(aabb: &Aabb, center: Vec2, radius: f32) -> bool {
+    center.clamp(aabb.min, aabb.max).distance_squared(center) < radius.powi(2)
+}
+
+/// Smallest move that takes a circle out of an axis-aligned box, if they overlap
+pub fn circle_aabb_push(aabb: &Aabb, center: Vec2, radius: f32) -> Option<Vec2> {
+    let closest = center.clamp(aabb.min, aabb.max);
+    if closest != center {
+        let offset = center - closest;
+        let distance = offset.length();
+        return (distance < radius).then(|| offset / distance * (radius - distance));
+    }
+
+    // Center inside the box: out through the nearest side
+    let (to_min, to_max) = (center - aabb.min, aabb.max - center);
+    [
+        (to_min.x, Vec2::NEG_X),
+        (to_max.x, Vec2::X),
+        (to_min.y, Vec2::NEG_Y),
+        (to_max.y, Vec2::Y),
+    ]
+    .into_iter()
+    .min_by(|a, b| a.0.total_cmp(&b.0))
+    .map(|(depth, direction)| direction * (depth + radius))
+}
+
+/// Whether an axis-aligned box with `layers` overlaps an enabled level polygon it collides with
+pub fn aabb_overlaps_level(level: &Level, aabb: &Aabb, layers: CollisionLayers) -> bool {
+    level
+        .polygons
+        .iter()
+        .any(|polygon| polygon.collides_with(&layers) && aabb_overlaps_polygon(polygon, aabb))
+}
+
+/// Whether a point lies inside a closed polygon outline (crossing number test)
+pub fn point_in_polygon(points: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+
+    for edge in points.windows(2) {
+        let (start, end) = (edge[0], edge[1]);
+        if (start.y > point.y) != (end.y > point.y) {
+            let crossing_x = start.x + (point.y - start.y) / (end.y - start.y) * (end.x - start.x);
+            if point.x < crossing_x {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
 }
@@ -0,0 +1,695 @@
+use std::fmt;
+
+use bevy::prelude::Resource;
+
+use crate::{
+    CEILING_NORMAL_Y_THRESHOLD, CORNER_CORRECTION_DISTANCE, GRAVITY_STRENGTH, GRID_SIZE,
+    GROUND_SNAP_DISTANCE, JUMP_RELEASE_VELOCITY_DIVISOR, JUMP_VELOCITY, LADDER_CLIMB_SPEED,
+    MAX_FALL_SPEED, MAX_SLOPE_ANGLE, PLAYER_ACCELERATION_SCALERS, PLAYER_MAX_SPEED,
+    PLAYER_TURN_AROUND_SCALER, SOLVER_ITERATIONS, STEP_HEIGHT_RATIO,
+    WALL_JUMP_ACCELERATION_REDUCTION, WALL_REGRAB_TIME,
+};
+
+/// Tunable character controller settings
+///
+/// Defaults mirror the module-level constants in `lib.rs`, so inserting
+/// `ControllerConfig::default()` reproduces the original movement feel.
+#[derive(Resource, Clone)]
+pub struct ControllerConfig {
+    /// Maximum horizontal speed when not sprinting (pixels/second)
+    pub max_speed: f32,
+    /// Acceleration rates when not sprinting
+    pub acceleration: AccelerationCurves,
+    /// Target horizontal speed while airborne, for every movement tier (`None` keeps the tier's speed)
+    ///
+    /// Air acceleration and deceleration are set per tier in `AccelerationCurves::air`.
+    pub max_air_speed: Option<f32>,
+    /// Extra jumps available while airborne, refilled on the ground or a wall
+    pub air_jumps: u32,
+    /// Jump charged while jump is held on the ground and done on release, instead of the
+    /// instant (buffered) jump (`None` for instant jumps)
+    pub charge_jump: Option<ChargeJumpConfig>,
+    /// Jetpack used in the air instead of the air jumps (`None` for normal air jumps)
+    pub jetpack: Option<JetpackConfig>,
+    /// Terminal fall speed (pixels/second)
+    pub max_fall_speed: f32,
+    /// Gravity multipliers for the parts of a jump arc
+    pub gravity_multipliers: GravityMultipliers,
+    /// What releasing jump early does to a jump
+    pub jump_cut: JumpCut,
+    /// Optional cap on horizontal speed (pixels/second)
+    pub max_horizontal_speed: Option<f32>,
+    /// Maximum horizontal nudge (pixels) around ceiling corners when rising (0.0 disables)
+    pub corner_correction_distance: f32,
+    /// Steepest slope (degrees from flat) the player can stand on; steeper non-wall slopes slide
+    pub max_slope_angle: f32,
+    /// Surfaces whose normal (in gravity's frame) has a Y component below this are ceilings
+    /// (between -1.0 and 0.0)
+    pub ceiling_normal_y: f32,
+    /// Maximum drop (pixels) below the player that still snaps them to the ground (0.0 disables)
+    pub ground_snap_distance: f32,
+    /// Tallest step climbed automatically while grounded, relative to the player radius (0.0 disables)
+    pub step_height_ratio: f32,
+    /// Foot sensor deciding ground contact; `None` counts ground within a fixed touch distance
+    /// of the body circle
+    pub foot_sensor: Option<FootSensorConfig>,
+    /// Most relaxation passes the collision solver makes to resolve all penetrating contacts
+    /// together (at least 1)
+    pub solver_iterations: u32,
+    /// Climbing speed on ladders (pixels/second)
+    pub ladder_climb_speed: f32,
+    /// Fraction of the steering input kept while stunned by knockback (0.0 removes control)
+    pub stun_control: f32,
+    /// Swimming settings
+    pub swim: SwimConfig,
+    /// Analog walk tier settings
+    pub walk: WalkConfig,
+    /// Sprint modifier settings
+    pub sprint: SprintConfig,
+    /// Apex hang-time settings
+    pub apex: ApexConfig,
+    /// Glide ability settings
+    pub glide: GlideConfig,
+    /// Ground pound ability settings
+    pub ground_pound: GroundPoundConfig,
+    /// Wall jump chaining rules
+    pub wall_jump: WallJumpConfig,
+    /// Wall run settings
+    pub wall_run: WallRunConfig,
+}
+
+impl Default for ControllerConfig {
+    fn default() -> Self {
+        Self {
+            max_speed: PLAYER_MAX_SPEED,
+            acceleration: AccelerationCurves::default(),
+            max_air_speed: None,
+            air_jumps: 0,
+            charge_jump: None,
+            jetpack: None,
+            max_fall_speed: MAX_FALL_SPEED,
+            gravity_multipliers: GravityMultipliers::default(),
+            jump_cut: JumpCut::Divide(JUMP_RELEASE_VELOCITY_DIVISOR),
+            max_horizontal_speed: None,
+            corner_correction_distance: CORNER_CORRECTION_DISTANCE,
+            max_slope_angle: MAX_SLOPE_ANGLE,
+            ceiling_normal_y: CEILING_NORMAL_Y_THRESHOLD,
+            ground_snap_distance: GROUND_SNAP_DISTANCE,
+            step_height_ratio: STEP_HEIGHT_RATIO,
+            foot_sensor: None,
+            solver_iterations: SOLVER_ITERATIONS,
+            ladder_climb_speed: LADDER_CLIMB_SPEED,
+            stun_control: 0.0,
+            swim: SwimConfig::default(),
+            walk: WalkConfig::default(),
+            sprint: SprintConfig::default(),
+            apex: ApexConfig::default(),
+            glide: GlideConfig::default(),
+            ground_pound: GroundPoundConfig::default(),
+            wall_jump: WallJumpConfig::default(),
+            wall_run: WallRunConfig::default(),
+        }
+    }
+}
+
+impl ControllerConfig {
+    /// Checks for setting combinations that make the controller misbehave
+    ///
+    /// `radius` is the player's collision radius and `gravity_multiplier` the strongest gravity
+    /// multiplier the player can be under (1.0 outside gravity zones).
+    pub fn validate(&self, radius: f32, gravity_multiplier: f32) -> Vec<ConfigWarning> {
+        let mut warnings = Vec::new();
+
+        // Peak of a full jump: v² / 2g
+        let rise_gravity = GRAVITY_STRENGTH * gravity_multiplier * self.gravity_multipliers.rise;
+        let jump_height = JUMP_VELOCITY.powi(2) / (2.0 * rise_gravity);
+        if jump_height < GRID_SIZE {
+            warnings.push(ConfigWarning::JumpBelowTile {
+                jump_height,
+                gravity_multiplier,
+            });
+        }
+
+        if self.step_height_ratio > 2.0 {
+            warnings.push(ConfigWarning::StepTallerThanCollider {
+                step_height: self.step_height_ratio * radius,
+                collider_height: 2.0 * radius,
+            });
+        }
+
+        if self.ground_snap_distance > radius {
+            warnings.push(ConfigWarning::GroundSnapBeyondRadius {
+                distance: self.ground_snap_distance,
+                radius,
+            });
+        }
+
+        if self.corner_correction_distance > radius {
+            warnings.push(ConfigWarning::CornerCorrectionBeyondRadius {
+                distance: self.corner_correction_distance,
+                radius,
+            });
+        }
+
+        if !(0.0..90.0).contains(&self.max_slope_angle) {
+            warnings.push(ConfigWarning::SlopeAngleOutOfRange(self.max_slope_angle));
+        }
+
+        if self.sprint.max_speed <= self.max_speed {
+            warnings.push(ConfigWarning::SprintNotFaster {
+                sprint_speed: self.sprint.max_speed,
+                run_speed: self.max_speed,
+            });
+        }
+
+        if self.walk.threshold > 0.0 && self.walk.max_speed >= self.max_speed {
+            warnings.push(ConfigWarning::WalkNotSlower {
+                walk_speed: self.walk.max_speed,
+                run_speed: self.max_speed,
+            });
+        }
+
+        if let Some(max_horizontal_speed) = self.max_horizontal_speed {
+            if max_horizontal_speed < self.max_speed {
+                warnings.push(ConfigWarning::HorizontalCapBelowRun {
+                    cap: max_horizontal_speed,
+                    run_speed: self.max_speed,
+                });
+            }
+        }
+
+        if self.glide.enabled && self.glide.max_fall_speed >= self.max_fall_speed {
+            warnings.push(ConfigWarning::GlideNotSlower {
+                glide_fall_speed: self.glide.max_fall_speed,
+                fall_speed: self.max_fall_speed,
+            });
+        }
+
+        if let Some(jetpack) = &self.jetpack {
+            let gravity = GRAVITY_STRENGTH * gravity_multiplier;
+            if jetpack.thrust <= gravity {
+                warnings.push(ConfigWarning::JetpackCantLift {
+                    thrust: jetpack.thrust,
+                    gravity,
+                });
+            }
+        }
+
+        warnings
+    }
+}
+
+/// Nonsensical combination of controller settings found by `ControllerConfig::validate`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConfigWarning {
+    /// A full jump can't clear one tile
+    JumpBelowTile {
+        jump_height: f32,
+        gravity_multiplier: f32,
+    },
+    /// Steps taller than the player are climbed automatically
+    StepTallerThanCollider {
+        step_height: f32,
+        collider_height: f32,
+    },
+    /// Ground snapping pulls the player down further than their radius
+    GroundSnapBeyondRadius { distance: f32, radius: f32 },
+    /// Corner correction nudges the player further than their radius
+    CornerCorrectionBeyondRadius { distance: f32, radius: f32 },
+    /// Walkable slope angle outside 0..90 degrees
+    SlopeAngleOutOfRange(f32),
+    /// Sprinting doesn't raise the top speed
+    SprintNotFaster { sprint_speed: f32, run_speed: f32 },
+    /// The walk tier isn't slower than running
+    WalkNotSlower { walk_speed: f32, run_speed: f32 },
+    /// `max_horizontal_speed` caps the player below their running speed
+    HorizontalCapBelowRun { cap: f32, run_speed: f32 },
+    /// Gliding doesn't slow the fall
+    GlideNotSlower {
+        glide_fall_speed: f32,
+        fall_speed: f32,
+    },
+    /// Jetpack thrust doesn't beat gravity
+    JetpackCantLift { thrust: f32, gravity: f32 },
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::JumpBelowTile {
+                jump_height,
+                gravity_multiplier,
+            } => write!(
+                f,
+                "jumps peak at {jump_height:.0}px under {gravity_multiplier}x gravity, below one \
+                 tile ({GRID_SIZE}px): raise JUMP_VELOCITY or weaken the gravity zone"
+            ),
+            Self::StepTallerThanCollider {
+                step_height,
+                collider_height,
+            } => write!(
+                f,
+                "step height {step_height:.0}px exceeds the player's height \
+                 ({collider_height:.0}px): lower step_height_ratio to 2.0 or less"
+            ),
+            Self::GroundSnapBeyondRadius { distance, radius } => write!(
+                f,
+                "ground_snap_distance {distance}px exceeds the player radius ({radius}px), so \
+                 the player gets pulled down ledges: lower it to {radius} or less"
+            ),
+            Self::CornerCorrectionBeyondRadius { distance, radius } => write!(
+                f,
+                "corner_correction_distance {distance}px exceeds the player radius \
+                 ({radius}px), so jumps slide past whole ceilings: lower it to {radius} or less"
+            ),
+            Self::SlopeAngleOutOfRange(angle) => write!(
+                f,
+                "max_slope_angle {angle} is outside 0..90 degrees: slopes and walls would be \
+                 classified wrongly"
+            ),
+            Self::SprintNotFaster {
+                sprint_speed,
+                run_speed,
+            } => write!(
+                f,
+                "sprint.max_speed ({sprint_speed}) is not above max_speed ({run_speed}): \
+                 sprinting has no effect"
+            ),
+            Self::WalkNotSlower {
+                walk_speed,
+                run_speed,
+            } => write!(
+                f,
+                "walk.max_speed ({walk_speed}) is not below max_speed ({run_speed}): lower it \
+                 or set walk.threshold to 0.0 to disable walking"
+            ),
+            Self::HorizontalCapBelowRun { cap, run_speed } => write!(
+                f,
+                "max_horizontal_speed ({cap}) is below max_speed ({run_speed}): the player \
+                 never reaches running speed"
+            ),
+            Self::GlideNotSlower {
+                glide_fall_speed,
+                fall_speed,
+            } => write!(
+                f,
+                "glide.max_fall_speed ({glide_fall_speed}) is not below max_fall_speed \
+                 ({fall_speed}): gliding doesn't slow the fall"
+            ),
+            Self::JetpackCantLift { thrust, gravity } => write!(
+                f,
+                "jetpack.thrust ({thrust}) is not above gravity ({gravity}): the jetpack only \
+                 slows the fall"
+            ),
+        }
+    }
+}
+
+/// Rates (1/second) at which velocity approaches the target velocity
+#[derive(Clone, Copy)]
+pub struct AccelerationRates {
+    /// Input held along (or without) the current velocity
+    pub accelerate: f32,
+    /// No input
+    pub decelerate: f32,
+    /// Input held against the current velocity
+    pub turn_around: f32,
+}
+
+impl AccelerationRates {
+    /// Rate for the current input situation
+    pub fn rate(&self, no_input: bool, turning_around: bool) -> f32 {
+        if no_input {
+            self.decelerate
+        } else if turning_around {
+            self.turn_around
+        } else {
+            self.accelerate
+        }
+    }
+}
+
+impl Default for AccelerationRates {
+    fn default() -> Self {
+        Self {
+            accelerate: PLAYER_ACCELERATION_SCALERS.0,
+            decelerate: PLAYER_ACCELERATION_SCALERS.1,
+            turn_around: PLAYER_TURN_AROUND_SCALER,
+        }
+    }
+}
+
+/// Separate acceleration rates on the ground and in the air
+#[derive(Clone, Copy, Default)]
+pub struct AccelerationCurves {
+    pub ground: AccelerationRates,
+    pub air: AccelerationRates,
+}
+
+impl AccelerationCurves {
+    /// Same rates on the ground and in the air
+    pub fn uniform(rates: AccelerationRates) -> Self {
+        Self {
+            ground: rates,
+            air: rates,
+        }
+    }
+}
+
+/// Analog walk tier: small stick deflections move at walk speed for precise positioning
+#[derive(Clone)]
+pub struct WalkConfig {
+    /// Input deflection (0.0..1.0) below which the player walks (0.0 disables walking)
+    pub threshold: f32,
+    /// Maximum speed while walking (pixels/second)
+    pub max_speed: f32,
+    /// Acceleration rates while walking
+    pub acceleration: AccelerationCurves,
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            max_speed: 120.0,
+            acceleration: AccelerationCurves::uniform(AccelerationRates {
+                accelerate: 8.0,
+                decelerate: 24.0,
+                turn_around: 12.0,
+            }),
+        }
+    }
+}
+
+/// Sprint modifier: held action that raises the top speed and changes acceleration
+#[derive(Clone)]
+pub struct SprintConfig {
+    /// Maximum speed while sprinting (pixels/second)
+    pub max_speed: f32,
+    /// Acceleration rates while sprinting
+    pub acceleration: AccelerationCurves,
+    /// Stamina cost of sprinting (`None` for unlimited sprinting)
+    pub stamina: Option<StaminaConfig>,
+    /// How sprinting behaves while airborne
+    pub air_behavior: SprintAirBehavior,
+}
+
+impl Default for SprintConfig {
+    fn default() -> Self {
+        Self {
+            max_speed: 450.0,
+            acceleration: AccelerationCurves::uniform(AccelerationRates {
+                accelerate: 10.0,
+                decelerate: 24.0,
+                turn_around: 10.0,
+            }),
+            stamina: None,
+            air_behavior: SprintAirBehavior::KeepFromGround,
+        }
+    }
+}
+
+/// Stamina pool drained while sprinting
+#[derive(Clone)]
+pub struct StaminaConfig {
+    /// Maximum stamina (seconds of sprinting at a drain rate of 1.0)
+    pub max: f32,
+    /// Stamina drained per second of sprinting
+    pub drain_rate: f32,
+    /// Stamina recovered per second while not sprinting
+    pub regen_rate: f32,
+}
+
+impl Default for StaminaConfig {
+    fn default() -> Self {
+        Self {
+            max: 2.0,
+            drain_rate: 1.0,
+            regen_rate: 0.5,
+        }
+    }
+}
+
+/// Sprint behavior while the player is in the air
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SprintAirBehavior {
+    /// Sprinting can be started and stopped freely in the air
+    Allow,
+    /// Sprint state is locked to whatever it was when the player left the ground
+    KeepFromGround,
+    /// Sprinting only applies on the ground
+    GroundOnly,
+}
+
+/// Apex modifier: near the top of a jump, gravity is reduced and air control boosted
+#[derive(Clone)]
+pub struct ApexConfig {
+    /// Vertical speed (pixels/second) below which the player counts as being at the apex
+    pub velocity_threshold: f32,
+    /// Gravity multiplier while at the apex
+    pub gravity_multiplier: f32,
+    /// Horizontal acceleration multiplier while at the apex
+    pub acceleration_multiplier: f32,
+    /// Maximum time (seconds) the apex modifier can apply per airtime
+    pub max_duration: f32,
+}
+
+impl Default for ApexConfig {
+    fn default() -> Self {
+        Self {
+            velocity_threshold: 60.0,
+            gravity_multiplier: 0.5,
+            acceleration_multiplier: 1.5,
+            max_duration: 0.1,
+        }
+    }
+}
+
+/// Gravity multipliers while airborne (or sliding down steep slopes); glide and apex hang time
+/// override them
+#[derive(Clone)]
+pub struct GravityMultipliers {
+    /// While rising
+    pub rise: f32,
+    /// While falling
+    pub fall: f32,
+    /// While still rising after releasing jump early
+    pub jump_cut: f32,
+}
+
+impl Default for GravityMultipliers {
+    fn default() -> Self {
+        Self {
+            rise: 1.0,
+            fall: 1.0,
+            jump_cut: 1.0,
+        }
+    }
+}
+
+/// Jump cut applied once when jump is released while a jump the player made is still rising
+/// (not to launches from jump pads, boosts or knockback)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum JumpCut {
+    /// Divides the upward velocity
+    Divide(f32),
+    /// Caps the upward velocity (pixels/second)
+    Velocity(f32),
+    /// Keeps the velocity; only `GravityMultipliers::jump_cut` shortens the rise
+    Gravity,
+}
+
+/// Glide ability: holding jump while falling
+#[derive(Clone)]
+pub struct GlideConfig {
+    /// Whether the glide ability is available
+    pub enabled: bool,
+    /// Gravity multiplier while gliding
+    pub gravity_multiplier: f32,
+    /// Terminal fall speed while gliding (pixels/second)
+    pub max_fall_speed: f32,
+    /// Multiplier on the target horizontal speed while gliding
+    pub horizontal_speed_multiplier: f32,
+}
+
+impl Default for GlideConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gravity_multiplier: 0.3,
+            max_fall_speed: 120.0,
+            horizontal_speed_multiplier: 1.2,
+        }
+    }
+}
+
+/// Small circle below the player's center (along gravity) that touches the ground for them
+#[derive(Clone)]
+pub struct FootSensorConfig {
+    /// Distance of the sensor's center below the player's, relative to the player radius
+    pub offset_ratio: f32,
+    /// Sensor radius relative to the player radius
+    pub radius_ratio: f32,
+}
+
+impl Default for FootSensorConfig {
+    fn default() -> Self {
+        Self {
+            offset_ratio: 0.75,
+            radius_ratio: 0.3,
+        }
+    }
+}
+
+/// Charge jump: the longer jump is held on the ground, the higher the jump on release
+#[derive(Clone)]
+pub struct ChargeJumpConfig {
+    /// Jump velocity (pixels/second) of an uncharged jump
+    pub min_velocity: f32,
+    /// Jump velocity (pixels/second) of a fully charged jump
+    pub max_velocity: f32,
+    /// Time (seconds) to charge fully
+    pub charge_time: f32,
+}
+
+impl Default for ChargeJumpConfig {
+    fn default() -> Self {
+        Self {
+            min_velocity: JUMP_VELOCITY * 0.6,
+            max_velocity: JUMP_VELOCITY * 1.3,
+            charge_time: 0.6,
+        }
+    }
+}
+
+/// Jetpack: pressing jump in the air and holding it thrusts upward while fuel lasts
+#[derive(Clone)]
+pub struct JetpackConfig {
+    /// Upward acceleration (pixels/second²) while thrusting, working against gravity
+    pub thrust: f32,
+    /// Rising speed (pixels/second) thrust doesn't push past
+    pub max_rise_speed: f32,
+    /// Maximum fuel (seconds of thrust)
+    pub fuel: f32,
+    /// Fuel recovered per second on the ground
+    pub recharge_rate: f32,
+}
+
+impl Default for JetpackConfig {
+    fn default() -> Self {
+        Self {
+            thrust: 3000.0,
+            max_rise_speed: 300.0,
+            fuel: 1.0,
+            recharge_rate: 2.0,
+        }
+    }
+}
+
+/// Ground pound ability: down + jump in the air slams the player straight down
+#[derive(Clone)]
+pub struct GroundPoundConfig {
+    /// Whether the ground pound is available
+    pub enabled: bool,
+    /// Fall speed (pixels/second) of the slam, replacing gravity
+    pub speed: f32,
+    /// Time (seconds) after landing during which the player can't move or jump
+    pub recovery: f32,
+}
+
+impl Default for GroundPoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            speed: 1000.0,
+            recovery: 0.25,
+        }
+    }
+}
+
+/// Rules for chaining wall jumps
+#[derive(Clone)]
+pub struct WallJumpConfig {
+    /// Time (seconds) after a wall jump before the same wall can be grabbed again (0.0 allows
+    /// re-grabbing at once)
+    pub regrab_time: f32,
+    /// Most consecutive wall jumps off the same wall before touching the ground (`None` for no
+    /// limit)
+    pub max_same_wall_jumps: Option<u32>,
+    /// Multiplier on the acceleration after a wall jump until the next ground or wall contact
+    /// (`None` leaves it unchanged)
+    pub acceleration_reduction: Option<f32>,
+}
+
+impl Default for WallJumpConfig {
+    fn default() -> Self {
+        Self {
+            regrab_time: WALL_REGRAB_TIME,
+            max_same_wall_jumps: None,
+            acceleration_reduction: Some(WALL_JUMP_ACCELERATION_REDUCTION),
+        }
+    }
+}
+
+/// Wall run: hitting a wall fast runs up it for a short time before the normal wall handling
+#[derive(Clone)]
+pub struct WallRunConfig {
+    /// Whether wall runs happen
+    pub enabled: bool,
+    /// Slowest speed (pixels/second) into the wall that starts a wall run
+    pub min_speed: f32,
+    /// Fraction of the speed into the wall turned into upward speed
+    pub speed_conversion: f32,
+    /// Longest wall run (seconds)
+    pub duration: f32,
+    /// Gravity multiplier while wall running
+    pub gravity_multiplier: f32,
+}
+
+impl Default for WallRunConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_speed: 280.0,
+            speed_conversion: 0.8,
+            duration: 0.4,
+            gravity_multiplier: 0.5,
+        }
+    }
+}
+
+/// Swimming in water volumes
+#[derive(Clone)]
+pub struct SwimConfig {
+    /// Submerged fraction (0.0..1.0) of the player at which swimming starts
+    pub enter_fraction: f32,
+    /// Submerged fraction at or below which jumping leaves the water instead of stroking
+    pub surface_fraction: f32,
+    /// Gravity multiplier while swimming
+    pub gravity_multiplier: f32,
+    /// Upward acceleration when fully submerged (pixels/second²), scaled by the submerged fraction
+    pub buoyancy: f32,
+    /// Velocity lost per second to drag (1/second)
+    pub drag: f32,
+    /// Acceleration from input (pixels/second²)
+    pub acceleration: f32,
+    /// Maximum horizontal and sinking speed (pixels/second)
+    pub max_speed: f32,
+    /// Upward velocity of a swim stroke (pixels/second)
+    pub stroke_velocity: f32,
+}
+
+impl Default for SwimConfig {
+    fn default() -> Self {
+        Self {
+            enter_fraction: 0.5,
+            surface_fraction: 0.75,
+            gravity_multiplier: 0.3,
+            buoyancy: 1200.0,
+            drag: 3.0,
+            acceleration: 900.0,
+            max_speed: 200.0,
+            stroke_velocity: 260.0,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::{input::ButtonInput, prelude::*};
+
+use crate::{
+    config::{ConfigWarning, ControllerConfig},
+    gravity::Gravity,
+    schedule::ControllerPhase,
+    PLAYER_RADIUS,
+};
+
+// Debug panel constants
+const WARNINGS_PANEL_MARGIN: f32 = 24.0;
+const WARNINGS_PANEL_PADDING: f32 = 12.0;
+
+/// Validates the controller config whenever it (or the level's gravity) changes, logging new
+/// warnings; F5 toggles a debug panel listing the current ones
+pub struct ConfigWarningsPlugin;
+
+impl Plugin for ConfigWarningsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConfigWarnings>()
+            .init_resource::<ConfigWarningsPanel>()
+            .add_systems(
+                Update,
+                s_validate_config
+                    .before(ControllerPhase::Input)
+                    .run_if(resource_changed::<ControllerConfig>.or(resource_changed::<Gravity>)),
+            )
+            .add_systems(Update, s_config_warnings_panel.after(s_validate_config));
+    }
+}
+
+/// Warnings for the current controller config
+#[derive(Resource, Default)]
+pub struct ConfigWarnings(pub Vec<ConfigWarning>);
+
+/// Root UI node of the warnings panel, while shown
+#[derive(Resource, Default)]
+pub struct ConfigWarningsPanel(Option<Entity>);
+
+/// Marker for the warnings panel text
+#[derive(Component)]
+pub struct ConfigWarningsText;
+
+/// Re-validates the config, logging warnings that weren't already reported
+///
+/// Gravity zones are taken into account through the strongest zone of the level.
+pub fn s_validate_config(
+    config: Res<ControllerConfig>,
+    gravity: Res<Gravity>,
+    mut warnings: ResMut<ConfigWarnings>,
+) {
+    let gravity_multiplier = gravity
+        .zones
+        .iter()
+        .map(|zone| zone.strength)
+        .fold(1.0, f32::max);
+    let new_warnings = config.validate(PLAYER_RADIUS, gravity_multiplier);
+
+    for warning in &new_warnings {
+        if !warnings.0.contains(warning) {
+            warn!("controller config: {warning}");
+        }
+    }
+
+    if warnings.0 != new_warnings {
+        warnings.0 = new_warnings;
+    }
+}
+
+/// F5 toggles the config warnings panel and keeps its text up to date
+pub fn s_config_warnings_panel(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    warnings: Res<ConfigWarnings>,
+    mut panel: ResMut<ConfigWarningsPanel>,
+    mut text_query: Query<&mut Text, With<ConfigWarningsText>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F5) {
+        if let Some(panel_entity) = panel.0.take() {
+            commands.entity(panel_entity).despawn();
+        } else {
+            let panel_entity = commands
+                .spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(WARNINGS_PANEL_MARGIN),
+                        bottom: Val::Px(WARNINGS_PANEL_MARGIN),
+                        padding: UiRect::all(Val::Px(WARNINGS_PANEL_PADDING)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(config_warnings_text(&warnings)),
+                        ConfigWarningsText,
+                    ));
+                })
+                .id();
+            panel.0 = Some(panel_entity);
+        }
+        return;
+    }
+
+    if warnings.is_changed() {
+        if let Ok(mut text) = text_query.single_mut() {
+            text.0 = config_warnings_text(&warnings);
+        }
+    }
+}
+
+/// Panel text listing the warnings
+fn config_warnings_text(warnings: &ConfigWarnings) -> String {
+    if warnings.0.is_empty() {
+        return "Config: no warnings".to_string();
+    }
+
+    let mut text = format!("Config: {} warning(s)", warnings.0.len());
+    for warning in &warnings.0 {
+        text.push_str(&format!("\n- {warning}"));
+    }
+    text
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{circle_overlaps_polygon, GroundPoundLanding},
+    death::{s_respawn, PlayerRespawned},
+    s_render,
+    schedule::ControllerPhase,
+    Level, Physics, Player,
+};
+
+pub const CRUMBLING_COLOR: Color = Color::srgb(0.8, 0.55, 0.3);
+// Default time (seconds) a platform shakes before falling away
+pub const DEFAULT_CRUMBLE_DELAY: f32 = 0.5;
+// Default time (seconds) a crumbled platform stays gone
+pub const DEFAULT_CRUMBLE_RESPAWN: f32 = 3.0;
+// Shake offset (pixels) and frequency (radians/second) of the outline while crumbling
+const SHAKE_AMPLITUDE: f32 = 2.0;
+const SHAKE_FREQUENCY: f32 = 60.0;
+// Alpha of the outline of a crumbled platform waiting to come back
+const GONE_ALPHA: f32 = 0.15;
+
+/// Crumbling platforms: shake once the player stands on them, fall away after a delay and come
+/// back later
+pub struct CrumblingPlugin;
+
+impl Plugin for CrumblingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, s_crumble.after(ControllerPhase::Collision))
+            .add_systems(Update, s_reset_crumbling.after(s_respawn))
+            .add_systems(Update, s_render_crumbling.after(s_render));
+    }
+}
+
+/// Where a crumbling platform is in its cycle
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CrumbleState {
+    Solid,
+    /// Stood on; falls away when `elapsed` reaches the delay
+    Shaking {
+        elapsed: f32,
+    },
+    /// Fallen away; comes back when `elapsed` reaches the respawn time
+    Gone {
+        elapsed: f32,
+    },
+}
+
+/// Polygon in `Level` that crumbles under the player
+#[derive(Component)]
+pub struct Crumbling {
+    /// Index of the polygon in `Level::polygons`
+    pub polygon: usize,
+    /// Time (seconds) between the player standing on it and it falling away
+    pub delay: f32,
+    /// Time (seconds) it stays gone
+    pub respawn_time: f32,
+    pub state: CrumbleState,
+}
+
+/// Starts platforms shaking when stood on, then disables and later restores their polygons
+///
+/// A ground pound breaks a platform at once. A platform only comes back once the player is
+/// clear of it.
+pub fn s_crumble(
+    time: Res<Time>,
+    mut level: ResMut<Level>,
+    mut pound_reader: MessageReader<GroundPoundLanding>,
+    player_query: Query<(&Transform, &Physics, &Player)>,
+    mut crumbling_query: Query<&mut Crumbling>,
+) {
+    let dt = time.delta_secs();
+    let player = player_query.single().ok();
+    let pounded: Vec<usize> = pound_reader
+        .read()
+        .filter_map(|landing| landing.polygon)
+        .collect();
+
+    for mut crumbling in &mut crumbling_query {
+        let polygon_index = crumbling.polygon;
+        crumbling.state = match crumbling.state {
+            CrumbleState::Solid | CrumbleState::Shaking { .. }
+                if pounded.contains(&polygon_index) =>
+            {
+                level.polygons[polygon_index].enabled = false;
+                CrumbleState::Gone { elapsed: 0.0 }
+            }
+            CrumbleState::Solid => {
+                let stood_on = player.is_some_and(|(.., player_data)| {
+                    player_data
+                        .ground_edge
+                        .is_some_and(|edge_id| edge_id.polygon == polygon_index)
+                });
+                if stood_on {
+                    CrumbleState::Shaking { elapsed: 0.0 }
+                } else {
+                    CrumbleState::Solid
+                }
+            }
+            CrumbleState::Shaking { elapsed } if elapsed + dt >= crumbling.delay => {
+                level.polygons[polygon_index].enabled = false;
+                CrumbleState::Gone { elapsed: 0.0 }
+            }
+            CrumbleState::Shaking { elapsed } => CrumbleState::Shaking {
+                elapsed: elapsed + dt,
+            },
+            CrumbleState::Gone { elapsed } if elapsed + dt >= crumbling.respawn_time => {
+                let blocked = player.is_some_and(|(player_transform, player_physics, _)| {
+                    circle_overlaps_polygon(
+                        &level.polygons[polygon_index],
+                        player_transform.translation.xy(),
+                        player_physics.radius,
+                    )
+                });
+                if blocked {
+                    CrumbleState::Gone { elapsed }
+                } else {
+                    level.polygons[polygon_index].enabled = true;
+                    CrumbleState::Solid
+                }
+            }
+            CrumbleState::Gone { elapsed } => CrumbleState::Gone {
+                elapsed: elapsed + dt,
+            },
+        };
+    }
+}
+
+/// Restores every crumbling platform when the player respawns
+pub fn s_reset_crumbling(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    mut level: ResMut<Level>,
+    mut crumbling_query: Query<&mut Crumbling>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    for mut crumbling in &mut crumbling_query {
+        crumbling.state = CrumbleState::Solid;
+        level.polygons[crumbling.polygon].enabled = true;
+    }
+}
+
+/// Shakes the outline of crumbling platforms and ghosts the ones that fell away
+pub fn s_render_crumbling(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    level: Res<Level>,
+    crumbling_query: Query<&Crumbling>,
+) {
+    let t = time.elapsed_secs() * SHAKE_FREQUENCY;
+    let shake = Vec2::new(t.sin(), (t * 1.3).cos()) * SHAKE_AMPLITUDE;
+
+    for crumbling in &crumbling_query {
+        let polygon = &level.polygons[crumbling.polygon];
+        match crumbling.state {
+            CrumbleState::Solid => {}
+            CrumbleState::Shaking { .. } => {
+                gizmos.linestrip_2d(
+                    polygon.points.iter().map(|&point| point + shake),
+                    polygon.color,
+                );
+            }
+            CrumbleState::Gone { .. } => {
+                gizmos.linestrip_2d(
+                    polygon.points.iter().copied(),
+                    polygon.color.with_alpha(GONE_ALPHA),
+                );
+            }
+        }
+    }
+}
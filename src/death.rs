@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+
+use crate::{
+    assist::AssistMode, config::ControllerConfig, level::VolumeKind, player_bundle,
+    schedule::ControllerSystems, Level, Physics, Player,
+};
+
+/// Player death: hazards write `PlayerKilled`, and the player respawns at the level's spawn
+/// point unless assist mode makes them invulnerable
+pub struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<PlayerKilled>()
+            .add_message::<PlayerRespawned>()
+            .init_resource::<SpawnPoint>()
+            .add_systems(Update, s_hazard_volumes.after(ControllerSystems))
+            .add_systems(Update, s_respawn.after(s_hazard_volumes));
+    }
+}
+
+/// A hazard killed the player
+#[derive(Message, Clone, Copy)]
+pub struct PlayerKilled {
+    /// Where the player was
+    pub position: Vec2,
+}
+
+/// The player was respawned after dying; level scripts reset their state on this
+#[derive(Message, Clone, Copy)]
+pub struct PlayerRespawned;
+
+/// Where the player respawns (set when the level spawns, moved by checkpoints)
+#[derive(Resource, Default)]
+pub struct SpawnPoint {
+    pub position: Vec2,
+    /// Moving platform the spawn point rides, with the point in the platform's start frame;
+    /// respawning then happens where the platform is, at its velocity
+    pub platform: Option<(Entity, Vec2)>,
+}
+
+impl SpawnPoint {
+    /// Spawn point fixed in the level
+    pub fn at(position: Vec2) -> Self {
+        Self {
+            position,
+            platform: None,
+        }
+    }
+}
+
+/// Kills the player when their center enters a hazard volume
+pub fn s_hazard_volumes(
+    level: Res<Level>,
+    player_query: Query<&Transform, With<Player>>,
+    mut killed_writer: MessageWriter<PlayerKilled>,
+) {
+    if let Ok(player_transform) = player_query.single() {
+        let player_pos = player_transform.translation.xy();
+
+        if level.in_volume(player_pos, VolumeKind::Hazard) {
+            killed_writer.write(PlayerKilled {
+                position: player_pos,
+            });
+        }
+    }
+}
+
+/// Resets the player at the spawn point when killed
+///
+/// Invulnerable players (assist mode) ignore deaths.
+pub fn s_respawn(
+    mut killed_reader: MessageReader<PlayerKilled>,
+    mut respawned_writer: MessageWriter<PlayerRespawned>,
+    assist: Res<AssistMode>,
+    config: Res<ControllerConfig>,
+    spawn_point: Res<SpawnPoint>,
+    mut player_query: Query<(&mut Transform, &mut Physics, &mut Player)>,
+) {
+    // Several hazards can hit in the same frame; it's still one death
+    if killed_reader.read().count() == 0 || assist.is_invulnerable() {
+        return;
+    }
+    let Ok((mut player_transform, mut player_physics, mut player_data)) = player_query.single_mut()
+    else {
+        return;
+    };
+
+    (*player_transform, *player_physics, *player_data) =
+        player_bundle(spawn_point.position, &config);
+    respawned_writer.write(PlayerRespawned);
+}
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{ContactsThisTick, LandingContact},
+    levels::GameState,
+    s_render,
+    schedule::ControllerPhase,
+    Player, PLAYER_MAX_SPEED,
+};
+
+// Most decals alive at once; placing another one reuses the oldest slot
+const MAX_DECALS: usize = 256;
+// Landings slower than this (pixels/second) leave no scuff
+const MIN_SCUFF_SPEED: f32 = 400.0;
+// Ground speed (pixels/second) above which the player leaves streaks, and their spacing (pixels)
+const STREAK_SPEED: f32 = PLAYER_MAX_SPEED * 1.2;
+const STREAK_SPACING: f32 = 12.0;
+// Distance (pixels) between two footprints in snow
+const FOOTPRINT_SPACING: f32 = 18.0;
+// Distance (pixels) decals are drawn off the surface so they don't hide its outline
+const DECAL_OFFSET: f32 = 1.5;
+const SNOW_COLOR: Color = Color::srgb(0.9, 0.95, 1.0);
+
+/// Surface decals: fading marks left where the player touches the level (landing scuffs, streaks
+/// when moving fast along the ground, footprints in snow), placed from `ContactsThisTick` and
+/// kept in a fixed-size pool
+pub struct DecalPlugin;
+
+impl Plugin for DecalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Decals>()
+            .add_systems(OnEnter(GameState::Playing), s_clear_decals)
+            .add_systems(Update, s_place_decals.after(ControllerPhase::Collision))
+            .add_systems(Update, s_render_decals.after(s_render));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecalKind {
+    Scuff,
+    Streak,
+    Footprint,
+}
+
+impl DecalKind {
+    /// Time (seconds) the mark takes to fade out
+    pub fn lifetime(self) -> f32 {
+        match self {
+            Self::Scuff => 3.0,
+            Self::Streak => 1.5,
+            Self::Footprint => 8.0,
+        }
+    }
+
+    /// Length (pixels) of the mark along the surface
+    pub fn length(self) -> f32 {
+        match self {
+            Self::Scuff => 20.0,
+            Self::Streak => 12.0,
+            Self::Footprint => 6.0,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Scuff => Color::srgb(0.6, 0.55, 0.5),
+            Self::Streak => Color::srgb(1.0, 0.8, 0.4),
+            Self::Footprint => Color::srgb(0.55, 0.65, 0.8),
+        }
+    }
+}
+
+/// Mark on a surface
+#[derive(Clone, Copy, Debug)]
+pub struct Decal {
+    pub kind: DecalKind,
+    /// Contact point on the surface (world space)
+    pub position: Vec2,
+    /// Surface normal at the contact; the mark lies along its perpendicular
+    pub normal: Vec2,
+    /// Time (seconds) since the mark was placed
+    pub age: f32,
+}
+
+impl Decal {
+    pub fn expired(&self) -> bool {
+        self.age >= self.kind.lifetime()
+    }
+}
+
+/// Pool of decals plus where the last trail marks were placed
+#[derive(Resource, Default)]
+pub struct Decals {
+    pub slots: Vec<Decal>,
+    /// Slot the next decal goes in once the pool is full (the oldest one)
+    next: usize,
+    last_streak: Option<Vec2>,
+    last_footprint: Option<Vec2>,
+}
+
+impl Decals {
+    /// Adds a decal, reusing the oldest slot when the pool is full
+    pub fn place(&mut self, kind: DecalKind, position: Vec2, normal: Vec2) {
+        let decal = Decal {
+            kind,
+            position,
+            normal,
+            age: 0.0,
+        };
+        if self.slots.len() < MAX_DECALS {
+            self.slots.push(decal);
+        } else {
+            self.slots[self.next] = decal;
+            self.next = (self.next + 1) % MAX_DECALS;
+        }
+    }
+}
+
+/// Area of the level where walking leaves footprints
+#[derive(Component)]
+pub struct SnowPatch {
+    pub area: Rect,
+}
+
+/// Empties the pool when a level starts
+pub fn s_clear_decals(mut decals: ResMut<Decals>) {
+    *decals = Decals::default();
+}
+
+/// Ages the decals and places new ones from the player's contacts this tick
+pub fn s_place_decals(
+    time: Res<Time>,
+    contacts: Res<ContactsThisTick>,
+    mut landing_reader: MessageReader<LandingContact>,
+    player_query: Query<&Player>,
+    snow_query: Query<&SnowPatch>,
+    mut decals: ResMut<Decals>,
+) {
+    let dt = time.delta_secs();
+    for decal in &mut decals.slots {
+        decal.age += dt;
+    }
+
+    // Landing scuffs, where the landing contact touched the polygon
+    for landing in landing_reader.read() {
+        if landing.impact_speed < MIN_SCUFF_SPEED {
+            continue;
+        }
+        if let Some(contact) = contacts.0.iter().find(|contact| {
+            landing.polygon.is_some() && contact.edge.map(|edge| edge.polygon) == landing.polygon
+        }) {
+            decals.place(DecalKind::Scuff, contact.point, contact.normal);
+        }
+    }
+
+    // Trails along the ground edge the player stands on
+    let ground_contact = contacts.0.iter().find(|contact| {
+        player_query.get(contact.entity).is_ok_and(|player_data| {
+            contact.edge.is_some() && player_data.ground_edge == contact.edge
+        })
+    });
+    let Some(contact) = ground_contact else {
+        decals.last_streak = None;
+        decals.last_footprint = None;
+        return;
+    };
+
+    let ground_speed = contact.relative_velocity.dot(contact.normal.perp()).abs();
+    if ground_speed >= STREAK_SPEED {
+        if decals
+            .last_streak
+            .is_none_or(|last| last.distance(contact.point) >= STREAK_SPACING)
+        {
+            decals.place(DecalKind::Streak, contact.point, contact.normal);
+            decals.last_streak = Some(contact.point);
+        }
+    } else {
+        decals.last_streak = None;
+    }
+
+    if snow_query
+        .iter()
+        .any(|snow| snow.area.contains(contact.point))
+    {
+        if decals
+            .last_footprint
+            .is_none_or(|last| last.distance(contact.point) >= FOOTPRINT_SPACING)
+        {
+            decals.place(DecalKind::Footprint, contact.point, contact.normal);
+            decals.last_footprint = Some(contact.point);
+        }
+    } else {
+        decals.last_footprint = None;
+    }
+}
+
+/// Draws the live decals along their surfaces, fading with age, and the snow patches
+pub fn s_render_decals(mut gizmos: Gizmos, decals: Res<Decals>, snow_query: Query<&SnowPatch>) {
+    for snow in &snow_query {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(snow.area.center()),
+            snow.area.size(),
+            SNOW_COLOR,
+        );
+    }
+
+    for decal in decals.slots.iter().filter(|decal| !decal.expired()) {
+        let alpha = 1.0 - decal.age / decal.kind.lifetime();
+        let center = decal.position + decal.normal * DECAL_OFFSET;
+        let half = decal.normal.perp() * decal.kind.length() / 2.0;
+        gizmos.line_2d(
+            center - half,
+            center + half,
+            decal.kind.color().with_alpha(alpha),
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use bevy::{input::ButtonInput, math::Isometry2d, prelude::*};
+
+use crate::{s_render, schedule::ControllerPhase, shop::shop_closed, Physics, Player};
+
+// Dialogue rendering constants
+const SIGN_SIZE: Vec2 = Vec2::new(16.0, 20.0);
+const SIGN_COLOR: Color = Color::srgb(0.8, 0.6, 0.3);
+const DIALOGUE_BOX_MARGIN: f32 = 24.0;
+const DIALOGUE_BOX_PADDING: f32 = 12.0;
+
+/// Signs/NPCs the player can read, shown in a typewriter-style dialogue box
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DialogueConfig>()
+            .init_resource::<ActiveDialogue>()
+            .add_systems(Update, s_advance_dialogue.before(s_open_dialogue))
+            .add_systems(
+                Update,
+                s_open_dialogue
+                    .run_if(shop_closed)
+                    .before(ControllerPhase::Input),
+            )
+            .add_systems(Update, s_render_signs.after(s_render));
+    }
+}
+
+/// A readable sign or NPC
+#[derive(Component)]
+pub struct Sign {
+    /// Dialogue pages, shown one at a time
+    pub pages: Vec<String>,
+}
+
+/// Dialogue tuning
+#[derive(Resource, Clone)]
+pub struct DialogueConfig {
+    /// Typewriter speed (characters/second)
+    pub chars_per_second: f32,
+    /// Simulation speed while a dialogue is open (0.0 pauses the game)
+    pub time_scale: f32,
+    /// Extra distance (pixels) beyond the player's radius from which signs can be read
+    pub interact_distance: f32,
+}
+
+impl Default for DialogueConfig {
+    fn default() -> Self {
+        Self {
+            chars_per_second: 40.0,
+            time_scale: 0.0,
+            interact_distance: 12.0,
+        }
+    }
+}
+
+/// Currently open dialogue, if any
+#[derive(Resource, Default)]
+pub struct ActiveDialogue(pub Option<DialogueState>);
+
+pub struct DialogueState {
+    pages: Vec<String>,
+    page: usize,
+    /// Characters of the current page revealed so far
+    revealed: f32,
+    /// Simulation speed to restore when the dialogue closes
+    previous_speed: f32,
+    /// Root UI node of the dialogue box
+    dialogue_box: Entity,
+}
+
+/// Run condition: no dialogue is open, so the shop can take E/Enter
+pub fn dialogue_closed(active_dialogue: Res<ActiveDialogue>) -> bool {
+    active_dialogue.0.is_none()
+}
+
+/// Marker for the dialogue box text
+#[derive(Component)]
+pub struct DialogueText;
+
+/// Opens the dialogue of the closest sign in reach when the player presses E/Enter
+pub fn s_open_dialogue(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut active_dialogue: ResMut<ActiveDialogue>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    config: Res<DialogueConfig>,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+    sign_query: Query<(&Transform, &Sign)>,
+) {
+    if active_dialogue.0.is_some()
+        || !keyboard_input.any_just_pressed([KeyCode::KeyE, KeyCode::Enter])
+    {
+        return;
+    }
+
+    let Ok((player_transform, player_physics)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.xy();
+    let reach = player_physics.radius + config.interact_distance;
+
+    let closest_sign = sign_query
+        .iter()
+        .map(|(transform, sign)| (transform.translation.xy().distance(player_pos), sign))
+        .filter(|(distance, sign)| *distance <= reach && !sign.pages.is_empty())
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    if let Some((_, sign)) = closest_sign {
+        let previous_speed = virtual_time.relative_speed();
+        if config.time_scale > 0.0 {
+            virtual_time.set_relative_speed(config.time_scale);
+        } else {
+            virtual_time.pause();
+        }
+
+        let dialogue_box = commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(DIALOGUE_BOX_MARGIN),
+                    right: Val::Px(DIALOGUE_BOX_MARGIN),
+                    bottom: Val::Px(DIALOGUE_BOX_MARGIN),
+                    padding: UiRect::all(Val::Px(DIALOGUE_BOX_PADDING)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            ))
+            .with_children(|parent| {
+                parent.spawn((Text::new(""), DialogueText));
+            })
+            .id();
+
+        active_dialogue.0 = Some(DialogueState {
+            pages: sign.pages.clone(),
+            page: 0,
+            revealed: 0.0,
+            previous_speed,
+            dialogue_box,
+        });
+    }
+}
+
+/// Animates the open dialogue and handles paging and skipping
+///
+/// E/Enter finishes the current page or advances to the next one; Tab skips the dialogue.
+/// Keys used here are consumed so they don't also trigger a jump or reopen the sign.
+pub fn s_advance_dialogue(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut active_dialogue: ResMut<ActiveDialogue>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+    config: Res<DialogueConfig>,
+    mut text_query: Query<&mut Text, With<DialogueText>>,
+) {
+    let Some(dialogue) = &mut active_dialogue.0 else {
+        return;
+    };
+
+    let advance_pressed = keyboard_input.clear_just_pressed(KeyCode::KeyE)
+        | keyboard_input.clear_just_pressed(KeyCode::Enter);
+    let mut close = keyboard_input.clear_just_pressed(KeyCode::Tab);
+    keyboard_input.clear_just_pressed(KeyCode::Space);
+
+    let page_len = dialogue.pages[dialogue.page].chars().count() as f32;
+
+    if advance_pressed {
+        if dialogue.revealed < page_len {
+            // Skip the typewriter animation
+            dialogue.revealed = page_len;
+        } else if dialogue.page + 1 < dialogue.pages.len() {
+            dialogue.page += 1;
+            dialogue.revealed = 0.0;
+        } else {
+            close = true;
+        }
+    } else {
+        // Animate in real time so text still appears while the simulation is paused
+        dialogue.revealed =
+            (dialogue.revealed + config.chars_per_second * real_time.delta_secs()).min(page_len);
+    }
+
+    if close {
+        commands.entity(dialogue.dialogue_box).despawn();
+        virtual_time.unpause();
+        virtual_time.set_relative_speed(dialogue.previous_speed);
+        active_dialogue.0 = None;
+        return;
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        text.0 = dialogue.pages[dialogue.page]
+            .chars()
+            .take(dialogue.revealed as usize)
+            .collect();
+    }
+}
+
+/// Draws signs as small boards
+pub fn s_render_signs(mut gizmos: Gizmos, sign_query: Query<&Transform, With<Sign>>) {
+    for sign_transform in &sign_query {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(sign_transform.translation.xy()),
+            SIGN_SIZE,
+            SIGN_COLOR,
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+
+use crate::{
+    collisions::LandingContact, death::PlayerKilled, jump_pad::JumpPadLaunch,
+    schedule::ControllerSystems, MAX_FALL_SPEED,
+};
+
+// Minimum time (seconds) between two outputs on a channel
+const RUMBLE_INTERVAL: f32 = 0.15;
+const SHAKE_INTERVAL: f32 = 0.1;
+const SOUND_INTERVAL: f32 = 0.05;
+// Length (seconds) of a rumble pulse at full intensity
+const RUMBLE_DURATION: f32 = 0.2;
+// Landings slower than this fraction of the max fall speed give no feedback
+const MIN_LANDING_INTENSITY: f32 = 0.3;
+// Intensity of a jump pad launch
+const LAUNCH_INTENSITY: f32 = 0.6;
+
+/// Feedback routing: gameplay writes `FeedbackCue`s, and the router turns them into rumble,
+/// screen shake and sound outputs, throttled per channel so bursts of events (many landings or
+/// hits per second) collapse into the strongest one instead of flooding the outputs
+pub struct FeedbackPlugin;
+
+impl Plugin for FeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<FeedbackCue>()
+            .add_message::<ScreenShake>()
+            .add_message::<SoundCue>()
+            .init_resource::<FeedbackRouter>()
+            .add_systems(Update, s_feedback_cues.after(ControllerSystems))
+            .add_systems(Update, s_route_feedback.after(s_feedback_cues));
+    }
+}
+
+/// What happened
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeedbackKind {
+    Landing,
+    Launch,
+    Hit,
+    Death,
+}
+
+/// Importance of a cue: higher priorities win ties within a frame and cut through a channel's
+/// throttle
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum FeedbackPriority {
+    Low,
+    Medium,
+    High,
+}
+
+impl FeedbackKind {
+    pub fn priority(self) -> FeedbackPriority {
+        match self {
+            Self::Landing => FeedbackPriority::Low,
+            Self::Launch | Self::Hit => FeedbackPriority::Medium,
+            Self::Death => FeedbackPriority::High,
+        }
+    }
+
+    /// Channels the cue is routed to
+    pub fn channels(self) -> &'static [FeedbackChannel] {
+        match self {
+            Self::Landing | Self::Launch => &[FeedbackChannel::Rumble, FeedbackChannel::Sound],
+            Self::Hit | Self::Death => &[
+                FeedbackChannel::Rumble,
+                FeedbackChannel::Shake,
+                FeedbackChannel::Sound,
+            ],
+        }
+    }
+}
+
+/// Request for feedback, written by gameplay systems
+#[derive(Message, Clone, Copy, Debug)]
+pub struct FeedbackCue {
+    pub kind: FeedbackKind,
+    /// Strength from 0.0 to 1.0
+    pub intensity: f32,
+}
+
+impl FeedbackCue {
+    /// Whether this cue should replace `other` as a channel's output
+    fn outranks(&self, other: &FeedbackCue) -> bool {
+        (self.kind.priority(), self.intensity) > (other.kind.priority(), other.intensity)
+    }
+}
+
+/// Output device of the router
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeedbackChannel {
+    Rumble,
+    Shake,
+    Sound,
+}
+
+impl FeedbackChannel {
+    const ALL: [Self; 3] = [Self::Rumble, Self::Shake, Self::Sound];
+
+    /// Minimum time (seconds) between two outputs
+    pub fn interval(self) -> f32 {
+        match self {
+            Self::Rumble => RUMBLE_INTERVAL,
+            Self::Shake => SHAKE_INTERVAL,
+            Self::Sound => SOUND_INTERVAL,
+        }
+    }
+}
+
+/// Camera shake output, for the camera effect to apply
+#[derive(Message, Clone, Copy, Debug)]
+pub struct ScreenShake {
+    /// Strength from 0.0 to 1.0
+    pub intensity: f32,
+}
+
+/// Sound output, for the SFX player to play
+#[derive(Message, Clone, Copy, Debug)]
+pub struct SoundCue {
+    pub kind: FeedbackKind,
+    /// Volume from 0.0 to 1.0
+    pub volume: f32,
+}
+
+/// Throttle state of a channel
+#[derive(Default, Clone, Copy)]
+pub struct ChannelState {
+    /// Time (seconds) left before the channel outputs again
+    pub cooldown: f32,
+    /// Last output, while the cooldown runs
+    pub last: Option<FeedbackCue>,
+    /// Strongest cue held back by the cooldown, output when it ends
+    pub pending: Option<FeedbackCue>,
+}
+
+/// Per-channel throttle state of the feedback router
+#[derive(Resource, Default)]
+pub struct FeedbackRouter {
+    pub channels: [ChannelState; 3],
+}
+
+impl FeedbackRouter {
+    /// Advances the throttles by `dt` and routes the cues, returning the outputs per channel
+    pub fn route(
+        &mut self,
+        dt: f32,
+        cues: impl IntoIterator<Item = FeedbackCue>,
+    ) -> Vec<(FeedbackChannel, FeedbackCue)> {
+        // Only the strongest cue per channel matters within a frame
+        let mut strongest: [Option<FeedbackCue>; 3] = [None; 3];
+        for cue in cues {
+            for &channel in cue.kind.channels() {
+                let slot = &mut strongest[channel as usize];
+                if slot.is_none_or(|current| cue.outranks(&current)) {
+                    *slot = Some(cue);
+                }
+            }
+        }
+
+        let mut outputs = Vec::new();
+        for channel in FeedbackChannel::ALL {
+            let state = &mut self.channels[channel as usize];
+            state.cooldown = (state.cooldown - dt).max(0.0);
+            if state.cooldown <= 0.0 {
+                state.last = None;
+            }
+
+            // New cues join the held-back one; a higher priority than the last output cuts
+            // through the cooldown
+            let candidate = match (state.pending, strongest[channel as usize]) {
+                (Some(pending), Some(cue)) if cue.outranks(&pending) => Some(cue),
+                (Some(pending), _) => Some(pending),
+                (None, cue) => cue,
+            };
+            let Some(candidate) = candidate else {
+                continue;
+            };
+            let preempts = state
+                .last
+                .is_some_and(|last| candidate.kind.priority() > last.kind.priority());
+
+            if state.cooldown <= 0.0 || preempts {
+                outputs.push((channel, candidate));
+                state.cooldown = channel.interval();
+                state.last = Some(candidate);
+                state.pending = None;
+            } else {
+                state.pending = Some(candidate);
+            }
+        }
+        outputs
+    }
+}
+
+/// Turns controller and level messages into feedback cues
+pub fn s_feedback_cues(
+    mut landing_reader: MessageReader<LandingContact>,
+    mut launch_reader: MessageReader<JumpPadLaunch>,
+    mut killed_reader: MessageReader<PlayerKilled>,
+    mut cue_writer: MessageWriter<FeedbackCue>,
+) {
+    for landing in landing_reader.read() {
+        let intensity = (landing.impact_speed / MAX_FALL_SPEED).min(1.0);
+        if intensity >= MIN_LANDING_INTENSITY {
+            cue_writer.write(FeedbackCue {
+                kind: FeedbackKind::Landing,
+                intensity,
+            });
+        }
+    }
+    for _ in launch_reader.read() {
+        cue_writer.write(FeedbackCue {
+            kind: FeedbackKind::Launch,
+            intensity: LAUNCH_INTENSITY,
+        });
+    }
+    for _ in killed_reader.read() {
+        cue_writer.write(FeedbackCue {
+            kind: FeedbackKind::Death,
+            intensity: 1.0,
+        });
+    }
+}
+
+/// Routes the frame's cues through the throttles and emits the outputs
+///
+/// Throttles run on real time so slow motion doesn't stretch them.
+pub fn s_route_feedback(
+    real_time: Res<Time<Real>>,
+    mut router: ResMut<FeedbackRouter>,
+    mut cue_reader: MessageReader<FeedbackCue>,
+    gamepad_query: Query<Entity, With<Gamepad>>,
+    mut rumble_writer: MessageWriter<GamepadRumbleRequest>,
+    mut shake_writer: MessageWriter<ScreenShake>,
+    mut sound_writer: MessageWriter<SoundCue>,
+) {
+    let outputs = router.route(real_time.delta_secs(), cue_reader.read().copied());
+
+    for (channel, cue) in outputs {
+        match channel {
+            FeedbackChannel::Rumble => {
+                for gamepad in &gamepad_query {
+                    rumble_writer.write(GamepadRumbleRequest::Add {
+                        gamepad,
+                        intensity: GamepadRumbleIntensity {
+                            strong_motor: cue.intensity,
+                            weak_motor: cue.intensity,
+                        },
+                        duration: Duration::from_secs_f32(RUMBLE_DURATION * cue.intensity),
+                    });
+                }
+            }
+            FeedbackChannel::Shake => {
+                shake_writer.write(ScreenShake {
+                    intensity: cue.intensity,
+                });
+            }
+            FeedbackChannel::Sound => {
+                sound_writer.write(SoundCue {
+                    kind: cue.kind,
+                    volume: cue.intensity,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use bevy::{input::ButtonInput, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    progression::ProgressSave, schedule::ControllerPhase, InputDir, Player, EPSILON, MAX_JUMP_TIMER,
+};
+
+// Settings menu constants
+const SETTINGS_MENU_MARGIN: f32 = 24.0;
+const SETTINGS_MENU_PADDING: f32 = 12.0;
+// Adjustment per Left/Right press for zone values and response exponents
+const ZONE_STEP: f32 = 0.01;
+const EXPONENT_STEP: f32 = 0.1;
+const MAX_ZONE: f32 = 0.9;
+const MIN_EXPONENT: f32 = 0.2;
+const MAX_EXPONENT: f32 = 4.0;
+
+/// Gamepad input layer: left stick movement with per-axis response, South jumps, West sprints
+///
+/// F1 opens a settings menu to tune the stick response.
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StickConfig>()
+            .init_resource::<SettingsMenu>()
+            .add_systems(Update, s_settings_menu.before(ControllerPhase::Input))
+            .add_systems(
+                Update,
+                s_gamepad_input
+                    .after(ControllerPhase::Input)
+                    .before(ControllerPhase::Movement),
+            );
+    }
+}
+
+/// Response settings for one stick axis
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct AxisResponse {
+    /// Raw deflection below which the axis reads as zero (0.0..1.0)
+    pub dead_zone: f32,
+    /// Output just outside the dead zone, so small deflections still move the player (0.0..1.0)
+    pub anti_dead_zone: f32,
+    /// Response curve exponent (1.0 linear, >1.0 finer control near the center)
+    pub response_exponent: f32,
+}
+
+impl AxisResponse {
+    /// Maps a raw axis value (-1.0..1.0) through the dead zone, response curve and anti-dead zone
+    pub fn apply(&self, raw: f32) -> f32 {
+        let magnitude = raw.abs().min(1.0);
+        if magnitude <= self.dead_zone {
+            return 0.0;
+        }
+
+        let normalized = (magnitude - self.dead_zone) / (1.0 - self.dead_zone);
+        let curved = normalized.powf(self.response_exponent);
+        let output = self.anti_dead_zone + (1.0 - self.anti_dead_zone) * curved;
+
+        output * raw.signum()
+    }
+}
+
+impl Default for AxisResponse {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.15,
+            anti_dead_zone: 0.0,
+            response_exponent: 1.0,
+        }
+    }
+}
+
+/// Left stick response, per axis
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct StickConfig {
+    pub x: AxisResponse,
+    pub y: AxisResponse,
+}
+
+impl StickConfig {
+    /// Processed stick direction, with a length of at most 1.0
+    pub fn apply(&self, raw: Vec2) -> Vec2 {
+        Vec2::new(self.x.apply(raw.x), self.y.apply(raw.y)).clamp_length_max(1.0)
+    }
+}
+
+/// Currently open settings menu, if any
+#[derive(Resource, Default)]
+pub struct SettingsMenu(pub Option<SettingsMenuState>);
+
+pub struct SettingsMenuState {
+    /// Selected row (index into `SETTINGS_LABELS`)
+    selected: usize,
+    /// Simulation speed to restore when the menu closes
+    previous_speed: f32,
+    /// Root UI node of the menu
+    menu: Entity,
+}
+
+/// Marker for the settings menu text
+#[derive(Component)]
+pub struct SettingsText;
+
+/// Labels of the adjustable stick settings, in menu order
+const SETTINGS_LABELS: [&str; 6] = [
+    "Stick X dead zone",
+    "Stick X anti-dead zone",
+    "Stick X response",
+    "Stick Y dead zone",
+    "Stick Y anti-dead zone",
+    "Stick Y response",
+];
+
+/// The setting on a menu row, with its (step, min, max)
+fn setting_mut(config: &mut StickConfig, row: usize) -> (&mut f32, f32, f32, f32) {
+    let axis = if row < 3 {
+        &mut config.x
+    } else {
+        &mut config.y
+    };
+    match row % 3 {
+        0 => (&mut axis.dead_zone, ZONE_STEP, 0.0, MAX_ZONE),
+        1 => (&mut axis.anti_dead_zone, ZONE_STEP, 0.0, MAX_ZONE),
+        _ => (
+            &mut axis.response_exponent,
+            EXPONENT_STEP,
+            MIN_EXPONENT,
+            MAX_EXPONENT,
+        ),
+    }
+}
+
+/// Applies the first connected gamepad on top of the keyboard input
+pub fn s_gamepad_input(
+    stick_config: Res<StickConfig>,
+    settings_menu: Res<SettingsMenu>,
+    mut input_dir: ResMut<InputDir>,
+    gamepad_query: Query<&Gamepad>,
+    mut player_query: Query<&mut Player>,
+) {
+    if settings_menu.0.is_some() {
+        return;
+    }
+    let Some(gamepad) = gamepad_query.iter().next() else {
+        return;
+    };
+
+    if let Ok(mut player_data) = player_query.single_mut() {
+        // Left stick to move (overrides the arrow keys while deflected)
+        let stick = stick_config.apply(gamepad.left_stick());
+        if stick.length_squared() > EPSILON {
+            input_dir.dir = stick;
+        }
+
+        // West to sprint
+        player_data.sprint_held |= gamepad.pressed(GamepadButton::West);
+
+        // South to jump
+        player_data.jump_held |= gamepad.pressed(GamepadButton::South);
+        if gamepad.just_pressed(GamepadButton::South) {
+            player_data.jump_timer = MAX_JUMP_TIMER;
+        }
+    }
+}
+
+/// F1 toggles the stick settings menu; Up/Down selects a setting and Left/Right adjusts it
+///
+/// Keys used here are consumed so they don't also move the player. Closing the menu saves the
+/// settings to the current profile.
+pub fn s_settings_menu(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut stick_config: ResMut<StickConfig>,
+    mut save: ProgressSave,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut text_query: Query<&mut Text, With<SettingsText>>,
+) {
+    let toggle = keyboard_input.clear_just_pressed(KeyCode::F1);
+
+    if settings_menu.0.is_none() {
+        if toggle {
+            let previous_speed = virtual_time.relative_speed();
+            virtual_time.pause();
+
+            let menu = commands
+                .spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(SETTINGS_MENU_MARGIN),
+                        top: Val::Px(SETTINGS_MENU_MARGIN),
+                        padding: UiRect::all(Val::Px(SETTINGS_MENU_PADDING)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new(""), SettingsText));
+                })
+                .id();
+
+            settings_menu.0 = Some(SettingsMenuState {
+                selected: 0,
+                previous_speed,
+                menu,
+            });
+        }
+        return;
+    }
+    let Some(menu_state) = &mut settings_menu.0 else {
+        return;
+    };
+
+    if toggle | keyboard_input.clear_just_pressed(KeyCode::Tab) {
+        commands.entity(menu_state.menu).despawn();
+        virtual_time.unpause();
+        virtual_time.set_relative_speed(menu_state.previous_speed);
+        settings_menu.0 = None;
+
+        save.progress.0.settings.stick = Some(*stick_config);
+        save.store();
+        return;
+    }
+
+    if keyboard_input.clear_just_pressed(KeyCode::ArrowUp) && menu_state.selected > 0 {
+        menu_state.selected -= 1;
+    }
+    if keyboard_input.clear_just_pressed(KeyCode::ArrowDown)
+        && menu_state.selected + 1 < SETTINGS_LABELS.len()
+    {
+        menu_state.selected += 1;
+    }
+
+    let (value, step, min, max) = setting_mut(&mut stick_config, menu_state.selected);
+    if keyboard_input.clear_just_pressed(KeyCode::ArrowLeft) {
+        *value = (*value - step).clamp(min, max);
+    }
+    if keyboard_input.clear_just_pressed(KeyCode::ArrowRight) {
+        *value = (*value + step).clamp(min, max);
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        let mut menu_text = "Controller Settings\n".to_string();
+        for (i, label) in SETTINGS_LABELS.iter().enumerate() {
+            let cursor = if i == menu_state.selected { ">" } else { " " };
+            let (value, ..) = setting_mut(&mut stick_config, i);
+            menu_text.push_str(&format!("{} {}: {:.2}\n", cursor, label, value));
+        }
+        menu_text.push_str("Up/Down: select, Left/Right: adjust, F1/Tab: close");
+        text.0 = menu_text;
+    }
+}
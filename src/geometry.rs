@@ -0,0 +1,88 @@
+use bevy::math::Vec2;
+
+use crate::EPSILON;
+
+// Distance (pixels) within which parallel segments count as collinear
+const COLLINEAR_TOLERANCE: f32 = 1e-3;
+
+/// Squared distance from `point` to the segment from `start` to `end`, and the closest point
+/// of the segment (an end point when `point` projects beyond it)
+pub fn find_projection(start: Vec2, end: Vec2, point: Vec2) -> (f32, Vec2) {
+    let projection = closest_point_on_segment(start, end, point);
+    (projection.distance_squared(point), projection)
+}
+
+/// Closest point to `point` on the segment from `start` to `end`
+pub fn closest_point_on_segment(start: Vec2, end: Vec2, point: Vec2) -> Vec2 {
+    let line_vec = end - start;
+    let length_sq = line_vec.length_squared();
+    if length_sq < EPSILON {
+        return start;
+    }
+
+    let t = ((point - start).dot(line_vec) / length_sq).clamp(0.0, 1.0);
+    start + line_vec * t
+}
+
+/// Side of the line through `line_start` and `line_end` a point is on: 1.0 to the left (looking
+/// from start to end) or on the line, -1.0 to the right
+pub fn side_of_line_detection(line_start: Vec2, line_end: Vec2, point: Vec2) -> f32 {
+    cross_product(line_end - line_start, point - line_start).signum()
+}
+
+/// Where the segment from `line_1_start` to `line_1_end` meets the one from `line_2_start` to
+/// `line_2_end`, if they do
+///
+/// Overlapping collinear segments meet at the first shared point along the first segment, and
+/// zero-length segments act as points.
+pub fn line_intersect(
+    line_1_start: Vec2,
+    line_1_end: Vec2,
+    line_2_start: Vec2,
+    line_2_end: Vec2,
+) -> Option<Vec2> {
+    let line_1 = line_1_end - line_1_start;
+    let line_2 = line_2_end - line_2_start;
+    let a_to_c = line_2_start - line_1_start;
+
+    // A point meets a segment when it lies on it
+    let line_1_length_sq = line_1.length_squared();
+    if line_1_length_sq < EPSILON {
+        return (closest_point_on_segment(line_2_start, line_2_end, line_1_start)
+            .distance_squared(line_1_start)
+            <= COLLINEAR_TOLERANCE.powi(2))
+        .then_some(line_1_start);
+    }
+
+    let line_1_length = line_1_length_sq.sqrt();
+    let r_cross_s = cross_product(line_1, line_2);
+    if r_cross_s.abs() <= EPSILON * line_1_length * line_2.length() {
+        // Parallel (or a point for line 2): only collinear segments meet, where their ranges
+        // along line 1 overlap
+        if cross_product(a_to_c, line_1).abs() > COLLINEAR_TOLERANCE * line_1_length {
+            return None;
+        }
+        let t_start = a_to_c.dot(line_1) / line_1_length_sq;
+        let t_end = t_start + line_2.dot(line_1) / line_1_length_sq;
+        let (t_min, t_max) = (t_start.min(t_end), t_start.max(t_end));
+        if t_max < 0.0 || t_min > 1.0 {
+            return None;
+        }
+        return Some(line_1_start + line_1 * t_min.max(0.0));
+    }
+
+    let t = cross_product(a_to_c, line_2) / r_cross_s;
+    let u = cross_product(a_to_c, line_1) / r_cross_s;
+
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(line_1_start + line_1 * t)
+    } else {
+        None
+    }
+}
+
+/// Z component of the 3D cross product of two vectors (positive when `b` is counter-clockwise
+/// from `a`)
+pub fn cross_product(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
@@ -0,0 +1,260 @@
+use bevy::{input::ButtonInput, prelude::*};
+
+use crate::{
+    collisions::{raycast_level, CollisionLayers},
+    input_modes::s_auto_run,
+    s_render,
+    schedule::ControllerPhase,
+    InputDir, Level, Physics, Player, EPSILON,
+};
+
+const ROPE_COLOR: Color = Color::srgb(0.8, 0.7, 0.5);
+const ANCHOR_RADIUS: f32 = 4.0;
+const ANCHOR_COLOR: Color = Color::srgb(0.8, 0.7, 0.5);
+
+/// Ropes: the grappling hook and level-placed swing anchors
+///
+/// Grappling hook: C fires a rope toward the input direction that the player swings on.
+/// Up reels in, Down lets rope out, and C or Space releases with the current momentum.
+///
+/// Swing anchors: X latches onto the closest anchor in reach, swinging on a rope of fixed
+/// length. Space or X lets go with the tangential momentum.
+pub struct GrapplePlugin;
+
+impl Plugin for GrapplePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GrappleConfig>()
+            .add_systems(
+                Update,
+                s_grapple_input
+                    .after(s_auto_run)
+                    .before(ControllerPhase::Movement),
+            )
+            .add_systems(
+                Update,
+                s_swing_input
+                    .after(s_auto_run)
+                    .before(ControllerPhase::Movement),
+            )
+            .add_systems(
+                Update,
+                (s_rope_constraint, s_swing_constraint)
+                    .after(ControllerPhase::Movement)
+                    .before(ControllerPhase::Collision),
+            )
+            .add_systems(Update, (s_render_rope, s_render_swing).after(s_render));
+    }
+}
+
+/// Grappling hook tuning
+#[derive(Resource, Clone)]
+pub struct GrappleConfig {
+    /// Maximum rope length when firing (pixels)
+    pub max_distance: f32,
+    /// Shortest rope length reachable by reeling in (pixels)
+    pub min_length: f32,
+    /// Reel in/out speed (pixels/second)
+    pub reel_speed: f32,
+    /// Maximum distance (pixels) from a swing anchor to latch onto it
+    pub latch_distance: f32,
+}
+
+impl Default for GrappleConfig {
+    fn default() -> Self {
+        Self {
+            max_distance: 240.0,
+            min_length: 24.0,
+            reel_speed: 180.0,
+            latch_distance: 64.0,
+        }
+    }
+}
+
+/// Attached grappling hook rope
+#[derive(Component)]
+pub struct Grapple {
+    /// Point on the level the rope is attached to
+    pub anchor: Vec2,
+    /// Current rope length (pixels)
+    pub length: f32,
+}
+
+/// Level-placed point the player can swing from
+#[derive(Component)]
+pub struct SwingAnchor;
+
+/// Pendulum state: the player is held at a fixed distance from a swing anchor
+#[derive(Component)]
+pub struct Swing {
+    pub anchor: Vec2,
+    pub length: f32,
+}
+
+/// Fires, reels and releases the grappling hook
+pub fn s_grapple_input(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    input_dir: Res<InputDir>,
+    level: Res<Level>,
+    config: Res<GrappleConfig>,
+    mut player_query: Query<
+        (Entity, &Transform, Option<&mut Grapple>),
+        (With<Player>, Without<Swing>),
+    >,
+) {
+    let Ok((entity, player_transform, grapple)) = player_query.single_mut() else {
+        return;
+    };
+
+    match grapple {
+        Some(mut grapple) => {
+            // Release, keeping the swing momentum
+            if keyboard_input.any_just_pressed([KeyCode::KeyC, KeyCode::Space]) {
+                commands.entity(entity).remove::<Grapple>();
+                return;
+            }
+
+            let reel = keyboard_input.pressed(KeyCode::ArrowDown) as i32 as f32
+                - keyboard_input.pressed(KeyCode::ArrowUp) as i32 as f32;
+            grapple.length = (grapple.length + reel * config.reel_speed * time.delta_secs())
+                .clamp(config.min_length, config.max_distance);
+        }
+        None => {
+            if !keyboard_input.just_pressed(KeyCode::KeyC) {
+                return;
+            }
+
+            // Aim along the input, straight up without input
+            let aim = if input_dir.dir.length_squared() > EPSILON {
+                input_dir.dir
+            } else {
+                Vec2::Y
+            };
+
+            let player_pos = player_transform.translation.xy();
+            if let Some((anchor, distance)) = raycast_level(
+                &level,
+                player_pos,
+                aim,
+                config.max_distance,
+                CollisionLayers::default(),
+            ) {
+                commands.entity(entity).insert(Grapple {
+                    anchor,
+                    length: distance.max(config.min_length),
+                });
+            }
+        }
+    }
+}
+
+/// Rope constraint: keeps the player within the rope length of the anchor
+///
+/// Removes the outward velocity so gravity and input turn into a swing around the anchor.
+pub fn s_rope_constraint(mut player_query: Query<(&mut Transform, &mut Physics, &Grapple)>) {
+    let Ok((mut player_transform, mut player_physics, grapple)) = player_query.single_mut() else {
+        return;
+    };
+
+    let offset = player_transform.translation.xy() - grapple.anchor;
+    let distance = offset.length();
+    if distance <= grapple.length || distance < EPSILON {
+        return;
+    }
+
+    let rope_dir = offset / distance;
+    let constrained = grapple.anchor + rope_dir * grapple.length;
+    player_transform.translation.x = constrained.x;
+    player_transform.translation.y = constrained.y;
+
+    let outward_speed = player_physics.velocity.dot(rope_dir);
+    if outward_speed > 0.0 {
+        player_physics.velocity -= rope_dir * outward_speed;
+    }
+}
+
+/// Draws the rope
+pub fn s_render_rope(mut gizmos: Gizmos, player_query: Query<(&Transform, &Grapple)>) {
+    for (player_transform, grapple) in &player_query {
+        gizmos.line_2d(
+            player_transform.translation.xy(),
+            grapple.anchor,
+            ROPE_COLOR,
+        );
+    }
+}
+
+/// Latches onto and lets go of swing anchors
+pub fn s_swing_input(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    config: Res<GrappleConfig>,
+    player_query: Query<(Entity, &Transform, Has<Swing>), (With<Player>, Without<Grapple>)>,
+    anchor_query: Query<&Transform, With<SwingAnchor>>,
+) {
+    let Ok((entity, player_transform, swinging)) = player_query.single() else {
+        return;
+    };
+
+    if swinging {
+        if keyboard_input.any_just_pressed([KeyCode::KeyX, KeyCode::Space]) {
+            commands.entity(entity).remove::<Swing>();
+        }
+        return;
+    }
+
+    if !keyboard_input.just_pressed(KeyCode::KeyX) {
+        return;
+    }
+
+    let player_pos = player_transform.translation.xy();
+    let closest_anchor = anchor_query
+        .iter()
+        .map(|anchor_transform| anchor_transform.translation.xy())
+        .map(|anchor| (anchor, anchor.distance(player_pos)))
+        .filter(|(_, distance)| *distance <= config.latch_distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    if let Some((anchor, distance)) = closest_anchor {
+        commands.entity(entity).insert(Swing {
+            anchor,
+            length: distance.max(EPSILON),
+        });
+    }
+}
+
+/// Pendulum constraint: keeps the player exactly on the circle around the anchor, with only
+/// tangential velocity
+pub fn s_swing_constraint(mut player_query: Query<(&mut Transform, &mut Physics, &Swing)>) {
+    let Ok((mut player_transform, mut player_physics, swing)) = player_query.single_mut() else {
+        return;
+    };
+
+    let rope_dir = (player_transform.translation.xy() - swing.anchor).normalize_or(Vec2::NEG_Y);
+    let constrained = swing.anchor + rope_dir * swing.length;
+    player_transform.translation.x = constrained.x;
+    player_transform.translation.y = constrained.y;
+
+    let radial_speed = player_physics.velocity.dot(rope_dir);
+    player_physics.velocity -= rope_dir * radial_speed;
+}
+
+/// Draws swing anchors and the swing rope
+pub fn s_render_swing(
+    mut gizmos: Gizmos,
+    anchor_query: Query<&Transform, With<SwingAnchor>>,
+    player_query: Query<(&Transform, &Swing)>,
+) {
+    for anchor_transform in &anchor_query {
+        gizmos.circle_2d(
+            anchor_transform.translation.xy(),
+            ANCHOR_RADIUS,
+            ANCHOR_COLOR,
+        );
+    }
+
+    for (player_transform, swing) in &player_query {
+        gizmos.line_2d(player_transform.translation.xy(), swing.anchor, ROPE_COLOR);
+    }
+}
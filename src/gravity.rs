@@ -0,0 +1,156 @@
+use bevy::{input::ButtonInput, prelude::*};
+
+use crate::{
+    collisions::point_in_polygon, level::Aabb, levels::GameState, schedule::ControllerPhase, Player,
+};
+
+/// Gravity direction: a uniform direction or radial pull set per level, and G flips it so the
+/// player runs on ceilings
+pub struct GravityPlugin;
+
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Gravity>()
+            .add_systems(OnEnter(GameState::Playing), s_reset_gravity)
+            .add_systems(
+                Update,
+                s_flip_gravity
+                    .after(ControllerPhase::Input)
+                    .before(ControllerPhase::Movement)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Where gravity pulls
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GravityField {
+    /// Same direction everywhere (world space, +Y up)
+    Uniform(Vec2),
+    /// Towards a point, e.g. the center of a small planet
+    Radial(Vec2),
+}
+
+impl Default for GravityField {
+    fn default() -> Self {
+        Self::Uniform(Vec2::NEG_Y)
+    }
+}
+
+/// Level area that overrides gravity while the player's center is inside (low gravity rooms,
+/// inverted rooms)
+#[derive(Clone, Debug)]
+pub struct GravityZone {
+    /// Outline in world space
+    pub points: Vec<Vec2>,
+    pub aabb: Aabb,
+    /// Direction gravity pulls inside (`None` keeps the level's field direction)
+    pub direction: Option<Vec2>,
+    /// Gravity strength multiplier inside
+    pub strength: f32,
+}
+
+impl GravityZone {
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.aabb.contains(point) && point_in_polygon(&self.points, point)
+    }
+}
+
+/// Current gravity
+#[derive(Resource, Default)]
+pub struct Gravity {
+    /// Set from the level when it spawns
+    pub field: GravityField,
+    /// Set from the level when it spawns; the first zone containing a position applies there
+    pub zones: Vec<GravityZone>,
+    /// Whether gravity is reversed from the field's direction
+    pub flipped: bool,
+}
+
+impl Gravity {
+    /// Zone overriding gravity at a position, if any
+    pub fn zone_at(&self, position: Vec2) -> Option<&GravityZone> {
+        self.zones.iter().find(|zone| zone.contains(position))
+    }
+
+    /// Direction gravity pulls at a position, ignoring flips
+    pub fn field_direction(&self, position: Vec2) -> Vec2 {
+        let zone_direction = self.zone_at(position).and_then(|zone| zone.direction);
+        match (zone_direction, self.field) {
+            (Some(direction), _) | (None, GravityField::Uniform(direction)) => direction,
+            (None, GravityField::Radial(center)) => center - position,
+        }
+        .normalize_or(Vec2::NEG_Y)
+    }
+
+    /// Effective gravity at a position: gravity-aligned basis and strength multiplier
+    pub fn frame_at(&self, position: Vec2) -> GravityFrame {
+        let down = self.field_direction(position);
+        GravityFrame {
+            up: if self.flipped { down } else { -down },
+            // Left/right along the ground keep their on-screen sense when gravity flips
+            right: down.perp(),
+            strength: self.zone_at(position).map_or(1.0, |zone| zone.strength),
+        }
+    }
+}
+
+/// Basis aligned with gravity: `up` points away from gravity and `right` along the ground
+///
+/// The controller logic is written as if gravity pointed down; it works on vectors converted
+/// with `to_local` and converts the results back with `to_world`.
+#[derive(Clone, Copy, Debug)]
+pub struct GravityFrame {
+    pub up: Vec2,
+    pub right: Vec2,
+    /// Multiplier on the gravity strength (from gravity zones)
+    pub strength: f32,
+}
+
+impl GravityFrame {
+    /// Movement input expressed in the frame
+    ///
+    /// Input is rotated with the gravity field (so left/right walk around a planet) but not
+    /// mirrored by flips, which keeps Up/Down meaning up/down on screen for ladders.
+    pub fn input_to_local(&self, input: Vec2) -> Vec2 {
+        self.to_local(self.right * input.x + self.right.perp() * input.y)
+    }
+
+    /// World vector expressed as (along `right`, along `up`)
+    pub fn to_local(&self, world: Vec2) -> Vec2 {
+        Vec2::new(world.dot(self.right), world.dot(self.up))
+    }
+
+    /// Local (along `right`, along `up`) vector expressed in world space
+    pub fn to_world(&self, local: Vec2) -> Vec2 {
+        self.right * local.x + self.up * local.y
+    }
+}
+
+/// Levels always start with gravity pointing down
+pub fn s_reset_gravity(mut gravity: ResMut<Gravity>) {
+    gravity.flipped = false;
+}
+
+/// G flips gravity while the player is on the ground
+pub fn s_flip_gravity(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut gravity: ResMut<Gravity>,
+    mut player_query: Query<&mut Player>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+    let Ok(mut player_data) = player_query.single_mut() else {
+        return;
+    };
+
+    if player_data.grounded_timer > 0.0 {
+        gravity.flipped = !gravity.flipped;
+        // The old ground is now overhead: no coyote jump off it
+        player_data.grounded_timer = 0.0;
+        player_data.is_grounded = false;
+        player_data.ground_contact = false;
+        player_data.ground_edge = None;
+    }
+}
@@ -0,0 +1,214 @@
+use bevy::{input::ButtonInput, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    gamepad::s_gamepad_input, gravity::Gravity, schedule::ControllerPhase, InputDir, Physics,
+    Player, MAX_JUMP_TIMER,
+};
+
+// Minimum horizontal input that counts as a turn while auto-running
+const AUTO_RUN_TURN_THRESHOLD: f32 = 0.5;
+
+/// Alternative input modes, implemented as wrappers that rewrite the device input
+/// (`InputDir` and the player's input state) before `s_movement` reads it
+pub struct InputModesPlugin;
+
+impl Plugin for InputModesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutoRun>()
+            .insert_resource(OneButton {
+                enabled: false,
+                config: serde_json::from_slice(include_bytes!("../assets/input/one_button.json"))
+                    .expect("invalid one-button config"),
+                dash_used: false,
+            })
+            .add_systems(
+                Update,
+                s_auto_run
+                    .after(s_gamepad_input)
+                    .before(ControllerPhase::Movement),
+            )
+            .add_systems(
+                Update,
+                s_one_button
+                    .after(s_auto_run)
+                    .before(ControllerPhase::Movement),
+            );
+    }
+}
+
+/// Auto-run: the player always runs forward, horizontal input only turns them around
+#[derive(Resource)]
+pub struct AutoRun {
+    pub enabled: bool,
+    /// Current running direction (-1.0 left, 1.0 right)
+    pub facing: f32,
+}
+
+impl Default for AutoRun {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            facing: 1.0,
+        }
+    }
+}
+
+/// F2 toggles auto-run; while enabled, replaces the movement input with the facing direction
+///
+/// Tapping the opposite direction turns around. Wall jumps turn the player to face away from
+/// the wall in auto-run and one-button mode alike, since both run in the facing direction.
+pub fn s_auto_run(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut auto_run: ResMut<AutoRun>,
+    one_button: Res<OneButton>,
+    mut input_dir: ResMut<InputDir>,
+    gravity: Res<Gravity>,
+    player_query: Query<(&Transform, &Player, &Physics)>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F2) {
+        auto_run.enabled = !auto_run.enabled;
+    }
+    if !auto_run.enabled && !one_button.enabled {
+        return;
+    }
+
+    if auto_run.enabled && input_dir.dir.x.abs() >= AUTO_RUN_TURN_THRESHOLD {
+        auto_run.facing = input_dir.dir.x.signum();
+    }
+
+    if let Ok((player_transform, player_data, player_physics)) = player_query.single() {
+        // Facing is along the ground, so compare with the velocity in gravity's frame
+        let frame = gravity.frame_at(player_transform.translation.xy());
+        let lateral_velocity = frame.to_local(player_physics.velocity).x;
+        if player_data.has_wall_jumped && lateral_velocity * auto_run.facing < 0.0 {
+            auto_run.facing = -auto_run.facing;
+        }
+    }
+
+    if auto_run.enabled {
+        input_dir.dir = Vec2::new(auto_run.facing, 0.0);
+    }
+}
+
+/// Controller situation a one-button rule applies in
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum OneButtonContext {
+    /// On the ground (including coyote time)
+    Grounded,
+    /// Touching a wall (including wall coyote time)
+    OnWall,
+    /// Neither grounded nor on a wall
+    Airborne,
+    /// Always matches
+    Any,
+}
+
+/// What the button does in a context
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum OneButtonAction {
+    /// Jump (wall jump when on a wall)
+    Jump,
+    /// Burst forward in the running direction, once per airtime
+    Dash,
+    /// Do nothing
+    None,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct OneButtonRule {
+    pub context: OneButtonContext,
+    pub action: OneButtonAction,
+}
+
+/// One-button mode rules, loaded from `assets/input/one_button.json`
+#[derive(Deserialize, Clone)]
+pub struct OneButtonConfig {
+    /// Horizontal dash speed (pixels/second)
+    pub dash_speed: f32,
+    /// Rules checked in order; the first matching context decides the action
+    pub rules: Vec<OneButtonRule>,
+}
+
+/// One-button mode: the player auto-runs and a single button (Space / gamepad South)
+/// jumps, wall jumps or dashes depending on the context rules
+#[derive(Resource)]
+pub struct OneButton {
+    pub enabled: bool,
+    pub config: OneButtonConfig,
+    /// Whether the dash was used since last touching the ground or a wall
+    dash_used: bool,
+}
+
+impl OneButton {
+    /// Action of the first rule whose context holds
+    pub fn action(&self, grounded: bool, on_wall: bool) -> OneButtonAction {
+        self.config
+            .rules
+            .iter()
+            .find(|rule| match rule.context {
+                OneButtonContext::Grounded => grounded,
+                OneButtonContext::OnWall => on_wall,
+                OneButtonContext::Airborne => !grounded && !on_wall,
+                OneButtonContext::Any => true,
+            })
+            .map_or(OneButtonAction::None, |rule| rule.action)
+    }
+}
+
+/// F3 toggles one-button mode; while enabled, runs in the auto-run direction and replaces
+/// the jump input with the contextual action
+pub fn s_one_button(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    mut one_button: ResMut<OneButton>,
+    auto_run: Res<AutoRun>,
+    gravity: Res<Gravity>,
+    mut input_dir: ResMut<InputDir>,
+    mut player_query: Query<(&Transform, &mut Player, &mut Physics)>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        one_button.enabled = !one_button.enabled;
+    }
+    if !one_button.enabled {
+        return;
+    }
+
+    input_dir.dir = Vec2::new(auto_run.facing, 0.0);
+
+    let Ok((player_transform, mut player_data, mut player_physics)) = player_query.single_mut()
+    else {
+        return;
+    };
+
+    let grounded = player_data.grounded_timer > 0.0;
+    let on_wall = player_data.wall_timer > 0.0;
+    if grounded || on_wall {
+        one_button.dash_used = false;
+    }
+
+    let pressed = keyboard_input.just_pressed(KeyCode::Space)
+        || gamepad_query
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+    if !pressed {
+        return;
+    }
+
+    // The device layer buffered a jump for this press; the rules decide whether it stays
+    player_data.jump_timer = 0.0;
+    match one_button.action(grounded, on_wall) {
+        OneButtonAction::Jump => player_data.jump_timer = MAX_JUMP_TIMER,
+        OneButtonAction::Dash if !one_button.dash_used => {
+            let frame = gravity.frame_at(player_transform.translation.xy());
+            let mut velocity = frame.to_local(player_physics.velocity);
+            velocity.x = auto_run.facing * one_button.config.dash_speed;
+            velocity.y = velocity.y.max(0.0);
+            player_physics.velocity = frame.to_world(velocity);
+            one_button.dash_used = true;
+        }
+        OneButtonAction::Dash | OneButtonAction::None => {}
+    }
+}
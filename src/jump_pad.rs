@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use crate::{s_render, schedule::ControllerPhase, Physics, Player};
+
+// Jump pad constants
+const JUMP_PAD_RADIUS: f32 = 10.0;
+const JUMP_PAD_COLOR: Color = Color::srgb(0.2, 0.9, 0.4);
+// Time (seconds) after a launch during which releasing jump doesn't cut the velocity and the
+// pad can't fire again
+pub const LAUNCH_JUMP_CUT_LOCK: f32 = 0.3;
+
+/// Jump pads / springs: touching one launches the player with the pad's velocity
+pub struct JumpPadPlugin;
+
+impl Plugin for JumpPadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<JumpPadLaunch>()
+            .add_systems(
+                Update,
+                s_jump_pads
+                    .after(ControllerPhase::Input)
+                    .before(ControllerPhase::Movement),
+            )
+            .add_systems(Update, s_render_jump_pads.after(s_render));
+    }
+}
+
+/// Spring that launches the player on contact
+#[derive(Component)]
+pub struct JumpPad {
+    /// Launch velocity (pixels/second, world space)
+    pub velocity: Vec2,
+}
+
+/// A jump pad launched the player, for SFX/VFX
+#[derive(Message, Clone, Copy)]
+pub struct JumpPadLaunch {
+    /// World position of the pad
+    pub position: Vec2,
+    /// Launch velocity of the pad
+    pub velocity: Vec2,
+}
+
+/// Launches the player off the jump pads they touch
+///
+/// The velocity along the launch direction is replaced by the pad's; sideways momentum is
+/// kept. Like a jump, this leaves the ground, so no coyote jump follows.
+pub fn s_jump_pads(
+    pad_query: Query<(&Transform, &JumpPad)>,
+    mut player_query: Query<(&Transform, &mut Physics, &mut Player)>,
+    mut launch_writer: MessageWriter<JumpPadLaunch>,
+) {
+    let Ok((player_transform, mut player_physics, mut player_data)) = player_query.single_mut()
+    else {
+        return;
+    };
+    if player_data.launch_timer > 0.0 {
+        return;
+    }
+
+    let player_pos = player_transform.translation.xy();
+    for (pad_transform, pad) in &pad_query {
+        let pad_pos = pad_transform.translation.xy();
+        if pad_pos.distance(player_pos) > player_physics.radius + JUMP_PAD_RADIUS {
+            continue;
+        }
+
+        let Some(direction) = pad.velocity.try_normalize() else {
+            continue;
+        };
+        player_physics.velocity =
+            player_physics.velocity.reject_from_normalized(direction) + pad.velocity;
+
+        player_data.launch_timer = LAUNCH_JUMP_CUT_LOCK;
+        player_data.jumping = false;
+        player_data.jump_timer = 0.0;
+        player_data.grounded_timer = 0.0;
+
+        launch_writer.write(JumpPadLaunch {
+            position: pad_pos,
+            velocity: pad.velocity,
+        });
+        break;
+    }
+}
+
+/// Draws jump pads with their launch direction
+pub fn s_render_jump_pads(mut gizmos: Gizmos, pad_query: Query<(&Transform, &JumpPad)>) {
+    for (pad_transform, pad) in &pad_query {
+        let pad_pos = pad_transform.translation.xy();
+        gizmos.circle_2d(pad_pos, JUMP_PAD_RADIUS, JUMP_PAD_COLOR);
+
+        // Launch direction
+        let direction = pad.velocity.normalize_or_zero();
+        gizmos.line_2d(
+            pad_pos,
+            pad_pos + direction * JUMP_PAD_RADIUS * 2.0,
+            JUMP_PAD_COLOR,
+        );
+    }
+}
@@ -1,14 +1,51 @@
-use bevy::{color::Color, math::Vec2};
+use std::{collections::HashMap, f32::consts::TAU};
+
+use bevy::{
+    color::Color,
+    math::{IVec2, Vec2},
+};
 use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    collisions::{CollisionLayers, EdgeId},
+    geometry::closest_point_on_segment,
+    gravity::{GravityField, GravityZone},
+    moving_platform::CrushResponse,
+    phase_blocks::PhaseColor,
+    rect_points,
+    teleporter::TeleportVelocity,
+    EPSILON,
+};
+
+const ONE_WAY_COLOR: Color = Color::srgb(0.8, 0.7, 0.5);
+const CURVE_COLOR: Color = Color::srgb(0.6, 0.8, 0.6);
+const CARVED_COLOR: Color = Color::srgb(0.55, 0.5, 0.65);
+// Default largest gap (pixels) between a Bezier curve and the segments it is tessellated into
+const CURVE_TOLERANCE: f32 = 0.5;
+// Side of an edge grid cell (pixels)
+const EDGE_GRID_CELL_SIZE: f32 = 64.0;
+// Most edges in an edge BVH leaf
+const BVH_LEAF_SIZE: usize = 4;
+// Distance (pixels) within which edges of neighbouring polygons count as sharing a seam
+const WELD_TOLERANCE: f32 = 0.01;
 
 /// Axis-aligned bounding box for spatial optimization
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Aabb {
     pub min: Vec2,
     pub max: Vec2,
 }
 
 impl Aabb {
+    /// Create an AABB from two opposite corners in any order
+    pub fn from_corners(a: Vec2, b: Vec2) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
     /// Create an AABB from a point and radius (for player collision checks)
     pub fn from_point_radius(center: Vec2, radius: f32) -> Self {
         Self {
@@ -25,6 +62,11 @@ impl Aabb {
             && self.max.y >= other.min.y
     }
 
+    /// Check if a point lies inside this AABB
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
     /// Expand AABB by a given amount in all directions
     pub fn expand(&self, amount: f32) -> Self {
         Self {
@@ -32,23 +74,1492 @@ impl Aabb {
             max: self.max + Vec2::splat(amount),
         }
     }
+
+    /// Smallest AABB containing both AABBs
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Check if the segment from `start` to `end` passes through this AABB (slab test)
+    pub fn intersects_segment(&self, start: Vec2, end: Vec2) -> bool {
+        let delta = end - start;
+        let (mut t_min, mut t_max) = (0.0_f32, 1.0_f32);
+
+        for axis in 0..2 {
+            if delta[axis].abs() < f32::EPSILON {
+                if start[axis] < self.min[axis] || start[axis] > self.max[axis] {
+                    return false;
+                }
+                continue;
+            }
+
+            let (t0, t1) = (
+                (self.min[axis] - start[axis]) / delta[axis],
+                (self.max[axis] - start[axis]) / delta[axis],
+            );
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+            if t_min > t_max {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Uniform grid over the level's polygon edges, so collision only tests the edges near the
+/// player instead of every edge of every polygon
+#[derive(Default)]
+pub struct EdgeGrid {
+    cells: HashMap<IVec2, Vec<EdgeId>>,
+    /// Per polygon: the `generation` it was indexed at and the cells holding its edges
+    indexed: Vec<(u32, Vec<IVec2>)>,
+}
+
+impl EdgeGrid {
+    /// Cells covered by a bounding box
+    fn cells_in(aabb: &Aabb) -> impl Iterator<Item = IVec2> {
+        let min = (aabb.min / EDGE_GRID_CELL_SIZE).floor().as_ivec2();
+        let max = (aabb.max / EDGE_GRID_CELL_SIZE).floor().as_ivec2();
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+    }
+
+    /// Re-indexes polygons that were added, moved or reset since the last call, and forgets
+    /// removed ones
+    pub fn refresh(&mut self, polygons: &[Polygon]) {
+        while self.indexed.len() > polygons.len() {
+            self.remove_polygon(self.indexed.len() - 1);
+            self.indexed.pop();
+        }
+
+        for (polygon_index, polygon) in polygons.iter().enumerate() {
+            match self.indexed.get(polygon_index) {
+                Some((generation, _)) if *generation == polygon.generation => continue,
+                Some(_) => self.remove_polygon(polygon_index),
+                None => self.indexed.push((polygon.generation, Vec::new())),
+            }
+
+            let mut polygon_cells = Vec::new();
+            for i in 1..polygon.points.len() {
+                if polygon.is_internal_edge(i - 1) {
+                    continue;
+                }
+                let edge_aabb = Aabb::from_corners(polygon.points[i - 1], polygon.points[i]);
+                for cell in Self::cells_in(&edge_aabb) {
+                    self.cells.entry(cell).or_default().push(EdgeId {
+                        polygon: polygon_index,
+                        edge: i - 1,
+                    });
+                    polygon_cells.push(cell);
+                }
+            }
+            polygon_cells.sort_unstable_by_key(|cell| (cell.x, cell.y));
+            polygon_cells.dedup();
+            self.indexed[polygon_index] = (polygon.generation, polygon_cells);
+        }
+    }
+
+    /// Takes a polygon's edges out of the cells they were indexed in
+    fn remove_polygon(&mut self, polygon_index: usize) {
+        for cell in std::mem::take(&mut self.indexed[polygon_index].1) {
+            if let Some(edges) = self.cells.get_mut(&cell) {
+                edges.retain(|edge| edge.polygon != polygon_index);
+                if edges.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    /// Edges sharing a cell with the bounding box, each once, sorted by polygon then edge
+    /// (disabled polygons included, internal edges left out)
+    pub fn query(&self, aabb: &Aabb) -> Vec<EdgeId> {
+        let mut edges: Vec<EdgeId> = Self::cells_in(aabb)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        edges.sort_unstable_by_key(|edge| (edge.polygon, edge.edge));
+        edges.dedup();
+        edges
+    }
+}
+
+/// Node of an `EdgeBvh`
+enum BvhNode {
+    /// Range of `EdgeBvh::edges`
+    Leaf {
+        aabb: Aabb,
+        start: usize,
+        end: usize,
+    },
+    /// Indices of the two child nodes
+    Branch {
+        aabb: Aabb,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl BvhNode {
+    fn aabb(&self) -> &Aabb {
+        match self {
+            Self::Leaf { aabb, .. } | Self::Branch { aabb, .. } => aabb,
+        }
+    }
+}
+
+/// Bounding-volume hierarchy over the level's polygon edges, for box and ray queries
+///
+/// Built from the polygons as they are at load; polygons moved (`Polygon::generation` changed)
+/// or added since the last `build` are tested edge by edge instead, so queries stay exact
+/// without rebuilding for every moving platform.
+#[derive(Default)]
+pub struct EdgeBvh {
+    nodes: Vec<BvhNode>,
+    /// Edges and their bounding boxes, grouped by leaf
+    edges: Vec<(EdgeId, Aabb)>,
+    /// `Polygon::generation` of every polygon at build time
+    generations: Vec<u32>,
+}
+
+impl EdgeBvh {
+    /// Builds the hierarchy over every edge of the polygons but the internal ones
+    pub fn build(polygons: &[Polygon]) -> Self {
+        let mut edges: Vec<(EdgeId, Aabb)> = polygons
+            .iter()
+            .enumerate()
+            .flat_map(|(polygon_index, polygon)| {
+                polygon
+                    .points
+                    .windows(2)
+                    .enumerate()
+                    .filter(|(edge, _)| !polygon.is_internal_edge(*edge))
+                    .map(move |(edge, points)| {
+                        (
+                            EdgeId {
+                                polygon: polygon_index,
+                                edge,
+                            },
+                            Aabb::from_corners(points[0], points[1]),
+                        )
+                    })
+            })
+            .collect();
+
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            generations: polygons.iter().map(|polygon| polygon.generation).collect(),
+        };
+        if !edges.is_empty() {
+            bvh.build_node(&mut edges, 0);
+        }
+        bvh.edges = edges;
+        bvh
+    }
+
+    /// Number of polygons the hierarchy was built from
+    pub fn polygon_count(&self) -> usize {
+        self.generations.len()
+    }
+
+    /// Splits the edges at the median of the longest axis until leaves are small enough,
+    /// returning the node index
+    fn build_node(&mut self, edges: &mut [(EdgeId, Aabb)], offset: usize) -> usize {
+        let aabb = edges
+            .iter()
+            .skip(1)
+            .fold(edges[0].1, |aabb, (_, edge_aabb)| aabb.union(edge_aabb));
+        let index = self.nodes.len();
+
+        if edges.len() <= BVH_LEAF_SIZE {
+            self.nodes.push(BvhNode::Leaf {
+                aabb,
+                start: offset,
+                end: offset + edges.len(),
+            });
+            return index;
+        }
+
+        // Placeholder until the children exist
+        self.nodes.push(BvhNode::Leaf {
+            aabb,
+            start: offset,
+            end: offset,
+        });
+
+        let size = aabb.max - aabb.min;
+        let axis = if size.x >= size.y { 0 } else { 1 };
+        let mid = edges.len() / 2;
+        edges.select_nth_unstable_by(mid, |(_, a), (_, b)| {
+            (a.min[axis] + a.max[axis]).total_cmp(&(b.min[axis] + b.max[axis]))
+        });
+
+        let (left_edges, right_edges) = edges.split_at_mut(mid);
+        let left = self.build_node(left_edges, offset);
+        let right = self.build_node(right_edges, offset + mid);
+        self.nodes[index] = BvhNode::Branch { aabb, left, right };
+        index
+    }
+
+    /// Whether a polygon is indexed as it currently is
+    fn is_current(&self, polygon_index: usize, polygon: &Polygon) -> bool {
+        self.generations.get(polygon_index) == Some(&polygon.generation)
+    }
+
+    /// Edges whose bounding boxes pass `hit`, sorted by polygon then edge (disabled polygons
+    /// included)
+    fn query(&self, polygons: &[Polygon], hit: impl Fn(&Aabb) -> bool) -> Vec<EdgeId> {
+        let mut found = Vec::new();
+
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !hit(node.aabb()) {
+                continue;
+            }
+            match node {
+                BvhNode::Leaf { start, end, .. } => {
+                    found.extend(
+                        self.edges[*start..*end]
+                            .iter()
+                            .filter(|(edge, aabb)| {
+                                hit(aabb)
+                                    && polygons.get(edge.polygon).is_some_and(|polygon| {
+                                        self.is_current(edge.polygon, polygon)
+                                    })
+                            })
+                            .map(|(edge, _)| *edge),
+                    );
+                }
+                BvhNode::Branch { left, right, .. } => stack.extend([*left, *right]),
+            }
+        }
+
+        // Polygons moved or added since the build
+        for (polygon_index, polygon) in polygons.iter().enumerate() {
+            if self.is_current(polygon_index, polygon) || !hit(&polygon.aabb) {
+                continue;
+            }
+            found.extend(
+                polygon
+                    .points
+                    .windows(2)
+                    .enumerate()
+                    .filter(|(edge, points)| {
+                        !polygon.is_internal_edge(*edge)
+                            && hit(&Aabb::from_corners(points[0], points[1]))
+                    })
+                    .map(|(edge, _)| EdgeId {
+                        polygon: polygon_index,
+                        edge,
+                    }),
+            );
+        }
+
+        found.sort_unstable_by_key(|edge| (edge.polygon, edge.edge));
+        found
+    }
+
+    /// Edges whose bounding boxes overlap `aabb`
+    pub fn query_aabb(&self, polygons: &[Polygon], aabb: &Aabb) -> Vec<EdgeId> {
+        self.query(polygons, |edge_aabb| edge_aabb.overlaps(aabb))
+    }
+
+    /// Edges whose bounding boxes the segment from `start` to `end` passes through
+    pub fn query_segment(&self, polygons: &[Polygon], start: Vec2, end: Vec2) -> Vec<EdgeId> {
+        self.query(polygons, |edge_aabb| {
+            edge_aabb.intersects_segment(start, end)
+        })
+    }
+}
+
+/// What a level volume does to the player inside it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VolumeKind {
+    /// Up/down climbs with gravity disabled
+    Ladder,
+    /// Swimming with buoyancy and drag
+    Water,
+    /// Kills the player (lava, rising fluids)
+    Hazard,
+}
+
+/// Non-solid region of the level that changes how the player moves inside it
+pub struct Volume {
+    pub aabb: Aabb,
+    pub kind: VolumeKind,
+}
+
+impl Volume {
+    /// Fraction (0.0..=1.0) of a circle's height below the volume's top edge, 0.0 if the circle
+    /// doesn't overlap the volume
+    pub fn submerged_fraction(&self, center: Vec2, radius: f32) -> f32 {
+        if !Aabb::from_point_radius(center, radius).overlaps(&self.aabb) {
+            return 0.0;
+        }
+
+        ((self.aabb.max.y - (center.y - radius)) / (2.0 * radius)).clamp(0.0, 1.0)
+    }
+}
+
+/// Curved level edge: part of a circle, collided with through closest-point tests instead of
+/// being split into segments
+pub struct ArcEdge {
+    pub center: Vec2,
+    pub radius: f32,
+    /// Where the arc starts (radians counter-clockwise from +X)
+    pub start_angle: f32,
+    /// Counter-clockwise extent (radians, `TAU` for a full circle)
+    pub sweep: f32,
+    /// The player collides with the outside of the curve (round pillars, bumps) rather than
+    /// the inside (bowls, pipes)
+    pub convex: bool,
+    pub layers: CollisionLayers,
+}
+
+impl ArcEdge {
+    /// Point at `fraction` (0.0..=1.0) of the way along the arc
+    pub fn point_at(&self, fraction: f32) -> Vec2 {
+        self.center + Vec2::from_angle(self.start_angle + self.sweep * fraction) * self.radius
+    }
+
+    /// Closest point of the arc to `point`
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        let offset = point - self.center;
+        let angle = (offset.to_angle() - self.start_angle).rem_euclid(TAU);
+        if angle <= self.sweep {
+            return self.center
+                + offset.normalize_or(Vec2::from_angle(self.start_angle)) * self.radius;
+        }
+
+        // Outside the arc's angles: the nearer end
+        let (start, end) = (self.point_at(0.0), self.point_at(1.0));
+        if point.distance_squared(start) <= point.distance_squared(end) {
+            start
+        } else {
+            end
+        }
+    }
+
+    /// Whether `point` is on the side of the curve the player moves in
+    pub fn is_open_side(&self, point: Vec2) -> bool {
+        (point.distance_squared(self.center) > self.radius.powi(2)) == self.convex
+    }
+
+    /// Bounding box of the whole circle
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_point_radius(self.center, self.radius)
+    }
+
+    /// Whether `point` lies within the arc's angles, seen from the center
+    pub fn spans(&self, point: Vec2) -> bool {
+        ((point - self.center).to_angle() - self.start_angle).rem_euclid(TAU) <= self.sweep
+    }
+
+    /// Whether `point` is behind the curve, within the arc's angles (inside a pillar, outside a
+    /// bowl)
+    pub fn is_solid_side(&self, point: Vec2) -> bool {
+        !self.is_open_side(point) && self.spans(point)
+    }
+
+    /// Whether `point` is inside the solid disc of a full convex circle (partial arcs and
+    /// concave curves don't enclose a region)
+    pub fn encloses(&self, point: Vec2) -> bool {
+        self.convex
+            && self.sweep >= TAU
+            && point.distance_squared(self.center) < self.radius.powi(2)
+    }
+
+    /// Fraction (0.0..=1.0) of `motion` after which a point starting at `start` crosses the arc
+    pub fn ray_fraction(&self, start: Vec2, motion: Vec2) -> Option<f32> {
+        let (entering, leaving) = circle_crossings(start, motion, self.center, self.radius)?;
+        [entering, leaving]
+            .into_iter()
+            .find(|&t| (0.0..=1.0).contains(&t) && self.spans(start + motion * t))
+    }
+
+    /// Fraction (0.0..=1.0) of `motion` after which a circle starting at `start` first touches
+    /// the arc, if it does (circles already touching it are ignored, like
+    /// `swept_circle_time_of_impact`)
+    pub fn swept_circle_fraction(&self, start: Vec2, motion: Vec2, radius: f32) -> Option<f32> {
+        if self.closest_point(start).distance(start) <= radius {
+            return None;
+        }
+
+        // Curve: the center comes within `radius` of the circle, inside the arc's angles
+        let mut hits: Vec<f32> = Vec::new();
+        if let Some((entering, _)) =
+            circle_crossings(start, motion, self.center, self.radius + radius)
+        {
+            hits.push(entering);
+        }
+        if self.radius > radius {
+            if let Some((_, leaving)) =
+                circle_crossings(start, motion, self.center, self.radius - radius)
+            {
+                hits.push(leaving);
+            }
+        }
+        hits.retain(|&t| self.spans(start + motion * t));
+
+        // Ends: the circle reaches an end point
+        for end in [self.point_at(0.0), self.point_at(1.0)] {
+            if let Some((entering, _)) = circle_crossings(start, motion, end, radius) {
+                hits.push(entering);
+            }
+        }
+
+        hits.into_iter()
+            .filter(|t| (0.0..=1.0).contains(t))
+            .min_by(f32::total_cmp)
+    }
+}
+
+/// Fractions of `motion` at which a point starting at `start` crosses a circle, as (entering,
+/// leaving)
+fn circle_crossings(start: Vec2, motion: Vec2, center: Vec2, radius: f32) -> Option<(f32, f32)> {
+    let a = motion.length_squared();
+    if a <= EPSILON {
+        return None;
+    }
+    let offset = start - center;
+    let b = offset.dot(motion);
+    let discriminant = b.powi(2) - a * (offset.length_squared() - radius.powi(2));
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    Some(((-b - root) / a, (-b + root) / a))
+}
+
+/// Polyline the player can land on and grind along
+pub struct Rail {
+    pub points: Vec<Vec2>,
+    /// Distance (pixels) along the rail of each point
+    pub distances: Vec<f32>,
+}
+
+impl Rail {
+    pub fn new(points: Vec<Vec2>) -> Self {
+        let mut distances = vec![0.0];
+        for segment in points.windows(2) {
+            distances.push(distances[distances.len() - 1] + segment[0].distance(segment[1]));
+        }
+
+        Self { points, distances }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Point at `distance` along the rail and the rail's unit direction there
+    pub fn point_at(&self, distance: f32) -> (Vec2, Vec2) {
+        let segment = self
+            .distances
+            .windows(2)
+            .position(|pair| distance <= pair[1])
+            .unwrap_or(self.points.len().saturating_sub(2));
+        let (start, end) = (self.points[segment], self.points[segment + 1]);
+        let direction = (end - start).normalize_or_zero();
+
+        (
+            start + direction * (distance - self.distances[segment]),
+            direction,
+        )
+    }
+
+    /// Closest point of the rail to `point`, with its distance along the rail
+    pub fn closest_point(&self, point: Vec2) -> (Vec2, f32) {
+        self.points
+            .windows(2)
+            .enumerate()
+            .map(|(i, segment)| {
+                let closest = closest_point_on_segment(segment[0], segment[1], point);
+                (closest, self.distances[i] + segment[0].distance(closest))
+            })
+            .min_by(|a, b| {
+                a.0.distance_squared(point)
+                    .total_cmp(&b.0.distance_squared(point))
+            })
+            .unwrap_or((point, 0.0))
+    }
 }
 
 pub struct Polygon {
     pub points: Vec<Vec2>,
-    pub collision_side: f32,
+    /// `calculate_winding_order` sign of the outline
+    winding: f32,
+    /// Hole carved out of the polygon at this index: the player moves inside its outline
+    pub hole_of: Option<usize>,
     pub color: Color,
     /// Cached bounding box for spatial optimization
     pub aabb: Aabb,
+    /// Disabled polygons are skipped by collision and rendering (e.g. opened gates)
+    pub enabled: bool,
+    /// Surface of the edges without their own entry in `edge_materials`
+    pub material: SurfaceMaterial,
+    /// Per-edge surfaces overriding `material` (indexed like `EdgeId::edge`; empty when none)
+    pub edge_materials: Vec<Option<SurfaceMaterial>>,
+    /// Magnetic surface: the player sticks to it and walks along it at any angle, ceilings
+    /// included
+    pub sticky: bool,
+    /// One-way platform: only holds the player from above (while not rising or dropping
+    /// through)
+    pub one_way: bool,
+    /// Bodies whose `CollisionLayers` don't interact with these pass through the polygon
+    pub layers: CollisionLayers,
+    /// Edges covered by an opposite-facing edge of neighbouring geometry, inside the level's
+    /// solid (indexed like `EdgeId::edge`; empty when none), set by `mark_internal_edges` and
+    /// left out of the broad phase so seams don't catch the player
+    pub internal_edges: Vec<bool>,
+    /// Outline before the last `move_to` (empty for static polygons)
+    pub prev_points: Vec<Vec2>,
+    /// Bumped whenever the outline changes, so the edge grid knows to re-index it
+    pub generation: u32,
 }
 
-const LEVEL_DATA: &[u8] = include_bytes!("../assets/level.json");
+impl Polygon {
+    /// Create a polygon from a closed point list (last point equal to the first)
+    pub fn new(points: Vec<Vec2>, color: Color) -> Self {
+        let winding = calculate_winding_order(&points).signum();
+        let aabb = compute_polygon_aabb(&points);
 
-pub fn generate_level_polygons(grid_size: f32) -> Vec<Polygon> {
-    let mut rng = rand::rng();
+        Self {
+            points,
+            winding,
+            hole_of: None,
+            color,
+            aabb,
+            enabled: true,
+            material: SurfaceMaterial::default(),
+            edge_materials: Vec::new(),
+            sticky: false,
+            one_way: false,
+            layers: CollisionLayers::default(),
+            internal_edges: Vec::new(),
+            prev_points: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    /// Moves the outline, keeping the old one so collision can tell which side of a moving
+    /// edge the player was on
+    pub fn move_to(&mut self, points: Vec<Vec2>) {
+        self.prev_points = std::mem::replace(&mut self.points, points);
+        self.internal_edges.clear();
+        self.aabb = compute_polygon_aabb(&self.points);
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Puts the outline back without sweeping it (e.g. resets on respawn)
+    pub fn reset_to(&mut self, points: Vec<Vec2>) {
+        self.points = points;
+        self.internal_edges.clear();
+        self.prev_points.clear();
+        self.aabb = compute_polygon_aabb(&self.points);
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Surface of the edge from `points[edge]` to `points[edge + 1]`
+    pub fn edge_material(&self, edge: usize) -> &SurfaceMaterial {
+        self.edge_materials
+            .get(edge)
+            .and_then(Option::as_ref)
+            .unwrap_or(&self.material)
+    }
+
+    /// Gives the edges whose midpoint lies in `area` their own surface
+    pub fn set_edge_material(&mut self, area: &Aabb, material: &SurfaceMaterial) {
+        for edge in 0..self.points.len().saturating_sub(1) {
+            let midpoint = (self.points[edge] + self.points[edge + 1]) / 2.0;
+            if area.contains(midpoint) {
+                self.edge_materials.resize(self.points.len() - 1, None);
+                self.edge_materials[edge] = Some(material.clone());
+            }
+        }
+    }
+
+    /// Whether the edge from `points[edge]` to `points[edge + 1]` is a seam inside the solid
+    pub fn is_internal_edge(&self, edge: usize) -> bool {
+        self.internal_edges.get(edge).copied().unwrap_or(false)
+    }
+
+    /// Unit normal of the edge from `points[i - 1]` to `points[i]`, facing the side the player
+    /// collides from
+    fn outward_normal(&self, i: usize) -> Vec2 {
+        (self.points[i] - self.points[i - 1])
+            .normalize_or_zero()
+            .perp()
+            * self.collision_side()
+    }
 
-    let res = std::str::from_utf8(LEVEL_DATA);
-    let json_data: Vec<Vec<u32>> = serde_json::from_str(res.unwrap()).unwrap();
+    /// Whether a body with these layers collides with the polygon (enabled and interacting)
+    pub fn collides_with(&self, layers: &CollisionLayers) -> bool {
+        self.enabled && self.layers.interacts_with(layers)
+    }
+
+    /// Side of the edges (`side_of_line_detection`) the player collides from: outside the
+    /// outline for solid polygons, inside it for holes
+    pub fn collision_side(&self) -> f32 {
+        if self.hole_of.is_some() {
+            -self.winding
+        } else {
+            self.winding
+        }
+    }
+
+    /// The polygon split into convex pieces (`decompose_convex`) with the same surface
+    /// properties
+    pub fn convex_pieces(&self) -> Vec<Polygon> {
+        decompose_convex(&self.points)
+            .into_iter()
+            .map(|points| Polygon {
+                material: self.material.clone(),
+                sticky: self.sticky,
+                one_way: self.one_way,
+                layers: self.layers,
+                enabled: self.enabled,
+                ..Polygon::new(points, self.color)
+            })
+            .collect()
+    }
+
+    /// Edge from `points[i - 1]` to `points[i]` as it was before the last move
+    pub fn prev_edge(&self, i: usize) -> (Vec2, Vec2) {
+        let points = if self.prev_points.len() == self.points.len() {
+            &self.prev_points
+        } else {
+            &self.points
+        };
+        (points[i - 1], points[i])
+    }
+}
+
+/// Surface properties of a polygon edge, for movement (friction), collision (restitution) and
+/// gameplay (footstep sounds, damage floors) to read
+#[derive(Clone, Debug, PartialEq)]
+pub struct SurfaceMaterial {
+    /// Multiplier on braking and turning while standing on it (1.0 normal, lower is slippery)
+    pub friction: f32,
+    /// Fraction of the impact speed bounced back on contact (0.0 stops, 1.0 fully elastic)
+    pub restitution: f32,
+    /// Gameplay label, e.g. "grass" or "spikes"
+    pub tag: Option<String>,
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        Self {
+            friction: 1.0,
+            restitution: 0.0,
+            tag: None,
+        }
+    }
+}
+
+/// Level file contents: the tile grid plus optional entity definitions
+#[derive(Deserialize, Default)]
+pub struct LevelData {
+    pub tiles: Vec<Vec<u32>>,
+    #[serde(default)]
+    pub signs: Vec<SignData>,
+    #[serde(default)]
+    pub keys: Vec<KeyData>,
+    #[serde(default)]
+    pub gates: Vec<GateData>,
+    #[serde(default)]
+    pub ice: Vec<IceData>,
+    #[serde(default)]
+    pub bouncers: Vec<BouncerData>,
+    #[serde(default)]
+    pub phase_blocks: Vec<PhaseBlockData>,
+    /// Time (seconds) between automatic red/blue phase switches
+    #[serde(default)]
+    pub phase_interval: Option<f32>,
+    #[serde(default)]
+    pub sticky_surfaces: Vec<AreaData>,
+    #[serde(default)]
+    pub one_way_platforms: Vec<AreaData>,
+    /// Walls only enemies collide with
+    #[serde(default)]
+    pub enemy_walls: Vec<AreaData>,
+    #[serde(default)]
+    pub surface_materials: Vec<SurfaceMaterialData>,
+    #[serde(default)]
+    pub breakables: Vec<BreakableData>,
+    #[serde(default)]
+    pub crumbling: Vec<CrumblingData>,
+    #[serde(default)]
+    pub pushables: Vec<AreaData>,
+    #[serde(default)]
+    pub snow: Vec<AreaData>,
+    #[serde(default)]
+    pub rails: Vec<RailData>,
+    #[serde(default)]
+    pub circles: Vec<CircleData>,
+    #[serde(default)]
+    pub arcs: Vec<ArcData>,
+    #[serde(default)]
+    pub curves: Vec<CurveData>,
+    /// Largest gap (pixels) between a curve and its tessellated segments
+    #[serde(default)]
+    pub curve_tolerance: Option<f32>,
+    #[serde(default)]
+    pub carved: Vec<CarvedData>,
+    /// Split the level polygons into convex pieces at load
+    #[serde(default)]
+    pub convex_decomposition: bool,
+    #[serde(default)]
+    pub checkpoints: Vec<CheckpointData>,
+    #[serde(default)]
+    pub coins: Vec<CoinData>,
+    #[serde(default)]
+    pub shops: Vec<ShopData>,
+    #[serde(default)]
+    pub swing_anchors: Vec<SwingAnchorData>,
+    #[serde(default)]
+    pub ladders: Vec<LadderData>,
+    #[serde(default)]
+    pub water: Vec<WaterData>,
+    /// Static areas that kill the player (spikes, pits)
+    #[serde(default)]
+    pub hazards: Vec<AreaData>,
+    #[serde(default)]
+    pub level_shifts: Vec<LevelShiftData>,
+    #[serde(default)]
+    pub moving_platforms: Vec<MovingPlatformData>,
+    #[serde(default)]
+    pub bosses: Vec<BossData>,
+    #[serde(default)]
+    pub rising_fluids: Vec<RisingFluidData>,
+    #[serde(default)]
+    pub portals: Vec<[PortalData; 2]>,
+    #[serde(default)]
+    pub teleporters: Vec<TeleporterData>,
+    #[serde(default)]
+    pub triggers: Vec<TriggerData>,
+    #[serde(default)]
+    pub jump_pads: Vec<JumpPadData>,
+    #[serde(default)]
+    pub boosters: Vec<BoosterData>,
+    #[serde(default)]
+    pub force_zones: Vec<ForceZoneData>,
+    #[serde(default)]
+    pub time_bubbles: Vec<TimeBubbleData>,
+    #[serde(default)]
+    pub autoscroll: Vec<AutoscrollData>,
+    #[serde(default)]
+    pub gravity: GravityData,
+    #[serde(default)]
+    pub gravity_zones: Vec<GravityZoneData>,
+    /// Player spawn position in tile coordinates
+    #[serde(default)]
+    pub spawn: Option<[f32; 2]>,
+    /// Level exit position in tile coordinates
+    #[serde(default)]
+    pub goal: Option<[f32; 2]>,
+    #[serde(default)]
+    pub medals: MedalThresholds,
+}
+
+/// Sign/NPC definition: dialogue pages shown when the player interacts nearby
+#[derive(Deserialize, Clone)]
+pub struct SignData {
+    /// Position in tile coordinates (column, row from the top-left)
+    pub tile: [f32; 2],
+    pub pages: Vec<String>,
+}
+
+/// Key pickup definition
+#[derive(Deserialize, Clone)]
+pub struct KeyData {
+    /// Id of the gate(s) this key opens
+    pub id: String,
+    /// Position in tile coordinates
+    pub tile: [f32; 2],
+}
+
+/// Locked gate definition: a solid rectangle removed once the matching key is collected
+#[derive(Deserialize, Clone)]
+pub struct GateData {
+    /// Id of the key that opens this gate
+    pub id: String,
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+}
+
+/// Ice definition: a slippery solid rectangle
+#[derive(Deserialize, Clone)]
+pub struct IceData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    /// Multiplier on braking and turning while standing on it (defaults to `ICE_FRICTION`)
+    #[serde(default)]
+    pub friction: Option<f32>,
+}
+
+/// Bouncer definition: a springy solid rectangle (trampolines, bumpers)
+#[derive(Deserialize, Clone)]
+pub struct BouncerData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    /// Fraction of the impact speed bounced back (0.0 stops, 1.0 fully elastic)
+    pub restitution: f32,
+}
+
+/// Phase block definition: a solid rectangle only while its phase is active
+#[derive(Deserialize, Clone)]
+pub struct PhaseBlockData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    pub phase: PhaseColor,
+}
+
+/// Break-on-land definition: a solid rectangle removed the first time the player lands on it
+/// hard enough
+#[derive(Deserialize, Clone)]
+pub struct BreakableData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    /// Minimum landing speed (pixels/second) that breaks it
+    pub impact_threshold: f32,
+}
+
+/// Crumbling platform definition: a solid rectangle that falls away after being stood on
+#[derive(Deserialize, Clone)]
+pub struct CrumblingData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    /// Time (seconds) it shakes before falling away
+    #[serde(default)]
+    pub delay: Option<f32>,
+    /// Time (seconds) before it comes back
+    #[serde(default)]
+    pub respawn: Option<f32>,
+}
+
+/// Swing anchor definition: a point the player can latch onto and swing from
+#[derive(Deserialize, Clone)]
+pub struct SwingAnchorData {
+    /// Position in tile coordinates
+    pub tile: [f32; 2],
+}
+
+/// Ladder definition: a climbable rectangle
+#[derive(Deserialize, Clone)]
+pub struct LadderData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+}
+
+/// Water definition: a swimmable rectangle whose top edge is the surface
+#[derive(Deserialize, Clone)]
+pub struct WaterData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+}
+
+/// Coin pickup definition
+#[derive(Deserialize, Clone)]
+pub struct CoinData {
+    /// Position in tile coordinates
+    pub tile: [f32; 2],
+}
+
+/// Shop definition: sells the listed catalog items when the player interacts nearby
+#[derive(Deserialize, Clone)]
+pub struct ShopData {
+    /// Position in tile coordinates
+    pub tile: [f32; 2],
+    /// Ids of the items on sale (see `SHOP_CATALOG`)
+    pub items: Vec<String>,
+}
+
+/// Checkpoint definition: touching it moves the spawn point there
+#[derive(Deserialize, Clone)]
+pub struct CheckpointData {
+    /// Position in tile coordinates (at the start of the platform's path for attached ones)
+    pub tile: [f32; 2],
+    /// Index in `moving_platforms` of the platform the checkpoint rides
+    #[serde(default)]
+    pub platform: Option<usize>,
+}
+
+/// Round pillar definition: a solid circle
+#[derive(Deserialize, Clone)]
+pub struct CircleData {
+    /// Center in tile coordinates
+    pub center: [f32; 2],
+    /// Radius in tiles
+    pub radius: f32,
+}
+
+/// Arc edge definition: part of a circle the player collides with
+#[derive(Deserialize, Clone)]
+pub struct ArcData {
+    /// Center in tile coordinates
+    pub center: [f32; 2],
+    /// Radius in tiles
+    pub radius: f32,
+    /// Degrees counter-clockwise from +X
+    pub start_angle: f32,
+    /// Degrees counter-clockwise from +X, greater than `start_angle` (at most a full turn later)
+    pub end_angle: f32,
+    /// Collide with the outside of the curve (bumps) instead of the inside (bowls, pipes)
+    #[serde(default)]
+    pub convex: bool,
+}
+
+/// Solid shape outlined by straight and Bezier segments, tessellated into a polygon at load
+#[derive(Deserialize, Clone)]
+pub struct CurveData {
+    /// First point in tile coordinates
+    pub start: [f32; 2],
+    /// Segments in order; the outline closes back to `start`
+    pub segments: Vec<CurveSegmentData>,
+}
+
+/// Segment of a `CurveData` outline
+#[derive(Deserialize, Clone)]
+pub struct CurveSegmentData {
+    /// End point in tile coordinates
+    pub to: [f32; 2],
+    /// Bezier control points in tile coordinates: none for a straight line, one for a
+    /// quadratic curve, two for a cubic
+    #[serde(default)]
+    pub controls: Vec<[f32; 2]>,
+}
+
+/// Solid polygon with holes carved out of it (e.g. a cave inside a block)
+#[derive(Deserialize, Clone)]
+pub struct CarvedData {
+    /// Outer boundary in tile coordinates (closed automatically)
+    pub outline: Vec<[f32; 2]>,
+    /// Hole boundaries in tile coordinates (closed automatically)
+    #[serde(default)]
+    pub holes: Vec<Vec<[f32; 2]>>,
+}
+
+/// Grind rail definition
+#[derive(Deserialize, Clone)]
+pub struct RailData {
+    /// Polyline in tile coordinates
+    pub points: Vec<[f32; 2]>,
+}
+
+/// Teleporter pair definition: entering either area moves the player to the other
+#[derive(Deserialize, Clone)]
+pub struct TeleporterData {
+    pub a: AreaData,
+    pub b: AreaData,
+    #[serde(default)]
+    pub velocity: TeleportVelocity,
+}
+
+/// Surface material definition: the polygon edges whose midpoint lies in the area get the
+/// material, keeping their polygon's friction and restitution where unset
+#[derive(Deserialize, Clone)]
+pub struct SurfaceMaterialData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    #[serde(default)]
+    pub friction: Option<f32>,
+    #[serde(default)]
+    pub restitution: Option<f32>,
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// Rectangular area of the level
+#[derive(Deserialize, Clone)]
+pub struct AreaData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+}
+
+/// Rising lava/water definition: a hazard whose surface climbs from the top of `min`..`max`
+/// up to row `top`
+#[derive(Deserialize, Clone)]
+pub struct RisingFluidData {
+    /// Top-left corner of the starting region in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner of the starting region in tile coordinates
+    pub max: [f32; 2],
+    /// Highest surface row in tile coordinates
+    pub top: f32,
+    /// Rising speed (pixels/second)
+    pub speed: f32,
+    /// Time (seconds) before the fluid starts rising on its own
+    #[serde(default)]
+    pub delay: Option<f32>,
+    /// Entering this area starts the rise; without a trigger or delay the fluid rises at once
+    #[serde(default)]
+    pub trigger: Option<AreaData>,
+}
+
+/// Level shift definition: solid outlines moved together by a scripted event (collapsing
+/// floors, tilting rooms)
+#[derive(Deserialize, Clone)]
+pub struct LevelShiftData {
+    /// Closed outlines (last point equal to the first) in tile coordinates
+    pub polygons: Vec<Vec<[f32; 2]>>,
+    /// Rotation center in tile coordinates
+    #[serde(default)]
+    pub pivot: [f32; 2],
+    /// Translation at the end of the shift, in tiles (+y down, like tile coordinates)
+    #[serde(default)]
+    pub offset: [f32; 2],
+    /// Rotation at the end of the shift (degrees, counterclockwise)
+    #[serde(default)]
+    pub rotation: f32,
+    /// Length of the shift (seconds)
+    pub duration: f32,
+    /// Time (seconds) before the shift starts on its own
+    #[serde(default)]
+    pub delay: Option<f32>,
+    /// Entering this area starts the shift; without a trigger or delay it starts at once
+    #[serde(default)]
+    pub trigger: Option<AreaData>,
+}
+
+/// Moving platform definition: a solid rectangle travelling along a path and/or rotating
+#[derive(Deserialize, Clone)]
+pub struct MovingPlatformData {
+    /// Top-left corner at the start of the path, in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner at the start of the path, in tile coordinates
+    pub max: [f32; 2],
+    /// Later positions of the top-left corner, in tile coordinates
+    #[serde(default)]
+    pub path: Vec<[f32; 2]>,
+    /// Speed along the path (pixels/second)
+    #[serde(default)]
+    pub speed: f32,
+    /// Whether the platform returns from the last point to the start (otherwise it runs back
+    /// along the path)
+    #[serde(default)]
+    pub looped: bool,
+    /// Rotation center at the start of the path in tile coordinates (defaults to the center)
+    #[serde(default)]
+    pub pivot: Option<[f32; 2]>,
+    /// Rotation speed (degrees/second, counterclockwise)
+    #[serde(default)]
+    pub angular_speed: f32,
+    /// What happens when the platform squeezes the player against other geometry
+    #[serde(default)]
+    pub crush: CrushResponse,
+}
+
+/// Boss definition: a moving platform body stomped to deal damage, fought in phases
+#[derive(Deserialize, Clone)]
+pub struct BossData {
+    /// Name shown above the HP bar
+    pub name: String,
+    /// Body of the boss (its speed is set by the phases)
+    pub body: MovingPlatformData,
+    /// Entering this area starts the fight
+    pub arena: AreaData,
+    /// Hit points (one per stomp)
+    pub hp: u32,
+    /// Phases in order of decreasing HP thresholds
+    pub phases: Vec<BossPhaseData>,
+}
+
+/// Stage of a boss fight
+#[derive(Deserialize, Clone)]
+pub struct BossPhaseData {
+    /// The phase starts once the boss's HP drops to this
+    pub hp: u32,
+    /// Speed of the body along its path (pixels/second)
+    #[serde(default)]
+    pub speed: f32,
+    /// Solid rectangles that only exist during this phase
+    #[serde(default)]
+    pub walls: Vec<AreaData>,
+}
+
+/// One side of a portal pair: a segment entered from the side it faces
+#[derive(Deserialize, Clone)]
+pub struct PortalData {
+    /// Segment start in tile coordinates
+    pub start: [f32; 2],
+    /// Segment end in tile coordinates
+    pub end: [f32; 2],
+    /// Facing direction (world space, +y up)
+    pub facing: [f32; 2],
+}
+
+/// Trigger definition: a sensor volume reporting bodies entering and leaving it
+#[derive(Deserialize, Clone)]
+pub struct TriggerData {
+    /// Name gameplay code matches on
+    pub id: String,
+    pub shape: TriggerShapeData,
+}
+
+/// Shape of a trigger, in tile coordinates
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerShapeData {
+    Box {
+        /// Top-left corner
+        min: [f32; 2],
+        /// Bottom-right corner
+        max: [f32; 2],
+    },
+    Circle {
+        center: [f32; 2],
+        /// Radius in tiles
+        radius: f32,
+    },
+    /// Outline points (closed automatically)
+    Polygon(Vec<[f32; 2]>),
+}
+
+/// Jump pad definition: a spring launching the player on contact
+#[derive(Deserialize, Clone)]
+pub struct JumpPadData {
+    /// Position in tile coordinates
+    pub tile: [f32; 2],
+    /// Launch velocity (pixels/second, world space, +y up)
+    pub velocity: [f32; 2],
+}
+
+/// Boost ring / dash pad definition
+#[derive(Deserialize, Clone)]
+pub struct BoosterData {
+    /// Position in tile coordinates
+    pub tile: [f32; 2],
+    /// Boost direction (world space, +y up; normalized on spawn)
+    pub direction: [f32; 2],
+    /// Speed the player is set to (pixels/second)
+    pub speed: f32,
+    /// Time (seconds) the player can't steer after the boost
+    #[serde(default)]
+    pub lockout: f32,
+}
+
+/// Wind/force-field zone definition: a rectangle pushing the player inside it
+#[derive(Deserialize, Clone)]
+pub struct ForceZoneData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    /// Acceleration inside (pixels/second², world space, +y up)
+    pub acceleration: [f32; 2],
+}
+
+/// Timescale bubble definition: a rectangle where time runs at its own speed
+#[derive(Deserialize, Clone)]
+pub struct TimeBubbleData {
+    /// Top-left corner in tile coordinates
+    pub min: [f32; 2],
+    /// Bottom-right corner in tile coordinates
+    pub max: [f32; 2],
+    /// Delta time multiplier inside (e.g. 0.5 for a slow zone, 1.5 for a haste zone)
+    pub scale: f32,
+}
+
+/// Autoscroll camera segment definition: a checkpoint starting a scroll between two markers
+#[derive(Deserialize, Clone)]
+pub struct AutoscrollData {
+    /// Checkpoint area starting the segment
+    pub trigger: AreaData,
+    /// Camera center at the start of the scroll, in tile coordinates
+    pub start: [f32; 2],
+    /// Camera center at the end of the scroll, in tile coordinates
+    pub end: [f32; 2],
+    /// Scroll speed (pixels/second)
+    pub speed: f32,
+}
+
+/// Level gravity: straight down unless the level overrides it
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GravityData {
+    #[default]
+    Down,
+    /// Uniform gravity along a direction in world space (+y up)
+    Direction([f32; 2]),
+    /// Radial gravity towards a point in tile coordinates
+    Center([f32; 2]),
+}
+
+/// Gravity zone definition: overrides gravity while the player is inside the outline
+#[derive(Deserialize, Clone)]
+pub struct GravityZoneData {
+    /// Outline in tile coordinates
+    pub points: Vec<[f32; 2]>,
+    /// Direction gravity pulls inside (world space, +y up); the level's gravity if omitted
+    #[serde(default)]
+    pub direction: Option<[f32; 2]>,
+    /// Gravity strength multiplier inside (1.0 if omitted)
+    #[serde(default)]
+    pub strength: Option<f32>,
+}
+
+/// Medal thresholds for completing a level (completion itself always earns one medal)
+#[derive(Deserialize, Clone, Default)]
+pub struct MedalThresholds {
+    /// Completion time (seconds) at or under which the time medal is earned
+    #[serde(default)]
+    pub time: Option<f32>,
+    /// Number of coins that must have been collected for the collectibles medal
+    #[serde(default)]
+    pub collectibles: Option<usize>,
+}
+
+/// Level files are either a bare tile grid or a full `LevelData` object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LevelFile {
+    Grid(Vec<Vec<u32>>),
+    Data(LevelData),
+}
+
+impl LevelData {
+    /// Convert a position in tile coordinates to world space
+    pub fn tile_to_world(&self, tile: [f32; 2], grid_size: f32) -> Vec2 {
+        let offset = Vec2::new(
+            self.tiles[0].len() as f32 * -grid_size / 2.0,
+            self.tiles.len() as f32 * grid_size / 2.0,
+        );
+
+        Vec2::new(
+            tile[0] * grid_size + offset.x,
+            -tile[1] * grid_size + offset.y,
+        )
+    }
+
+    /// Player spawn position in world space
+    pub fn spawn_position(&self, grid_size: f32) -> Vec2 {
+        self.spawn.map_or(Vec2::new(0.0, -50.0), |spawn| {
+            self.tile_to_world(spawn, grid_size)
+        })
+    }
+
+    /// Ladder, water and hazard volumes in world space
+    pub fn volumes(&self, grid_size: f32) -> Vec<Volume> {
+        let ladders = self
+            .ladders
+            .iter()
+            .map(|ladder| (ladder.min, ladder.max, VolumeKind::Ladder));
+        let water = self
+            .water
+            .iter()
+            .map(|water| (water.min, water.max, VolumeKind::Water));
+        let hazards = self
+            .hazards
+            .iter()
+            .map(|hazard| (hazard.min, hazard.max, VolumeKind::Hazard));
+
+        ladders
+            .chain(water)
+            .chain(hazards)
+            .map(|(min, max, kind)| Volume {
+                aabb: Aabb::from_corners(
+                    self.tile_to_world(min, grid_size),
+                    self.tile_to_world(max, grid_size),
+                ),
+                kind,
+            })
+            .collect()
+    }
+
+    /// Rails in world space (rails need at least two points)
+    pub fn rails(&self, grid_size: f32) -> Vec<Rail> {
+        self.rails
+            .iter()
+            .filter(|rail| rail.points.len() >= 2)
+            .map(|rail| {
+                Rail::new(
+                    rail.points
+                        .iter()
+                        .map(|&point| self.tile_to_world(point, grid_size))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Circles and arcs in world space
+    pub fn arc_edges(&self, grid_size: f32) -> Vec<ArcEdge> {
+        let circles = self.circles.iter().map(|circle| ArcEdge {
+            center: self.tile_to_world(circle.center, grid_size),
+            radius: circle.radius * grid_size,
+            start_angle: 0.0,
+            sweep: TAU,
+            convex: true,
+            layers: CollisionLayers::default(),
+        });
+        let arcs = self.arcs.iter().map(|arc| ArcEdge {
+            center: self.tile_to_world(arc.center, grid_size),
+            radius: arc.radius * grid_size,
+            start_angle: arc.start_angle.to_radians(),
+            sweep: (arc.end_angle - arc.start_angle)
+                .to_radians()
+                .clamp(0.0, TAU),
+            convex: arc.convex,
+            layers: CollisionLayers::default(),
+        });
+
+        circles.chain(arcs).collect()
+    }
+
+    /// Curve outlines tessellated into polygons in world space
+    pub fn curve_polygons(&self, grid_size: f32) -> Vec<Polygon> {
+        let tolerance = self.curve_tolerance.unwrap_or(CURVE_TOLERANCE);
+
+        self.curves
+            .iter()
+            .filter(|curve| !curve.segments.is_empty())
+            .map(|curve| {
+                let start = self.tile_to_world(curve.start, grid_size);
+                let mut points = vec![start];
+                for segment in &curve.segments {
+                    let mut control = vec![points[points.len() - 1]];
+                    control.extend(
+                        segment
+                            .controls
+                            .iter()
+                            .map(|&point| self.tile_to_world(point, grid_size)),
+                    );
+                    control.push(self.tile_to_world(segment.to, grid_size));
+                    points.extend(tessellate_bezier(&control, tolerance));
+                }
+                if points[points.len() - 1] != start {
+                    points.push(start);
+                }
+
+                Polygon::new(points, CURVE_COLOR)
+            })
+            .collect()
+    }
+
+    /// Carved polygons in world space, each outline followed by its holes, for polygons
+    /// starting at index `first_index` of `Level::polygons`
+    pub fn carved_polygons(&self, grid_size: f32, first_index: usize) -> Vec<Polygon> {
+        let ring = |points: &[[f32; 2]]| {
+            let mut ring: Vec<Vec2> = points
+                .iter()
+                .map(|&point| self.tile_to_world(point, grid_size))
+                .collect();
+            ring.push(ring[0]);
+            ring
+        };
+
+        let mut polygons = Vec::new();
+        for carved in self
+            .carved
+            .iter()
+            .filter(|carved| carved.outline.len() >= 3)
+        {
+            let outer = first_index + polygons.len();
+            polygons.push(Polygon::new(ring(&carved.outline), CARVED_COLOR));
+            for hole in carved.holes.iter().filter(|hole| hole.len() >= 3) {
+                polygons.push(Polygon {
+                    hole_of: Some(outer),
+                    ..Polygon::new(ring(hole), CARVED_COLOR)
+                });
+            }
+        }
+        polygons
+    }
+
+    /// One-way platform polygons in world space
+    pub fn one_way_polygons(&self, grid_size: f32) -> Vec<Polygon> {
+        self.one_way_platforms
+            .iter()
+            .map(|platform| {
+                let min = self.tile_to_world(platform.min, grid_size);
+                let max = self.tile_to_world(platform.max, grid_size);
+                let mut polygon = Polygon::new(rect_points(min, max), ONE_WAY_COLOR);
+                polygon.one_way = true;
+                polygon.layers.memberships = CollisionLayers::ONE_WAY;
+                polygon
+            })
+            .collect()
+    }
+
+    /// Gravity zones in world space
+    pub fn gravity_zones(&self, grid_size: f32) -> Vec<GravityZone> {
+        self.gravity_zones
+            .iter()
+            .map(|zone| {
+                let points: Vec<Vec2> = zone
+                    .points
+                    .iter()
+                    .map(|&point| self.tile_to_world(point, grid_size))
+                    .collect();
+
+                GravityZone {
+                    aabb: compute_polygon_aabb(&points),
+                    points,
+                    direction: zone.direction.map(Vec2::from),
+                    strength: zone.strength.unwrap_or(1.0),
+                }
+            })
+            .collect()
+    }
+
+    /// Gravity field in world space
+    pub fn gravity_field(&self, grid_size: f32) -> GravityField {
+        match self.gravity {
+            GravityData::Down => GravityField::default(),
+            GravityData::Direction(direction) => GravityField::Uniform(Vec2::from(direction)),
+            GravityData::Center(center) => {
+                GravityField::Radial(self.tile_to_world(center, grid_size))
+            }
+        }
+    }
+}
+
+pub fn load_level_data(bytes: &[u8]) -> LevelData {
+    let res = std::str::from_utf8(bytes);
+
+    match serde_json::from_str(res.unwrap()).unwrap() {
+        LevelFile::Grid(tiles) => LevelData {
+            tiles,
+            ..Default::default()
+        },
+        LevelFile::Data(level_data) => level_data,
+    }
+}
+
+pub fn generate_level_polygons(json_data: &[Vec<u32>], grid_size: f32) -> Vec<Polygon> {
+    let mut rng = rand::rng();
 
     let offset = Vec2::new(
         json_data[0].len() as f32 * -grid_size / 2.0,
@@ -401,24 +1912,14 @@ pub fn generate_level_polygons(grid_size: f32) -> Vec<Polygon> {
             }
         }
 
-        let collision_side = calculate_winding_order(&polygon_lines).signum();
-
         let color = Color::srgb(
             rng.random_range(0.0..=1.0),
             rng.random_range(0.0..=1.0),
             rng.random_range(0.0..=1.0),
         );
 
-        // Compute bounding box for spatial optimization
-        let aabb = compute_polygon_aabb(&polygon_lines);
-
         // Add the polygon to the list of polygons
-        polygons.push(Polygon {
-            points: polygon_lines,
-            collision_side,
-            color,
-            aabb,
-        });
+        polygons.push(Polygon::new(polygon_lines, color));
     }
 
     polygons
@@ -436,8 +1937,237 @@ fn calculate_winding_order(vertices: &[Vec2]) -> f32 {
     sum
 }
 
+/// Points along a Bezier curve (any degree) after its first control point, spaced so no
+/// segment strays more than `tolerance` from the curve (Wang's formula)
+pub fn tessellate_bezier(control: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let degree = control.len().saturating_sub(1);
+    if degree < 2 {
+        return control.iter().skip(1).copied().collect();
+    }
+
+    let max_second_difference = control
+        .windows(3)
+        .map(|points| (points[0] - 2.0 * points[1] + points[2]).length())
+        .fold(0.0, f32::max);
+    let factor = (degree * (degree - 1)) as f32 / 8.0;
+    let segments = (factor * max_second_difference / tolerance.max(EPSILON))
+        .sqrt()
+        .ceil()
+        .max(1.0) as usize;
+
+    (1..=segments)
+        .map(|i| {
+            // De Casteljau
+            let t = i as f32 / segments as f32;
+            let mut points = control.to_vec();
+            while points.len() > 1 {
+                points = points
+                    .windows(2)
+                    .map(|pair| pair[0].lerp(pair[1], t))
+                    .collect();
+            }
+            points[0]
+        })
+        .collect()
+}
+
+/// Splits a simple polygon outline (closed, last point equal to the first) into convex
+/// outlines (closed, counter-clockwise): ear clipping into triangles, then merging neighbours
+/// back together while the result stays convex (Hertel-Mehlhorn)
+pub fn decompose_convex(points: &[Vec2]) -> Vec<Vec<Vec2>> {
+    let mut ring = points.to_vec();
+    if ring.len() > 1 && ring[0] == ring[ring.len() - 1] {
+        ring.pop();
+    }
+    if ring.len() < 3 {
+        return vec![points.to_vec()];
+    }
+    // Counter-clockwise, so convex corners turn left
+    if calculate_winding_order(&ring) > 0.0 {
+        ring.reverse();
+    }
+
+    let corner = |a: usize, b: usize, c: usize| (ring[b] - ring[a]).perp_dot(ring[c] - ring[b]);
+
+    // Ear clipping
+    let mut remaining: Vec<usize> = (0..ring.len()).collect();
+    let mut pieces: Vec<Vec<usize>> = Vec::new();
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let neighbours = |i: usize| {
+            (
+                remaining[(i + count - 1) % count],
+                remaining[(i + 1) % count],
+            )
+        };
+
+        // Collinear points add nothing to the outline
+        if let Some(i) = (0..count).find(|&i| {
+            let (previous, next) = neighbours(i);
+            corner(previous, remaining[i], next).abs() <= EPSILON
+        }) {
+            remaining.remove(i);
+            continue;
+        }
+
+        let ear = (0..count).find(|&i| {
+            let (previous, next) = neighbours(i);
+            let (a, b, c) = (ring[previous], ring[remaining[i]], ring[next]);
+            corner(previous, remaining[i], next) > 0.0
+                && remaining.iter().all(|&other| {
+                    let point = ring[other];
+                    point == a || point == b || point == c || !point_in_triangle(point, a, b, c)
+                })
+        });
+        // Not a simple polygon: keep what is left as one piece
+        let Some(i) = ear else {
+            break;
+        };
+
+        let (previous, next) = neighbours(i);
+        pieces.push(vec![previous, remaining[i], next]);
+        remaining.remove(i);
+    }
+    pieces.push(remaining);
+
+    // Merge pieces sharing an edge while the union stays convex
+    'merge: loop {
+        for a in 0..pieces.len() {
+            for b in a + 1..pieces.len() {
+                if let Some(merged) = merge_convex(&pieces[a], &pieces[b], &ring) {
+                    pieces[a] = merged;
+                    pieces.swap_remove(b);
+                    continue 'merge;
+                }
+            }
+        }
+        break;
+    }
+
+    pieces
+        .into_iter()
+        .map(|piece| {
+            let mut outline: Vec<Vec2> = piece.iter().map(|&i| ring[i]).collect();
+            outline.push(outline[0]);
+            outline
+        })
+        .collect()
+}
+
+/// Union of two counter-clockwise pieces (indices into `ring`) sharing an edge, if it is
+/// convex
+fn merge_convex(a: &[usize], b: &[usize], ring: &[Vec2]) -> Option<Vec<usize>> {
+    // `a` has the shared edge as (u, v), `b` as (v, u)
+    let (a_start, b_start) = (0..a.len()).find_map(|i| {
+        let (u, v) = (a[i], a[(i + 1) % a.len()]);
+        (0..b.len())
+            .find(|&j| b[j] == v && b[(j + 1) % b.len()] == u)
+            .map(|j| (i + 1, j + 1))
+    })?;
+
+    // From v around `a` to u, then the rest of `b` from after u back to before v
+    let mut merged: Vec<usize> = (0..a.len()).map(|k| a[(a_start + k) % a.len()]).collect();
+    merged.extend((1..b.len() - 1).map(|k| b[(b_start + k) % b.len()]));
+
+    let count = merged.len();
+    let convex = (0..count).all(|i| {
+        let (previous, point, next) = (
+            ring[merged[(i + count - 1) % count]],
+            ring[merged[i]],
+            ring[merged[(i + 1) % count]],
+        );
+        (point - previous).perp_dot(next - point) >= -EPSILON
+    });
+    convex.then_some(merged)
+}
+
+/// Whether a point lies inside or on a counter-clockwise triangle
+fn point_in_triangle(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(point - a) >= 0.0
+        && (c - b).perp_dot(point - b) >= 0.0
+        && (a - c).perp_dot(point - c) >= 0.0
+}
+
+/// Marks the edges of solid polygons lying along an opposite-facing edge of another polygon (or
+/// of the same one) over their whole length as internal
+///
+/// Such edges are seams inside the level's solid (tiles meeting a curve, convex pieces, an
+/// outline touching itself), whose end points would hand a player running across the seam
+/// spurious wall normals.
+pub fn mark_internal_edges(polygons: &mut [Polygon]) {
+    let bvh = EdgeBvh::build(polygons);
+    let mut internal: Vec<Vec<bool>> = Vec::with_capacity(polygons.len());
+
+    for (polygon_index, polygon) in polygons.iter().enumerate() {
+        let mut flags = vec![false; polygon.points.len().saturating_sub(1)];
+
+        for i in 1..polygon.points.len() {
+            let (start, end) = (polygon.points[i - 1], polygon.points[i]);
+            let length = start.distance(end);
+            if polygon.one_way || length < WELD_TOLERANCE {
+                continue;
+            }
+            let direction = (end - start) / length;
+            let normal = polygon.outward_normal(i);
+            let edge_id = EdgeId {
+                polygon: polygon_index,
+                edge: i - 1,
+            };
+
+            // Stretches of the edge (distances from `start`) covered by facing edges
+            let mut covered: Vec<(f32, f32)> = bvh
+                .query_aabb(
+                    polygons,
+                    &Aabb::from_corners(start, end).expand(WELD_TOLERANCE),
+                )
+                .into_iter()
+                .filter(|&other| other != edge_id)
+                .filter_map(|other| {
+                    let other_polygon = &polygons[other.polygon];
+                    if other_polygon.one_way || other_polygon.layers != polygon.layers {
+                        return None;
+                    }
+                    let j = other.edge + 1;
+                    let (other_start, other_end) =
+                        (other_polygon.points[j - 1], other_polygon.points[j]);
+                    let collinear = [other_start, other_end]
+                        .iter()
+                        .all(|&point| (point - start).dot(normal).abs() <= WELD_TOLERANCE);
+                    let facing = other_polygon.outward_normal(j).dot(normal) < 0.0;
+
+                    (collinear && facing).then(|| {
+                        let a = (other_start - start).dot(direction);
+                        let b = (other_end - start).dot(direction);
+                        (a.min(b), a.max(b))
+                    })
+                })
+                .collect();
+            covered.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut reached = 0.0;
+            for (from, to) in covered {
+                if from > reached + WELD_TOLERANCE {
+                    break;
+                }
+                reached = f32::max(reached, to);
+            }
+            flags[i - 1] = reached >= length - WELD_TOLERANCE;
+        }
+
+        internal.push(flags);
+    }
+
+    for (polygon, flags) in polygons.iter_mut().zip(internal) {
+        polygon.internal_edges = if flags.contains(&true) {
+            flags
+        } else {
+            Vec::new()
+        };
+    }
+}
+
 /// Compute axis-aligned bounding box for a polygon
-fn compute_polygon_aabb(points: &[Vec2]) -> Aabb {
+pub fn compute_polygon_aabb(points: &[Vec2]) -> Aabb {
     if points.is_empty() {
         return Aabb {
             min: Vec2::ZERO,
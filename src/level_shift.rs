@@ -0,0 +1,152 @@
+use bevy::{math::Affine2, prelude::*};
+
+use crate::{
+    death::{s_respawn, PlayerRespawned},
+    schedule::ControllerPhase,
+    Level, Player,
+};
+
+pub const LEVEL_SHIFT_COLOR: Color = Color::srgb(0.8, 0.6, 0.4);
+
+/// Scripted level shifts (collapsing floors, tilting rooms): polygon groups translated and
+/// rotated over time, carrying the player standing on them
+pub struct LevelShiftPlugin;
+
+impl Plugin for LevelShiftPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, s_level_shifts.before(ControllerPhase::Input))
+            .add_systems(Update, s_reset_level_shifts.after(s_respawn));
+    }
+}
+
+/// Group of `Level` polygons moved together by a scripted event
+#[derive(Component)]
+pub struct LevelShift {
+    /// Indices of the moved polygons in `Level::polygons`
+    pub polygons: Vec<usize>,
+    /// Outlines of the polygons before the shift
+    pub base_points: Vec<Vec<Vec2>>,
+    /// Rotation center before the shift (world space)
+    pub pivot: Vec2,
+    /// Translation at the end of the shift (pixels)
+    pub offset: Vec2,
+    /// Rotation at the end of the shift (radians, counterclockwise)
+    pub rotation: f32,
+    /// Length of the shift (seconds)
+    pub duration: f32,
+    /// Time (seconds) before the shift starts on its own (`None` waits for the trigger)
+    pub delay: Option<f32>,
+    /// Entering this area starts the shift
+    pub trigger: Option<Rect>,
+    /// Time (seconds) since the level started or the player respawned
+    pub elapsed: f32,
+    /// Time (seconds) since the shift started, if it has
+    pub progress: Option<f32>,
+}
+
+impl LevelShift {
+    /// Transform from the original outlines after `time` seconds of shifting
+    ///
+    /// Eased in and out, so the geometry starts and stops smoothly.
+    pub fn transform_at(&self, time: f32) -> Affine2 {
+        let t = (time / self.duration.max(f32::EPSILON)).clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+
+        Affine2::from_translation(self.pivot + self.offset * t)
+            * Affine2::from_angle(self.rotation * t)
+            * Affine2::from_translation(-self.pivot)
+    }
+
+    /// Current transform from the original outlines
+    pub fn transform(&self) -> Affine2 {
+        self.transform_at(self.progress.unwrap_or(0.0))
+    }
+}
+
+/// Starts shifts on their delay or trigger, moves their polygons and carries the player
+/// standing on them
+///
+/// Runs before the controller so collision resolves against this frame's geometry.
+pub fn s_level_shifts(
+    time: Res<Time>,
+    mut level: ResMut<Level>,
+    mut player_query: Query<(&mut Transform, &Player)>,
+    mut shift_query: Query<&mut LevelShift>,
+) {
+    let dt = time.delta_secs();
+    let mut player = player_query.single_mut().ok();
+
+    for mut shift in &mut shift_query {
+        shift.elapsed += dt;
+
+        // Finished shifts stay where they ended, and stop counting as moving
+        if shift
+            .progress
+            .is_some_and(|progress| progress >= shift.duration)
+        {
+            for &polygon_index in &shift.polygons {
+                level.polygons[polygon_index].prev_points.clear();
+            }
+            continue;
+        }
+
+        if shift.progress.is_none() {
+            let delay_over = shift.delay.is_some_and(|delay| shift.elapsed >= delay);
+            let triggered = shift.trigger.zip(player.as_ref()).is_some_and(
+                |(trigger, (player_transform, ..))| {
+                    trigger.contains(player_transform.translation.xy())
+                },
+            );
+            if !delay_over && !triggered {
+                continue;
+            }
+        }
+
+        let previous = shift.transform();
+        let progress = shift.progress.map_or(0.0, |progress| progress + dt);
+        shift.progress = Some(progress);
+        let current = shift.transform();
+
+        for (&polygon_index, base_points) in shift.polygons.iter().zip(&shift.base_points) {
+            level.polygons[polygon_index].move_to(
+                base_points
+                    .iter()
+                    .map(|&point| current.transform_point2(point))
+                    .collect(),
+            );
+        }
+
+        // Carry the player standing on the group along with it
+        if let Some((player_transform, player_data)) = &mut player {
+            let standing_on_group = player_data
+                .ground_edge
+                .is_some_and(|edge_id| shift.polygons.contains(&edge_id.polygon));
+
+            if standing_on_group {
+                let carry = current * previous.inverse();
+                let position = carry.transform_point2(player_transform.translation.xy());
+                player_transform.translation = position.extend(player_transform.translation.z);
+            }
+        }
+    }
+}
+
+/// Puts shifted geometry back when the player respawns
+pub fn s_reset_level_shifts(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    mut level: ResMut<Level>,
+    mut shift_query: Query<&mut LevelShift>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    for mut shift in &mut shift_query {
+        shift.elapsed = 0.0;
+        shift.progress = None;
+
+        for (&polygon_index, base_points) in shift.polygons.iter().zip(&shift.base_points) {
+            level.polygons[polygon_index].reset_to(base_points.clone());
+        }
+    }
+}
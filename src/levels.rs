@@ -0,0 +1,472 @@
+use bevy::{input::ButtonInput, prelude::*};
+
+use crate::{
+    assist::AssistMode,
+    level::{load_level_data, MedalThresholds},
+    progression::{Progress, ProgressSave},
+    s_render,
+    save::{RunRecord, SaveData},
+    schedule::ControllerSystems,
+    Physics, Player,
+};
+
+// Level manager constants
+pub const GOAL_RADIUS: f32 = 10.0;
+const GOAL_COLOR: Color = Color::srgb(0.2, 1.0, 0.4);
+const WORLD_MAP_MARGIN: f32 = 24.0;
+const WORLD_MAP_PADDING: f32 = 12.0;
+const MAP_NODE_RADIUS: f32 = 12.0;
+const MAP_ICON_RADIUS: f32 = 6.0;
+const MAP_MEDAL_RADIUS: f32 = 3.0;
+// Speed (pixels/second) of the player icon along world map paths
+const MAP_ICON_SPEED: f32 = 240.0;
+// Minimum dot product between the input and a path direction to follow that path
+const MAP_PATH_DIRECTION_THRESHOLD: f32 = 0.5;
+const MAP_PATH_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+const MAP_LOCKED_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const MAP_MEDAL_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+/// Level manager: level catalog, level loading per `GameState`, goals, medals and the world map
+pub struct LevelManagerPlugin;
+
+impl Plugin for LevelManagerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .init_resource::<LevelManager>()
+            .init_resource::<WorldMap>()
+            .add_systems(OnEnter(GameState::Playing), s_reset_level_timer)
+            .add_systems(OnExit(GameState::Playing), s_despawn_level)
+            .add_systems(OnEnter(GameState::WorldMap), s_spawn_world_map)
+            .add_systems(OnExit(GameState::WorldMap), s_despawn_world_map)
+            .add_systems(
+                Update,
+                (s_level_timer, s_open_world_map).run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                s_reach_goal
+                    .after(ControllerSystems)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (s_world_map, s_render_world_map.after(s_world_map))
+                    .run_if(in_state(GameState::WorldMap)),
+            )
+            .add_systems(Update, s_render_goals.after(s_render));
+    }
+}
+
+/// A level in the catalog
+pub struct LevelEntry {
+    /// Stable id used in the save file
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Index into `WORLDS`
+    pub world: usize,
+    /// Embedded level JSON
+    pub data: &'static [u8],
+    /// Position of the level's node on the world map
+    pub map_position: Vec2,
+}
+
+/// A group of levels gated behind a medal count
+pub struct WorldEntry {
+    pub name: &'static str,
+    /// Total medals needed to play this world's levels
+    pub medals_required: u32,
+}
+
+pub const WORLDS: &[WorldEntry] = &[WorldEntry {
+    name: "World 1",
+    medals_required: 0,
+}];
+
+pub const LEVELS: &[LevelEntry] = &[LevelEntry {
+    id: "intro",
+    name: "Intro",
+    world: 0,
+    data: include_bytes!("../assets/levels/intro.json"),
+    map_position: Vec2::new(-200.0, 0.0),
+}];
+
+/// Paths between world map nodes, as pairs of indices into `LEVELS`
+pub const WORLD_MAP_PATHS: &[(usize, usize)] = &[];
+
+/// Top-level game state
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    /// Save slot selection at startup
+    #[default]
+    SlotSelect,
+    /// A level is loaded and simulated
+    Playing,
+    /// Overworld: the player icon walks between level nodes
+    WorldMap,
+}
+
+/// Currently selected level and the running level time
+#[derive(Resource, Default)]
+pub struct LevelManager {
+    /// Index into `LEVELS`
+    pub current: usize,
+    /// Time (seconds) spent in the current level attempt
+    pub elapsed: f32,
+    /// Whether assist mode was used during the current attempt
+    pub assisted: bool,
+}
+
+impl LevelManager {
+    pub fn current_level(&self) -> &'static LevelEntry {
+        &LEVELS[self.current]
+    }
+}
+
+/// Marker for entities despawned when leaving a level
+#[derive(Component)]
+pub struct LevelEntity;
+
+/// Level exit: reaching it completes the level and awards medals
+#[derive(Component)]
+pub struct Goal {
+    pub medals: MedalThresholds,
+    /// Number of coins in the level
+    pub coin_count: usize,
+}
+
+/// World map navigation state
+#[derive(Resource, Default)]
+pub struct WorldMap {
+    /// Current position of the player icon
+    pub icon_position: Vec2,
+    /// Level node the icon is walking to, if any
+    pub target: Option<usize>,
+    /// Medals available per level, computed once from the level data
+    max_medals: Vec<u32>,
+}
+
+/// Marker for the world map UI root
+#[derive(Component)]
+pub struct WorldMapUi;
+
+/// Marker for the world map text
+#[derive(Component)]
+pub struct WorldMapText;
+
+/// Medals earned for a completion (one for finishing, plus one per threshold met)
+pub fn medals_earned(thresholds: &MedalThresholds, time: f32, collectibles: usize) -> u32 {
+    let mut medals = 1;
+    if thresholds.time.is_some_and(|max_time| time <= max_time) {
+        medals += 1;
+    }
+    if thresholds
+        .collectibles
+        .is_some_and(|required| collectibles >= required)
+    {
+        medals += 1;
+    }
+    medals
+}
+
+/// Best medals summed over all levels
+pub fn total_medals(save_data: &SaveData) -> u32 {
+    save_data.medals.values().sum()
+}
+
+/// Whether the world's medal requirement is met
+pub fn world_unlocked(world: usize, save_data: &SaveData) -> bool {
+    total_medals(save_data) >= WORLDS[world].medals_required
+}
+
+/// Levels directly connected to the given level by a world map path
+pub fn map_neighbors(level: usize) -> impl Iterator<Item = usize> {
+    WORLD_MAP_PATHS.iter().filter_map(move |&(a, b)| {
+        if a == level {
+            Some(b)
+        } else if b == level {
+            Some(a)
+        } else {
+            None
+        }
+    })
+}
+
+/// Coins of a level collected so far
+pub fn collected_coins(level: &LevelEntry, save_data: &SaveData) -> usize {
+    let prefix = format!("{}:", level.id);
+    save_data
+        .collected_coins
+        .iter()
+        .filter(|id| id.starts_with(&prefix))
+        .count()
+}
+
+pub fn s_reset_level_timer(mut level_manager: ResMut<LevelManager>, assist: Res<AssistMode>) {
+    level_manager.elapsed = 0.0;
+    level_manager.assisted = assist.enabled;
+}
+
+pub fn s_level_timer(
+    time: Res<Time>,
+    assist: Res<AssistMode>,
+    mut level_manager: ResMut<LevelManager>,
+    mut progress: ResMut<Progress>,
+) {
+    level_manager.elapsed += time.delta_secs();
+    progress.0.stats.play_time += time.delta_secs();
+    level_manager.assisted |= assist.enabled;
+}
+
+/// Despawns everything belonging to the level being left
+pub fn s_despawn_level(
+    mut commands: Commands,
+    level_entity_query: Query<Entity, With<LevelEntity>>,
+) {
+    for entity in &level_entity_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// M opens the world map
+pub fn s_open_world_map(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        next_state.set(GameState::WorldMap);
+    }
+}
+
+/// Completes the level when the player reaches the goal, saving the best medal count
+pub fn s_reach_goal(
+    level_manager: Res<LevelManager>,
+    mut save: ProgressSave,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+    goal_query: Query<(&Transform, &Goal)>,
+) {
+    let Ok((player_transform, player_physics)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.xy();
+
+    for (goal_transform, goal) in &goal_query {
+        if goal_transform.translation.xy().distance(player_pos)
+            > player_physics.radius + GOAL_RADIUS
+        {
+            continue;
+        }
+
+        let level = level_manager.current_level();
+        let collectibles = collected_coins(level, &save.progress.0).min(goal.coin_count);
+        let medals = medals_earned(&goal.medals, level_manager.elapsed, collectibles);
+
+        save.progress.0.stats.completions += 1;
+        let best = save
+            .progress
+            .0
+            .medals
+            .entry(level.id.to_string())
+            .or_insert(0);
+        *best = (*best).max(medals);
+
+        let record = RunRecord {
+            time: level_manager.elapsed,
+            assisted: level_manager.assisted,
+        };
+        if save
+            .progress
+            .0
+            .records
+            .get(level.id)
+            .is_none_or(|best| record.beats(best))
+        {
+            save.progress.0.records.insert(level.id.to_string(), record);
+        }
+        save.store();
+
+        next_state.set(GameState::WorldMap);
+        return;
+    }
+}
+
+/// Places the player icon on the current level's node and spawns the info panel
+pub fn s_spawn_world_map(
+    mut commands: Commands,
+    level_manager: Res<LevelManager>,
+    mut world_map: ResMut<WorldMap>,
+) {
+    world_map.icon_position = level_manager.current_level().map_position;
+    world_map.target = None;
+    if world_map.max_medals.is_empty() {
+        world_map.max_medals = LEVELS
+            .iter()
+            .map(|level| medals_earned(&load_level_data(level.data).medals, 0.0, usize::MAX))
+            .collect();
+    }
+
+    commands
+        .spawn((
+            WorldMapUi,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(WORLD_MAP_MARGIN),
+                top: Val::Px(WORLD_MAP_MARGIN),
+                padding: UiRect::all(Val::Px(WORLD_MAP_PADDING)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(""), WorldMapText));
+        });
+}
+
+pub fn s_despawn_world_map(mut commands: Commands, ui_query: Query<Entity, With<WorldMapUi>>) {
+    for entity in &ui_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// World map: arrow keys walk the icon along paths to neighboring levels, Enter plays the
+/// level under the icon if its world is unlocked
+pub fn s_world_map(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut level_manager: ResMut<LevelManager>,
+    mut world_map: ResMut<WorldMap>,
+    save: ProgressSave,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut text_query: Query<&mut Text, With<WorldMapText>>,
+) {
+    if let Some(target) = world_map.target {
+        // Walk towards the target node
+        let target_position = LEVELS[target].map_position;
+        let step = MAP_ICON_SPEED * time.delta_secs();
+        let to_target = target_position - world_map.icon_position;
+
+        if to_target.length() <= step {
+            world_map.icon_position = target_position;
+            world_map.target = None;
+            level_manager.current = target;
+        } else {
+            world_map.icon_position += to_target.normalize() * step;
+        }
+    } else {
+        let mut input_dir = Vec2::ZERO;
+        if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+            input_dir.y += 1.0;
+        }
+        if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+            input_dir.y -= 1.0;
+        }
+        if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+            input_dir.x -= 1.0;
+        }
+        if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+            input_dir.x += 1.0;
+        }
+        let input_dir = input_dir.normalize_or_zero();
+
+        // Follow the path best matching the input, skipping levels in locked worlds
+        let current_position = level_manager.current_level().map_position;
+        world_map.target = map_neighbors(level_manager.current)
+            .filter(|&neighbor| world_unlocked(LEVELS[neighbor].world, &save.progress.0))
+            .map(|neighbor| {
+                let path_dir =
+                    (LEVELS[neighbor].map_position - current_position).normalize_or_zero();
+                (neighbor, path_dir.dot(input_dir))
+            })
+            .filter(|(_, alignment)| *alignment >= MAP_PATH_DIRECTION_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(neighbor, _)| neighbor);
+
+        let selected = level_manager.current_level();
+        if world_map.target.is_none()
+            && keyboard_input.just_pressed(KeyCode::Enter)
+            && world_unlocked(selected.world, &save.progress.0)
+        {
+            next_state.set(GameState::Playing);
+        }
+
+        // Back to the save slots
+        if keyboard_input.just_pressed(KeyCode::Backspace) {
+            save.store();
+            next_state.set(GameState::SlotSelect);
+        }
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        let level = level_manager.current_level();
+        let world = &WORLDS[level.world];
+        let medals = save.progress.0.medals.get(level.id).copied().unwrap_or(0);
+
+        let mut map_text = format!("World Map - {} medals\n", total_medals(&save.progress.0));
+        map_text.push_str(&format!(
+            "{}: {} - medals {}/{}\n",
+            world.name, level.name, medals, world_map.max_medals[level_manager.current]
+        ));
+        if let Some(record) = save.progress.0.records.get(level.id) {
+            let assisted = if record.assisted { " (assisted)" } else { "" };
+            map_text.push_str(&format!("Best time: {:.2}s{}\n", record.time, assisted));
+        }
+        if !world_unlocked(level.world, &save.progress.0) {
+            map_text.push_str(&format!(
+                "Locked: {} medals needed\n",
+                world.medals_required
+            ));
+        }
+        map_text.push_str("Arrows: move, Enter: play, Backspace: save slots");
+        text.0 = map_text;
+    }
+}
+
+/// Draws the world map paths, level nodes with their medals and the player icon
+pub fn s_render_world_map(mut gizmos: Gizmos, world_map: Res<WorldMap>, progress: Res<Progress>) {
+    for &(a, b) in WORLD_MAP_PATHS {
+        gizmos.line_2d(
+            LEVELS[a].map_position,
+            LEVELS[b].map_position,
+            MAP_PATH_COLOR,
+        );
+    }
+
+    for (level_index, level) in LEVELS.iter().enumerate() {
+        let medals = progress.0.medals.get(level.id).copied().unwrap_or(0);
+        let node_color = if !world_unlocked(level.world, &progress.0) {
+            MAP_LOCKED_COLOR
+        } else if medals > 0 {
+            GOAL_COLOR
+        } else {
+            Color::WHITE
+        };
+        gizmos.circle_2d(level.map_position, MAP_NODE_RADIUS, node_color);
+
+        // One pip per available medal under the node, earned ones highlighted
+        let max_medals = world_map.max_medals[level_index];
+        let pip_spacing = MAP_MEDAL_RADIUS * 3.0;
+        let first_pip_x = -pip_spacing * (max_medals.saturating_sub(1)) as f32 / 2.0;
+        for medal in 0..max_medals {
+            let pip_position = level.map_position
+                + Vec2::new(
+                    first_pip_x + pip_spacing * medal as f32,
+                    -MAP_NODE_RADIUS - pip_spacing,
+                );
+            let pip_color = if medal < medals {
+                MAP_MEDAL_COLOR
+            } else {
+                MAP_LOCKED_COLOR
+            };
+            gizmos.circle_2d(pip_position, MAP_MEDAL_RADIUS, pip_color);
+        }
+    }
+
+    gizmos.circle_2d(world_map.icon_position, MAP_ICON_RADIUS, Color::WHITE);
+}
+
+/// Draws level goals
+pub fn s_render_goals(mut gizmos: Gizmos, goal_query: Query<&Transform, With<Goal>>) {
+    for goal_transform in &goal_query {
+        gizmos.circle_2d(goal_transform.translation.xy(), GOAL_RADIUS, GOAL_COLOR);
+    }
+}
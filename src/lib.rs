@@ -0,0 +1,2428 @@
+pub mod ascii_level;
+pub mod assist;
+pub mod boost;
+pub mod boss;
+pub mod breakable;
+pub mod camera;
+pub mod checkpoint;
+pub mod collisions;
+pub mod config;
+pub mod config_warnings;
+pub mod crumbling;
+pub mod death;
+pub mod decals;
+pub mod dialogue;
+pub mod feedback;
+pub mod gamepad;
+pub mod geometry;
+pub mod grapple;
+pub mod gravity;
+pub mod input_modes;
+pub mod jump_pad;
+pub mod level;
+pub mod level_shift;
+pub mod levels;
+pub mod moving_platform;
+pub mod noise;
+pub mod phase_blocks;
+#[cfg(feature = "physics-avian")]
+pub mod physics_avian;
+#[cfg(feature = "test_utils")]
+pub mod playtest;
+pub mod portal;
+pub mod progression;
+pub mod pushable;
+pub mod rising_fluid;
+pub mod save;
+pub mod schedule;
+pub mod shop;
+pub mod slots;
+pub mod snapshot;
+pub mod teleporter;
+#[cfg(feature = "test_utils")]
+pub mod test_utils;
+pub mod time_bubble;
+pub mod triggers;
+pub mod wind;
+
+use ::bevy::prelude::*;
+use assist::{AssistMode, AssistPlugin};
+use bevy::{app::AppExit, input::ButtonInput, math::Isometry2d};
+use boost::{BoostPlugin, Booster};
+use boss::{Boss, BossPhase, BossPlugin, BossState, BOSS_COLOR, BOSS_WALL_COLOR};
+use breakable::{Breakable, BreakablePlugin, BREAKABLE_COLOR};
+use camera::{AutoscrollSegment, CameraPlugin};
+use checkpoint::{Checkpoint, CheckpointPlugin};
+use collisions::{
+    point_in_polygon, swept_circle_time_of_impact, CollisionLayers, CollisionPlugin, Contact,
+    EdgeId, RayHit, ShapeHit,
+};
+use config::{ControllerConfig, JumpCut, SprintAirBehavior};
+use config_warnings::ConfigWarningsPlugin;
+use crumbling::{
+    CrumbleState, Crumbling, CrumblingPlugin, CRUMBLING_COLOR, DEFAULT_CRUMBLE_DELAY,
+    DEFAULT_CRUMBLE_RESPAWN,
+};
+use death::{DeathPlugin, SpawnPoint};
+use decals::{DecalPlugin, SnowPatch};
+use dialogue::{DialoguePlugin, Sign};
+use feedback::FeedbackPlugin;
+use gamepad::GamepadPlugin;
+use geometry::{closest_point_on_segment, line_intersect};
+use grapple::{GrapplePlugin, SwingAnchor};
+use gravity::{Gravity, GravityPlugin};
+use input_modes::InputModesPlugin;
+use jump_pad::{JumpPad, JumpPadPlugin};
+use level::{
+    generate_level_polygons, load_level_data, mark_internal_edges, Aabb, ArcEdge, EdgeBvh,
+    EdgeGrid, LevelData, MovingPlatformData, Polygon, Rail, SurfaceMaterial, TriggerShapeData,
+    Volume, VolumeKind,
+};
+use level_shift::{LevelShift, LevelShiftPlugin, LEVEL_SHIFT_COLOR};
+use levels::{GameState, Goal, LevelEntity, LevelManager, LevelManagerPlugin};
+use moving_platform::{MovingPlatform, MovingPlatformPlugin, MOVING_PLATFORM_COLOR};
+use noise::NoisePlugin;
+use phase_blocks::{apply_phase, PhaseBlock, PhaseBlocksPlugin, PhaseColor, PhaseSwitch};
+use portal::{PortalEnd, PortalPair, PortalPlugin};
+use progression::{Coin, Gate, KeyPickup, ProgressionPlugin, GATE_COLOR};
+use pushable::{Pushable, PushablePlugin, PUSHABLE_COLOR};
+use rising_fluid::{RisingFluid, RisingFluidPlugin, RISING_FLUID_COLOR};
+use schedule::ControllerSystems;
+use shop::{Shop, ShopPlugin};
+use slots::SlotsPlugin;
+use snapshot::SnapshotPlugin;
+use teleporter::{TeleporterPair, TeleporterPlugin};
+use time_bubble::{TimeBubble, TimeBubblePlugin};
+use triggers::{Trigger, TriggerPlugin, TriggerShape};
+use wind::{ForceZone, WindPlugin};
+
+// Floating point comparison epsilon
+const EPSILON: f32 = 1e-6;
+const LADDER_COLOR: Color = Color::srgb(0.6, 0.4, 0.2);
+const WATER_COLOR: Color = Color::srgb(0.2, 0.4, 0.9);
+const RAIL_COLOR: Color = Color::srgb(0.75, 0.75, 0.8);
+const ARC_COLOR: Color = Color::srgb(0.6, 0.8, 0.6);
+// Line segments each arc edge is drawn with
+const ARC_DRAW_SEGMENTS: usize = 32;
+const HAZARD_COLOR: Color = Color::srgb(1.0, 0.2, 0.1);
+const ICE_COLOR: Color = Color::srgb(0.7, 0.9, 1.0);
+const BOUNCER_COLOR: Color = Color::srgb(1.0, 0.4, 0.8);
+const STICKY_COLOR: Color = Color::srgb(0.5, 0.9, 0.3);
+const ENEMY_WALL_COLOR: Color = Color::srgb(0.6, 0.3, 0.3);
+const GRAVITY_ZONE_COLOR: Color = Color::srgb(0.7, 0.3, 0.9);
+const STUNNED_COLOR: Color = Color::srgb(1.0, 0.5, 0.5);
+const FUEL_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+const CHARGE_COLOR: Color = Color::srgb(0.3, 0.9, 1.0);
+const GAUGE_EMPTY_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+// Width of the fuel and jump charge gauges and their distance from the player center (pixels)
+const GAUGE_WIDTH: f32 = 24.0;
+const GAUGE_OFFSET: f32 = 20.0;
+// Size (pixels) of a level tile
+pub const GRID_SIZE: f32 = 32.0;
+
+/// The game on top of Bevy's default plugins: level management, menus and features
+///
+/// The controller phases themselves are added separately with `ControllerSchedule`, so apps
+/// can reorder or replace them.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::srgb(0.0, 0.0, 0.0)))
+            .insert_resource(InputDir { dir: Vec2::ZERO })
+            .insert_resource(ShouldExit(false))
+            .insert_resource(Level {
+                polygons: Vec::new(),
+                volumes: Vec::new(),
+                rails: Vec::new(),
+                arcs: Vec::new(),
+                edge_grid: EdgeGrid::default(),
+                edge_bvh: EdgeBvh::default(),
+            })
+            .init_resource::<ControllerConfig>()
+            .add_plugins(CollisionPlugin::default())
+            .add_plugins(NoisePlugin)
+            .add_plugins(DialoguePlugin)
+            .add_plugins(ProgressionPlugin)
+            .add_plugins(ShopPlugin)
+            .add_plugins(LevelManagerPlugin)
+            .add_plugins(GamepadPlugin)
+            .add_plugins(InputModesPlugin)
+            .add_plugins(GrapplePlugin)
+            .add_plugins(AssistPlugin)
+            .add_plugins(SlotsPlugin)
+            .add_plugins(GravityPlugin)
+            .add_plugins(SnapshotPlugin)
+            .add_plugins(WindPlugin)
+            .add_plugins(JumpPadPlugin)
+            .add_plugins(BoostPlugin)
+            .add_plugins(BreakablePlugin)
+            .add_plugins(ConfigWarningsPlugin)
+            .add_plugins(DeathPlugin)
+            .add_plugins(RisingFluidPlugin)
+            .add_plugins(LevelShiftPlugin)
+            .add_plugins(MovingPlatformPlugin)
+            .add_plugins(CheckpointPlugin)
+            .add_plugins(TimeBubblePlugin)
+            .add_plugins(CameraPlugin)
+            .add_plugins(BossPlugin)
+            .add_plugins(FeedbackPlugin)
+            .add_plugins(CrumblingPlugin)
+            .add_plugins(PushablePlugin)
+            .add_plugins(DecalPlugin)
+            .add_plugins(PhaseBlocksPlugin)
+            .add_plugins(PortalPlugin)
+            .add_plugins(TeleporterPlugin)
+            .add_plugins(TriggerPlugin)
+            // Startup systems
+            .add_systems(Startup, s_init)
+            // Level systems
+            .add_systems(OnEnter(GameState::Playing), s_spawn_level)
+            // Update systems
+            .add_systems(Update, s_render.after(ControllerSystems))
+            // Exit system runs last to ensure clean shutdown
+            .add_systems(Update, s_exit.after(s_render));
+    }
+}
+
+#[derive(Resource)]
+pub struct Level {
+    pub polygons: Vec<Polygon>,
+    pub volumes: Vec<Volume>,
+    pub rails: Vec<Rail>,
+    /// Curved edges (round pillars, bowls, pipes)
+    pub arcs: Vec<ArcEdge>,
+    /// Broad phase over the polygon edges, kept in sync by `refresh_edge_grid`
+    pub edge_grid: EdgeGrid,
+    /// Hierarchy over the polygon edges for box and ray queries, rebuilt by
+    /// `refresh_edge_bvh` when polygons are added or removed
+    pub edge_bvh: EdgeBvh,
+}
+
+impl Level {
+    /// Tile polygons, one-way platforms, curves, carved polygons, static surfaces (ice,
+    /// bouncers, sticky surfaces, enemy walls), volumes and rails of a level, with the seams
+    /// between polygons marked internal
+    pub fn from_data(level_data: &LevelData, grid_size: f32) -> Self {
+        let mut polygons = generate_level_polygons(&level_data.tiles, grid_size);
+        polygons.extend(level_data.one_way_polygons(grid_size));
+        polygons.extend(level_data.curve_polygons(grid_size));
+        if level_data.convex_decomposition {
+            polygons = polygons.iter().flat_map(Polygon::convex_pieces).collect();
+        }
+        polygons.extend(level_data.carved_polygons(grid_size, polygons.len()));
+        polygons.extend(surface_polygons(level_data, grid_size));
+
+        let mut level = Self {
+            edge_bvh: EdgeBvh::default(),
+            polygons,
+            volumes: level_data.volumes(grid_size),
+            rails: level_data.rails(grid_size),
+            arcs: level_data.arc_edges(grid_size),
+            edge_grid: EdgeGrid::default(),
+        };
+        level.weld_seams();
+        level.rebuild_edge_bvh();
+        level
+    }
+
+    /// Marks the seams between the polygons as internal edges (`mark_internal_edges`)
+    ///
+    /// Runs once all static polygons are in, before disableable or moving ones (gates,
+    /// breakables, crumbling and phase blocks, level shifts, platforms) are pushed, so those
+    /// keep every edge.
+    pub fn weld_seams(&mut self) {
+        mark_internal_edges(&mut self.polygons);
+    }
+
+    /// Rebuilds the edge BVH from the polygons as they are now
+    pub fn rebuild_edge_bvh(&mut self) {
+        self.edge_bvh = EdgeBvh::build(&self.polygons);
+    }
+
+    /// Rebuilds the edge BVH if polygons were added or removed since it was built (moved
+    /// polygons are handled by the queries)
+    pub fn refresh_edge_bvh(&mut self) {
+        if self.edge_bvh.polygon_count() != self.polygons.len() {
+            self.rebuild_edge_bvh();
+        }
+    }
+
+    /// Whether a point is inside the solid part of a polygon: inside its outline (a hole's
+    /// outer polygon for holes) and outside every hole carved from it
+    pub fn inside_solid(&self, polygon_index: usize, point: Vec2) -> bool {
+        let outer = self.polygons[polygon_index]
+            .hole_of
+            .unwrap_or(polygon_index);
+        point_in_polygon(&self.polygons[outer].points, point)
+            && !self.polygons.iter().any(|polygon| {
+                polygon.hole_of == Some(outer) && point_in_polygon(&polygon.points, point)
+            })
+    }
+
+    /// Whether a point is inside the solid part of an enabled polygon or full circle whose
+    /// layers interact with `mask`
+    pub fn contains_point(&self, point: Vec2, mask: CollisionLayers) -> bool {
+        self.polygons.iter().enumerate().any(|(index, polygon)| {
+            polygon.hole_of.is_none()
+                && polygon.collides_with(&mask)
+                && polygon.aabb.contains(point)
+                && self.inside_solid(index, point)
+        }) || self
+            .arcs
+            .iter()
+            .any(|arc| arc.layers.interacts_with(&mask) && arc.encloses(point))
+    }
+
+    /// Every edge of an enabled polygon and every arc edge whose layers interact with `mask`
+    /// that a circle overlaps
+    ///
+    /// Normals point out of the solid part of the polygon (or out of the solid side of the
+    /// curve) and `depth` is how far the circle would have to move along them to clear the
+    /// edge. There is no body, so `entity` is `Entity::PLACEHOLDER` and `relative_velocity` is
+    /// zero.
+    pub fn overlap_circle(&self, center: Vec2, radius: f32, mask: CollisionLayers) -> Vec<Contact> {
+        let candidates = self.edges_in_aabb(&Aabb::from_point_radius(center, radius));
+        let mut contacts = Vec::new();
+
+        for polygon_edges in candidates.chunk_by(|a, b| a.polygon == b.polygon) {
+            let polygon_index = polygon_edges[0].polygon;
+            if !self.polygons[polygon_index].collides_with(&mask) {
+                continue;
+            }
+
+            // Inside the solid part, the way out is through the edge
+            let inside = self.inside_solid(polygon_index, center);
+            for &edge in polygon_edges {
+                let (start, end) = self.edge_points(edge);
+                let point = closest_point_on_segment(start, end, center);
+                let distance = point.distance(center);
+                if !inside && distance > radius {
+                    continue;
+                }
+
+                let to_center = (center - point).normalize_or_zero();
+                let (normal, depth) = if inside {
+                    (-to_center, radius + distance)
+                } else {
+                    (to_center, radius - distance)
+                };
+                contacts.push(Contact {
+                    entity: Entity::PLACEHOLDER,
+                    edge: Some(edge),
+                    point,
+                    normal,
+                    depth,
+                    relative_velocity: Vec2::ZERO,
+                });
+            }
+        }
+
+        for arc in &self.arcs {
+            if !arc.layers.interacts_with(&mask) {
+                continue;
+            }
+            let point = arc.closest_point(center);
+            let distance = point.distance(center);
+            if distance > radius {
+                continue;
+            }
+
+            // Behind the curve, the way out is back through it
+            let to_center = (center - point).normalize_or_zero();
+            let (normal, depth) = if arc.is_solid_side(center) {
+                (-to_center, radius + distance)
+            } else {
+                (to_center, radius - distance)
+            };
+            contacts.push(Contact {
+                entity: Entity::PLACEHOLDER,
+                edge: None,
+                point,
+                normal,
+                depth,
+                relative_velocity: Vec2::ZERO,
+            });
+        }
+
+        contacts
+    }
+
+    /// Closest point to `point` on an edge of an enabled polygon or an arc edge whose layers
+    /// interact with `mask`, as (point, normal, distance, edge), the edge being `None` for arcs
+    ///
+    /// The normal points out of the solid part of the polygon (or the solid side of the curve),
+    /// even when `point` is inside it.
+    pub fn closest_point(
+        &self,
+        point: Vec2,
+        mask: CollisionLayers,
+    ) -> Option<(Vec2, Vec2, f32, Option<EdgeId>)> {
+        let mut closest: Option<(Vec2, f32, EdgeId)> = None;
+
+        for (polygon_index, polygon) in self.polygons.iter().enumerate() {
+            // Skip polygons whose bounding box is already further than the best edge
+            let box_distance = point
+                .clamp(polygon.aabb.min, polygon.aabb.max)
+                .distance(point);
+            if !polygon.collides_with(&mask)
+                || closest.is_some_and(|(_, distance, _)| box_distance >= distance)
+            {
+                continue;
+            }
+
+            for edge in 0..polygon.points.len().saturating_sub(1) {
+                let edge = EdgeId {
+                    polygon: polygon_index,
+                    edge,
+                };
+                let (start, end) = self.edge_points(edge);
+                let on_edge = closest_point_on_segment(start, end, point);
+                let distance = on_edge.distance(point);
+                if closest.is_none_or(|(_, closest_distance, _)| distance < closest_distance) {
+                    closest = Some((on_edge, distance, edge));
+                }
+            }
+        }
+
+        let closest_edge = closest.map(|(on_edge, distance, edge)| {
+            let to_point = (point - on_edge).normalize_or_zero();
+            let normal = if self.inside_solid(edge.polygon, point) {
+                -to_point
+            } else {
+                to_point
+            };
+            (on_edge, normal, distance, Some(edge))
+        });
+
+        let closest_arc = self
+            .arcs
+            .iter()
+            .filter(|arc| arc.layers.interacts_with(&mask))
+            .map(|arc| {
+                let on_arc = arc.closest_point(point);
+                let to_point = (point - on_arc).normalize_or_zero();
+                let normal = if arc.is_solid_side(point) {
+                    -to_point
+                } else {
+                    to_point
+                };
+                (on_arc, normal, on_arc.distance(point), None)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        match (closest_edge, closest_arc) {
+            (Some(edge), Some(arc)) if arc.2 < edge.2 => Some(arc),
+            (None, arc) => arc,
+            (edge, _) => edge,
+        }
+    }
+
+    /// Surface material of a polygon edge
+    pub fn edge_material(&self, edge: EdgeId) -> &SurfaceMaterial {
+        self.polygons[edge.polygon].edge_material(edge.edge)
+    }
+
+    /// End points of a polygon edge
+    pub fn edge_points(&self, edge: EdgeId) -> (Vec2, Vec2) {
+        let points = &self.polygons[edge.polygon].points;
+        (points[edge.edge], points[edge.edge + 1])
+    }
+
+    /// Edges of the level polygons whose bounding boxes overlap `aabb`, sorted by polygon
+    pub fn edges_in_aabb(&self, aabb: &Aabb) -> Vec<EdgeId> {
+        self.edge_bvh.query_aabb(&self.polygons, aabb)
+    }
+
+    /// Edges of the level polygons whose bounding boxes the segment passes through, sorted by
+    /// polygon
+    pub fn edges_along_segment(&self, start: Vec2, end: Vec2) -> Vec<EdgeId> {
+        self.edge_bvh.query_segment(&self.polygons, start, end)
+    }
+
+    /// First edge of an enabled polygon or arc edge the ray from `origin` along `direction` hits
+    /// within `max_distance`, skipping edges whose layers don't interact with `mask`
+    pub fn raycast(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        mask: CollisionLayers,
+    ) -> Option<RayHit> {
+        let direction = direction.normalize_or_zero();
+        let end = origin + direction * max_distance;
+
+        let edge_hits = self
+            .edges_along_segment(origin, end)
+            .into_iter()
+            .filter(|edge_id| self.polygons[edge_id.polygon].collides_with(&mask))
+            .filter_map(|edge| {
+                let (edge_start, edge_end) = self.edge_points(edge);
+                let point = line_intersect(origin, end, edge_start, edge_end)?;
+
+                // Face the side the ray came from
+                let mut normal = (edge_end - edge_start).perp().normalize_or_zero();
+                if normal.dot(direction) > 0.0 {
+                    normal = -normal;
+                }
+
+                Some(RayHit {
+                    point,
+                    normal,
+                    edge: Some(edge),
+                    t: point.distance(origin),
+                })
+            });
+        let arc_hits = self
+            .arcs
+            .iter()
+            .filter(|arc| arc.layers.interacts_with(&mask))
+            .filter_map(|arc| {
+                let point = origin + (end - origin) * arc.ray_fraction(origin, end - origin)?;
+
+                let mut normal = (point - arc.center).normalize_or_zero();
+                if normal.dot(direction) > 0.0 {
+                    normal = -normal;
+                }
+
+                Some(RayHit {
+                    point,
+                    normal,
+                    edge: None,
+                    t: point.distance(origin),
+                })
+            });
+
+        edge_hits.chain(arc_hits).min_by(|a, b| a.t.total_cmp(&b.t))
+    }
+
+    /// First edge of an enabled polygon or arc edge a circle of `radius` swept from `origin`
+    /// along `direction` touches within `max_distance`, skipping edges whose layers don't
+    /// interact with `mask`
+    ///
+    /// Edges the circle already touches at `origin` are ignored, so a cast can start in contact
+    /// with a surface.
+    pub fn shapecast(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        radius: f32,
+        max_distance: f32,
+        mask: CollisionLayers,
+    ) -> Option<ShapeHit> {
+        let motion = direction.normalize_or_zero() * max_distance;
+        if motion == Vec2::ZERO {
+            return None;
+        }
+        let swept_aabb = Aabb::from_corners(origin, origin + motion).expand(radius);
+
+        let edge_hits = self
+            .edges_in_aabb(&swept_aabb)
+            .into_iter()
+            .filter(|edge_id| self.polygons[edge_id.polygon].collides_with(&mask))
+            .filter_map(|edge| {
+                let (start, end) = self.edge_points(edge);
+                let time_of_impact =
+                    swept_circle_time_of_impact(origin, motion, radius, start, end)?;
+                let position = origin + motion * time_of_impact;
+                Some((
+                    Some(edge),
+                    time_of_impact,
+                    closest_point_on_segment(start, end, position),
+                ))
+            });
+        let arc_hits = self
+            .arcs
+            .iter()
+            .filter(|arc| arc.layers.interacts_with(&mask))
+            .filter_map(|arc| {
+                let time_of_impact = arc.swept_circle_fraction(origin, motion, radius)?;
+                let position = origin + motion * time_of_impact;
+                Some((None, time_of_impact, arc.closest_point(position)))
+            });
+        let (edge, time_of_impact, point) = edge_hits
+            .chain(arc_hits)
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        let position = origin + motion * time_of_impact;
+        Some(ShapeHit {
+            position,
+            point,
+            normal: (position - point).normalize_or_zero(),
+            edge,
+            t: max_distance * time_of_impact,
+        })
+    }
+
+    /// Brings the edge grid up to date with polygons added, moved or reset since the last call
+    pub fn refresh_edge_grid(&mut self) {
+        self.edge_grid.refresh(&self.polygons);
+    }
+
+    /// Whether a point lies inside a volume of the given kind
+    pub fn in_volume(&self, point: Vec2, kind: VolumeKind) -> bool {
+        self.volumes
+            .iter()
+            .any(|volume| volume.kind == kind && volume.aabb.contains(point))
+    }
+
+    /// Largest fraction of a circle submerged in volumes of the given kind
+    pub fn submerged_fraction(&self, center: Vec2, radius: f32, kind: VolumeKind) -> f32 {
+        self.volumes
+            .iter()
+            .filter(|volume| volume.kind == kind)
+            .map(|volume| volume.submerged_fraction(center, radius))
+            .fold(0.0, f32::max)
+    }
+}
+
+#[derive(Resource)]
+pub struct InputDir {
+    pub dir: Vec2,
+}
+
+#[derive(Resource)]
+pub struct ShouldExit(bool);
+
+// Player collision radius (units: pixels)
+pub const PLAYER_RADIUS: f32 = 12.0;
+
+// Movement constants (units: pixels/second)
+// Converted from 5.0 pixels/frame at 60fps = 300.0 pixels/second
+pub const PLAYER_MAX_SPEED: f32 = 300.0;
+
+// Acceleration scalers (units: 1/second)
+// These control how quickly velocity approaches target velocity
+// First value: acceleration rate when input is active (1/second)
+// Second value: deceleration rate when input is inactive (1/second)
+// Converted from frame-based: 0.2 per frame at 60fps = 12.0 per second
+pub const PLAYER_ACCELERATION_SCALERS: (f32, f32) = (12.0, 24.0);
+// PLAYER_TURN_AROUND_SCALER: Acceleration rate when input opposes the current velocity (1/second)
+pub const PLAYER_TURN_AROUND_SCALER: f32 = 12.0;
+
+// Timer constants (units: seconds)
+// These represent the duration windows for jump buffering, coyote time, and wall contact
+// Originally 10 frames at 60fps = 0.166 seconds
+pub const MAX_JUMP_TIMER: f32 = 0.166;
+pub const MAX_GROUNDED_TIMER: f32 = 0.166;
+pub const MAX_WALLED_TIMER: f32 = 0.166;
+// Time (seconds) one-way platforms are ignored after dropping through one
+pub const DROP_THROUGH_TIME: f32 = 0.25;
+// Minimum input towards gravity that turns a jump on a one-way platform into a drop
+const DROP_THROUGH_INPUT_THRESHOLD: f32 = 0.5;
+// Minimum input towards gravity that turns a jump in the air into a ground pound
+const GROUND_POUND_INPUT_THRESHOLD: f32 = 0.5;
+
+// Physics constants
+// Velocity constants (units: pixels/second)
+// Converted from frame-based: multiply by 60 (frames/second)
+pub const JUMP_VELOCITY: f32 = 540.0; // 9.0 pixels/frame * 60
+pub const WALL_JUMP_VELOCITY_Y: f32 = 270.0; // 4.5 pixels/frame * 60
+pub const WALL_JUMP_VELOCITY_X: f32 = 468.0; // 7.8 pixels/frame * 60
+
+// Gravity constant (units: pixels/second²)
+// Converted from frame-based: 0.5 pixels/frame² at 60fps = 1800.0 pixels/second²
+pub const GRAVITY_STRENGTH: f32 = 1800.0;
+
+// Terminal velocity (units: pixels/second)
+// Caps fall speed so fast falls can't tunnel through thin polygons
+pub const MAX_FALL_SPEED: f32 = 900.0; // 15.0 pixels/frame * 60
+
+// Wall jump acceleration reduction (unitless multiplier)
+pub const WALL_JUMP_ACCELERATION_REDUCTION: f32 = 0.5;
+// Time (seconds) after a wall jump before the same wall can be grabbed again
+pub const WALL_REGRAB_TIME: f32 = 0.15;
+
+// Jump release velocity divisor (unitless), the default jump cut
+pub const JUMP_RELEASE_VELOCITY_DIVISOR: f32 = 3.0;
+
+// Collision detection thresholds
+// NORMAL_DOT_THRESHOLD: Minimum dot product for considering a surface a "wall" (0.8 ≈ 37°)
+pub const NORMAL_DOT_THRESHOLD: f32 = 0.8;
+// GROUND_NORMAL_Y_THRESHOLD: Minimum Y component (in gravity's frame) of normal to be considered "ground"
+pub const GROUND_NORMAL_Y_THRESHOLD: f32 = 0.01;
+// CEILING_NORMAL_Y_THRESHOLD: Maximum Y component (in gravity's frame) of normal to be considered "ceiling"
+pub const CEILING_NORMAL_Y_THRESHOLD: f32 = -0.01;
+// CORNER_CORRECTION_DISTANCE: Maximum horizontal nudge (pixels) to slip around a ceiling corner
+pub const CORNER_CORRECTION_DISTANCE: f32 = 6.0;
+// MAX_SLOPE_ANGLE: Steepest slope (degrees from flat) the player can stand on
+pub const MAX_SLOPE_ANGLE: f32 = 50.0;
+// LADDER_GRAB_THRESHOLD: Minimum vertical input to grab a ladder
+pub const LADDER_GRAB_THRESHOLD: f32 = 0.5;
+// LADDER_CLIMB_SPEED: Climbing speed on ladders (pixels/second)
+pub const LADDER_CLIMB_SPEED: f32 = 150.0;
+// RAIL_ACCELERATION: Acceleration (pixels/second²) from input along a rail while grinding
+pub const RAIL_ACCELERATION: f32 = 300.0;
+// RAIL_MAX_SPEED: Fastest grinding speed (pixels/second)
+pub const RAIL_MAX_SPEED: f32 = 700.0;
+// GROUND_SNAP_DISTANCE: Maximum drop (pixels) the player is glued down across while running
+pub const GROUND_SNAP_DISTANCE: f32 = 8.0;
+// STEP_HEIGHT_RATIO: Tallest step (as a fraction of the player radius) climbed automatically
+pub const STEP_HEIGHT_RATIO: f32 = 0.5;
+// SOLVER_ITERATIONS: Relaxation passes the collision solver makes over the penetrating contacts
+pub const SOLVER_ITERATIONS: u32 = 4;
+// ICE_FRICTION: Braking and turning multiplier on ice (1.0 is normal ground)
+pub const ICE_FRICTION: f32 = 0.1;
+
+/// Movement mode that overrides the default physics
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MovementState {
+    #[default]
+    Normal,
+    /// Falling slowly with jump held
+    Gliding,
+    /// On a ladder: moving with the input, no gravity
+    Climbing,
+    /// In water: buoyancy, drag and swim strokes
+    Swimming,
+    /// Locked to a rail, sliding along it with momentum
+    Grinding,
+    /// Slamming straight down at a fixed speed after a ground pound
+    GroundPounding,
+    /// Running up a wall hit at speed, for a short time
+    WallRunning,
+    /// Knocked back by a hit: reduced control and no jumping until the stun wears off
+    Stunned,
+}
+
+/// Player component: Contains gameplay state (timers, jump state, wall contact)
+#[derive(Component, Clone)]
+pub struct Player {
+    /// Jump buffer timer: Time remaining (seconds) to execute a buffered jump input
+    jump_timer: f32,
+    /// Coyote time timer: Time remaining (seconds) player can still jump after leaving ground
+    grounded_timer: f32,
+    /// Wall contact timer: Time remaining (seconds) player is considered touching a wall
+    wall_timer: f32,
+    /// Wall direction: X direction of wall contact in gravity's frame (-1.0 for left, 1.0 for right, 0.0 for none)
+    wall_direction: f32,
+    /// Whether player has performed a wall jump (prevents multiple wall jumps)
+    has_wall_jumped: bool,
+    /// Whether player is currently grounded (derived from grounded_timer > 0)
+    is_grounded: bool,
+    /// Whether the player touched walkable ground during the last collision pass
+    ground_contact: bool,
+    /// Ground edge touched during the last collision pass
+    ground_edge: Option<EdgeId>,
+    /// Whether the player touched a sticky surface during the last collision pass
+    sticky_contact: bool,
+    /// Whether the player touched a ceiling during the last collision pass
+    touching_ceiling: bool,
+    /// Combined normal of the ceilings touched during the last collision pass (world space,
+    /// pointing down out of them)
+    ceiling_normal: Option<Vec2>,
+    /// Multiplier on the delta time from the timescale bubbles the player is in (1.0 outside)
+    time_scale: f32,
+    /// Velocity of the moving platform the player is riding (world space, zero when not
+    /// riding one), inherited on jump
+    platform_velocity: Vec2,
+    /// Last wall normal vector (for wall jump direction calculation)
+    last_wall_normal: Option<Vec2>,
+    /// Wall edge touched during the last collision pass (kept through the wall timer)
+    wall_edge: Option<EdgeId>,
+    /// Wall edge of the last wall jump
+    wall_jump_edge: Option<EdgeId>,
+    /// Consecutive wall jumps off `wall_jump_edge` since touching the ground
+    same_wall_jumps: u32,
+    /// Time remaining (seconds) during which `wall_jump_edge` can't be grabbed again
+    wall_regrab_timer: f32,
+    /// Speed (pixels/second) the player moved into a wall at during the last collision pass
+    /// (0.0 without wall contact)
+    wall_contact_speed: f32,
+    /// Time remaining (seconds) of the current wall run
+    wall_run_timer: f32,
+    /// Time remaining (seconds) of the knockback stun
+    stun_timer: f32,
+    /// Whether the sprint key is currently held
+    sprint_held: bool,
+    /// Whether the sprint modifier is currently applied to movement
+    is_sprinting: bool,
+    /// Remaining sprint stamina (only used when stamina is configured)
+    stamina: f32,
+    /// Time (seconds) spent in the apex hang window during the current airtime
+    apex_time: f32,
+    /// Whether the jump button is currently held
+    jump_held: bool,
+    /// Whether the current rise comes from a jump the player made (cleared once falling), so
+    /// only those are cut short by releasing jump
+    jumping: bool,
+    /// Whether the current rise was cut short by releasing jump (cleared once falling)
+    jump_cut: bool,
+    /// Whether jump has been held during the current jump, so a buffered press released before
+    /// takeoff doesn't cut it (cleared once not jumping)
+    jump_held_in_jump: bool,
+    /// Air jumps performed since last touching the ground or a wall
+    air_jumps_used: u32,
+    /// Remaining jetpack fuel (only used when the jetpack is configured)
+    fuel: f32,
+    /// Whether the jetpack is thrusting (from a jump press in the air until jump is released)
+    jetpacking: bool,
+    /// Time (seconds) the grounded jump has been charging, while it is (only used when charge
+    /// jumps are configured)
+    jump_charge: Option<f32>,
+    /// Current movement mode
+    state: MovementState,
+    /// Index in `Level::rails` of the rail being ground
+    rail: usize,
+    /// Distance (pixels) along the rail being ground
+    rail_distance: f32,
+    /// Speed (pixels/second) along the rail's direction while grinding (negative backwards)
+    rail_speed: f32,
+    /// Time remaining (seconds) during which releasing jump doesn't cut the velocity (after a
+    /// jump pad launch)
+    launch_timer: f32,
+    /// Time remaining (seconds) during which the input doesn't steer the player (after a boost)
+    control_lock_timer: f32,
+    /// Time remaining (seconds) during which one-way platforms don't hold the player (after
+    /// dropping through one)
+    drop_through_timer: f32,
+    /// Time remaining (seconds) during which the player can't move or jump (after landing a
+    /// ground pound)
+    pound_recovery_timer: f32,
+}
+
+impl Player {
+    /// Surface of the ground edge touched during the last collision pass
+    pub fn ground_material<'a>(&self, level: &'a Level) -> Option<&'a SurfaceMaterial> {
+        self.ground_edge.map(|edge| level.edge_material(edge))
+    }
+
+    /// Surface of the wall edge the player is on (kept through the wall timer)
+    pub fn wall_material<'a>(&self, level: &'a Level) -> Option<&'a SurfaceMaterial> {
+        self.wall_edge.map(|edge| level.edge_material(edge))
+    }
+
+    /// Whether the player touched a ceiling during the last collision pass (head bumps, corner
+    /// correction, hanging)
+    pub fn touching_ceiling(&self) -> bool {
+        self.touching_ceiling
+    }
+
+    /// Normal of the ceilings touched during the last collision pass (world space)
+    pub fn ceiling_normal(&self) -> Option<Vec2> {
+        self.ceiling_normal
+    }
+
+    /// Launches the player along `direction` at `strength` (pixels/second, world space) and
+    /// stuns them for `stun_duration` seconds, during which they keep only
+    /// `ControllerConfig::stun_control` of their steering and can't jump
+    pub fn apply_knockback(
+        &mut self,
+        physics: &mut Physics,
+        direction: Vec2,
+        strength: f32,
+        stun_duration: f32,
+    ) {
+        physics.velocity = direction.normalize_or_zero() * strength;
+        self.state = MovementState::Stunned;
+        self.stun_timer = stun_duration;
+        self.jump_timer = 0.0;
+        self.grounded_timer = 0.0;
+        self.jumping = false;
+        self.jetpacking = false;
+        self.jump_charge = None;
+    }
+}
+
+/// Physics component: Contains pure physics state (position, velocity, acceleration, collision)
+#[derive(Component, Clone)]
+pub struct Physics {
+    /// Previous frame's position (for collision detection)
+    pub prev_position: Vec2,
+    /// Current velocity vector (pixels/second)
+    pub velocity: Vec2,
+    /// Current acceleration vector (pixels/second²)
+    pub acceleration: Vec2,
+    /// Collision radius (pixels)
+    pub radius: f32,
+    /// Surface normal at current position (zero if not touching surface)
+    pub normal: Vec2,
+    /// External forces (pixels/second², world space) added since the last movement update;
+    /// `s_movement` integrates them into the velocity and clears them
+    pub external_force: Vec2,
+}
+
+impl Physics {
+    /// Changes the velocity at once (pixels/second, world space), e.g. for explosions and
+    /// knockback
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.velocity += impulse;
+    }
+
+    /// Adds a force (pixels/second², world space) acting until the next movement update, e.g.
+    /// for wind and scripted pushes; call it every frame for a continuous force
+    pub fn add_force(&mut self, force: Vec2) {
+        self.external_force += force;
+    }
+
+    /// Applies `convert` to the velocity, acceleration and normal (e.g. to change frames)
+    pub fn map_vectors(&mut self, convert: impl Fn(Vec2) -> Vec2) {
+        self.velocity = convert(self.velocity);
+        self.acceleration = convert(self.acceleration);
+        self.normal = convert(self.normal);
+    }
+}
+
+/// Initial setup system
+pub fn s_init(mut commands: Commands) {
+    // Spawn camera
+    commands.spawn((Camera2d, Transform::default()));
+}
+
+/// Level setup system: builds the current level and spawns the player and level entities
+/// Runs when entering `GameState::Playing`; everything spawned here is tagged `LevelEntity`
+pub fn s_spawn_level(
+    mut commands: Commands,
+    config: Res<ControllerConfig>,
+    level_manager: Res<LevelManager>,
+    mut gravity: ResMut<Gravity>,
+) {
+    let grid_size = GRID_SIZE;
+
+    let level_entry = level_manager.current_level();
+    let level_data = load_level_data(level_entry.data);
+    let mut level = Level::from_data(&level_data, grid_size);
+
+    // Spawn player
+    let initial_position = level_data.spawn_position(grid_size);
+    commands.spawn((LevelEntity, player_bundle(initial_position, &config)));
+    commands.insert_resource(SpawnPoint::at(initial_position));
+
+    // Spawn signs
+    for sign in &level_data.signs {
+        commands.spawn((
+            LevelEntity,
+            Transform::from_translation(level_data.tile_to_world(sign.tile, grid_size).extend(0.0)),
+            Sign {
+                pages: sign.pages.clone(),
+            },
+        ));
+    }
+
+    // Spawn keys
+    for key in &level_data.keys {
+        commands.spawn((
+            LevelEntity,
+            Transform::from_translation(level_data.tile_to_world(key.tile, grid_size).extend(0.0)),
+            KeyPickup { id: key.id.clone() },
+        ));
+    }
+
+    // Spawn coins
+    for (index, coin) in level_data.coins.iter().enumerate() {
+        commands.spawn((
+            LevelEntity,
+            Transform::from_translation(level_data.tile_to_world(coin.tile, grid_size).extend(0.0)),
+            Coin {
+                id: format!("{}:{}", level_entry.id, index),
+            },
+        ));
+    }
+
+    // Spawn shops
+    for shop in &level_data.shops {
+        commands.spawn((
+            LevelEntity,
+            Transform::from_translation(level_data.tile_to_world(shop.tile, grid_size).extend(0.0)),
+            Shop {
+                items: shop.items.clone(),
+            },
+        ));
+    }
+
+    // Spawn swing anchors
+    for anchor in &level_data.swing_anchors {
+        commands.spawn((
+            LevelEntity,
+            Transform::from_translation(
+                level_data.tile_to_world(anchor.tile, grid_size).extend(0.0),
+            ),
+            SwingAnchor,
+        ));
+    }
+
+    // Spawn goal
+    if let Some(goal) = level_data.goal {
+        commands.spawn((
+            LevelEntity,
+            Transform::from_translation(level_data.tile_to_world(goal, grid_size).extend(0.0)),
+            Goal {
+                medals: level_data.medals.clone(),
+                coin_count: level_data.coins.len(),
+            },
+        ));
+    }
+
+    // Spawn gates (their polygons live in the level so they collide like tiles)
+    for gate in &level_data.gates {
+        let min = level_data.tile_to_world(gate.min, grid_size);
+        let max = level_data.tile_to_world(gate.max, grid_size);
+
+        commands.spawn((
+            LevelEntity,
+            Gate {
+                id: gate.id.clone(),
+                polygon: level.polygons.len(),
+            },
+        ));
+        level
+            .polygons
+            .push(Polygon::new(rect_points(min, max), GATE_COLOR));
+    }
+
+    // Spawn red/blue phase blocks, starting in the red phase
+    let mut phase_blocks = Vec::new();
+    for block in &level_data.phase_blocks {
+        let min = level_data.tile_to_world(block.min, grid_size);
+        let max = level_data.tile_to_world(block.max, grid_size);
+
+        phase_blocks.push(PhaseBlock {
+            polygon: level.polygons.len(),
+            phase: block.phase,
+        });
+        level
+            .polygons
+            .push(Polygon::new(rect_points(min, max), block.phase.color()));
+    }
+    apply_phase(&mut level, &phase_blocks, PhaseColor::Red);
+    commands.spawn_batch(phase_blocks.into_iter().map(|block| (LevelEntity, block)));
+    commands.insert_resource(PhaseSwitch {
+        active: PhaseColor::Red,
+        interval: level_data.phase_interval,
+        timer: 0.0,
+    });
+
+    // Spawn break-on-land tiles
+    for breakable in &level_data.breakables {
+        let min = level_data.tile_to_world(breakable.min, grid_size);
+        let max = level_data.tile_to_world(breakable.max, grid_size);
+
+        commands.spawn((
+            LevelEntity,
+            Breakable {
+                polygon: level.polygons.len(),
+                impact_threshold: breakable.impact_threshold,
+            },
+        ));
+        level
+            .polygons
+            .push(Polygon::new(rect_points(min, max), BREAKABLE_COLOR));
+    }
+
+    // Spawn crumbling platforms
+    for platform in &level_data.crumbling {
+        let min = level_data.tile_to_world(platform.min, grid_size);
+        let max = level_data.tile_to_world(platform.max, grid_size);
+
+        commands.spawn((
+            LevelEntity,
+            Crumbling {
+                polygon: level.polygons.len(),
+                delay: platform.delay.unwrap_or(DEFAULT_CRUMBLE_DELAY),
+                respawn_time: platform.respawn.unwrap_or(DEFAULT_CRUMBLE_RESPAWN),
+                state: CrumbleState::Solid,
+            },
+        ));
+        level
+            .polygons
+            .push(Polygon::new(rect_points(min, max), CRUMBLING_COLOR));
+    }
+
+    // Spawn pushable blocks
+    for block in &level_data.pushables {
+        let min = level_data.tile_to_world(block.min, grid_size);
+        let max = level_data.tile_to_world(block.max, grid_size);
+        let center = (min + max) / 2.0;
+
+        commands.spawn((
+            LevelEntity,
+            Pushable {
+                polygon: level.polygons.len(),
+                start: center,
+                center,
+                half_size: (max - min).abs() / 2.0,
+                velocity: Vec2::ZERO,
+            },
+        ));
+        level
+            .polygons
+            .push(Polygon::new(rect_points(min, max), PUSHABLE_COLOR));
+    }
+
+    // Spawn level shifts (their polygons live in the level so they collide like tiles)
+    for shift in &level_data.level_shifts {
+        let base_points: Vec<Vec<Vec2>> = shift
+            .polygons
+            .iter()
+            .map(|outline| {
+                outline
+                    .iter()
+                    .map(|&point| level_data.tile_to_world(point, grid_size))
+                    .collect()
+            })
+            .collect();
+        let pivot = level_data.tile_to_world(shift.pivot, grid_size);
+        let offset = level_data.tile_to_world(
+            [
+                shift.pivot[0] + shift.offset[0],
+                shift.pivot[1] + shift.offset[1],
+            ],
+            grid_size,
+        ) - pivot;
+        let trigger = shift.trigger.as_ref().map(|trigger| {
+            Rect::from_corners(
+                level_data.tile_to_world(trigger.min, grid_size),
+                level_data.tile_to_world(trigger.max, grid_size),
+            )
+        });
+
+        let first_polygon = level.polygons.len();
+        for points in &base_points {
+            level
+                .polygons
+                .push(Polygon::new(points.clone(), LEVEL_SHIFT_COLOR));
+        }
+
+        commands.spawn((
+            LevelEntity,
+            LevelShift {
+                polygons: (first_polygon..level.polygons.len()).collect(),
+                base_points,
+                pivot,
+                offset,
+                rotation: shift.rotation.to_radians(),
+                duration: shift.duration,
+                delay: shift.delay.or(trigger.is_none().then_some(0.0)),
+                trigger,
+                elapsed: 0.0,
+                progress: None,
+            },
+        ));
+    }
+
+    // Spawn moving platforms (their polygons live in the level so they collide like tiles)
+    let platform_entities: Vec<Entity> = level_data
+        .moving_platforms
+        .iter()
+        .map(|platform| {
+            commands
+                .spawn((
+                    LevelEntity,
+                    spawn_moving_platform(
+                        &level_data,
+                        platform,
+                        grid_size,
+                        &mut level,
+                        MOVING_PLATFORM_COLOR,
+                    ),
+                ))
+                .id()
+        })
+        .collect();
+
+    // Spawn checkpoints, attached to their moving platform if they have one
+    for checkpoint in &level_data.checkpoints {
+        commands.spawn((
+            LevelEntity,
+            Checkpoint {
+                position: level_data.tile_to_world(checkpoint.tile, grid_size),
+                platform: checkpoint
+                    .platform
+                    .and_then(|index| platform_entities.get(index).copied()),
+            },
+        ));
+    }
+
+    // Spawn bosses: a moving platform body plus the arena walls of each phase (solid only
+    // during their phase)
+    for boss in &level_data.bosses {
+        let phases = boss
+            .phases
+            .iter()
+            .map(|phase| {
+                let first_wall = level.polygons.len();
+                for wall in &phase.walls {
+                    let min = level_data.tile_to_world(wall.min, grid_size);
+                    let max = level_data.tile_to_world(wall.max, grid_size);
+
+                    let mut polygon = Polygon::new(rect_points(min, max), BOSS_WALL_COLOR);
+                    polygon.enabled = false;
+                    level.polygons.push(polygon);
+                }
+
+                BossPhase {
+                    hp: phase.hp,
+                    speed: phase.speed,
+                    walls: (first_wall..level.polygons.len()).collect(),
+                }
+            })
+            .collect();
+
+        let mut body =
+            spawn_moving_platform(&level_data, &boss.body, grid_size, &mut level, BOSS_COLOR);
+        // The fight sets the speed
+        body.speed = 0.0;
+
+        commands.spawn((
+            LevelEntity,
+            body,
+            Boss {
+                name: boss.name.clone(),
+                arena: Rect::from_corners(
+                    level_data.tile_to_world(boss.arena.min, grid_size),
+                    level_data.tile_to_world(boss.arena.max, grid_size),
+                ),
+                max_hp: boss.hp,
+                hp: boss.hp,
+                phases,
+                phase: 0,
+                state: BossState::Dormant,
+                hit_timer: 0.0,
+            },
+        ));
+    }
+
+    // Spawn rising fluids (their hazard volumes live in the level and grow as they rise)
+    for fluid in &level_data.rising_fluids {
+        let aabb = Aabb::from_corners(
+            level_data.tile_to_world(fluid.min, grid_size),
+            level_data.tile_to_world(fluid.max, grid_size),
+        );
+        let max_height = level_data.tile_to_world([0.0, fluid.top], grid_size).y;
+        let trigger = fluid.trigger.as_ref().map(|trigger| {
+            Rect::from_corners(
+                level_data.tile_to_world(trigger.min, grid_size),
+                level_data.tile_to_world(trigger.max, grid_size),
+            )
+        });
+
+        commands.spawn((
+            LevelEntity,
+            RisingFluid {
+                volume: level.volumes.len(),
+                start_height: aabb.max.y,
+                max_height,
+                height: aabb.max.y,
+                speed: fluid.speed,
+                delay: fluid.delay.or(trigger.is_none().then_some(0.0)),
+                trigger,
+                elapsed: 0.0,
+                rising: false,
+            },
+            Sprite::from_color(RISING_FLUID_COLOR, aabb.max - aabb.min),
+            Transform::default(),
+        ));
+        level.volumes.push(Volume {
+            aabb,
+            kind: VolumeKind::Hazard,
+        });
+    }
+
+    // Spawn portal pairs
+    for pair in &level_data.portals {
+        let ends = pair.clone().map(|portal| PortalEnd {
+            start: level_data.tile_to_world(portal.start, grid_size),
+            end: level_data.tile_to_world(portal.end, grid_size),
+            normal: Vec2::from(portal.facing).normalize_or(Vec2::Y),
+        });
+
+        commands.spawn((LevelEntity, PortalPair { ends }));
+    }
+
+    // Spawn teleporters
+    for teleporter in &level_data.teleporters {
+        let areas = [&teleporter.a, &teleporter.b].map(|area| {
+            Rect::from_corners(
+                level_data.tile_to_world(area.min, grid_size),
+                level_data.tile_to_world(area.max, grid_size),
+            )
+        });
+
+        commands.spawn((
+            LevelEntity,
+            TeleporterPair {
+                areas,
+                velocity: teleporter.velocity,
+                cooldown: 0.0,
+            },
+        ));
+    }
+
+    // Spawn triggers
+    for trigger in &level_data.triggers {
+        let shape = match &trigger.shape {
+            TriggerShapeData::Box { min, max } => TriggerShape::Box(Aabb::from_corners(
+                level_data.tile_to_world(*min, grid_size),
+                level_data.tile_to_world(*max, grid_size),
+            )),
+            TriggerShapeData::Circle { center, radius } => TriggerShape::Circle {
+                center: level_data.tile_to_world(*center, grid_size),
+                radius: radius * grid_size,
+            },
+            TriggerShapeData::Polygon(points) if points.len() >= 3 => {
+                let mut points: Vec<Vec2> = points
+                    .iter()
+                    .map(|&point| level_data.tile_to_world(point, grid_size))
+                    .collect();
+                points.push(points[0]);
+                TriggerShape::Polygon(points)
+            }
+            TriggerShapeData::Polygon(_) => continue,
+        };
+        commands.spawn((LevelEntity, Trigger::new(trigger.id.clone(), shape)));
+    }
+
+    // Spawn jump pads
+    for pad in &level_data.jump_pads {
+        commands.spawn((
+            LevelEntity,
+            JumpPad {
+                velocity: Vec2::from(pad.velocity),
+            },
+            Transform::from_translation(level_data.tile_to_world(pad.tile, grid_size).extend(0.0)),
+        ));
+    }
+
+    // Spawn boosters
+    for booster in &level_data.boosters {
+        commands.spawn((
+            LevelEntity,
+            Booster {
+                direction: Vec2::from(booster.direction).normalize_or(Vec2::X),
+                speed: booster.speed,
+                lockout: booster.lockout,
+            },
+            Transform::from_translation(
+                level_data
+                    .tile_to_world(booster.tile, grid_size)
+                    .extend(0.0),
+            ),
+        ));
+    }
+
+    // Spawn wind/force-field zones
+    for zone in &level_data.force_zones {
+        let min = level_data.tile_to_world(zone.min, grid_size);
+        let max = level_data.tile_to_world(zone.max, grid_size);
+
+        commands.spawn((
+            LevelEntity,
+            ForceZone {
+                area: Rect::from_corners(min, max),
+                acceleration: Vec2::from(zone.acceleration),
+            },
+        ));
+    }
+
+    // Spawn autoscroll camera segments
+    for segment in &level_data.autoscroll {
+        commands.spawn((
+            LevelEntity,
+            AutoscrollSegment {
+                trigger: Rect::from_corners(
+                    level_data.tile_to_world(segment.trigger.min, grid_size),
+                    level_data.tile_to_world(segment.trigger.max, grid_size),
+                ),
+                start: level_data.tile_to_world(segment.start, grid_size),
+                end: level_data.tile_to_world(segment.end, grid_size),
+                speed: segment.speed,
+                done: false,
+            },
+        ));
+    }
+
+    // Spawn snow patches
+    for snow in &level_data.snow {
+        let min = level_data.tile_to_world(snow.min, grid_size);
+        let max = level_data.tile_to_world(snow.max, grid_size);
+
+        commands.spawn((
+            LevelEntity,
+            SnowPatch {
+                area: Rect::from_corners(min, max),
+            },
+        ));
+    }
+
+    // Spawn timescale bubbles
+    for bubble in &level_data.time_bubbles {
+        let min = level_data.tile_to_world(bubble.min, grid_size);
+        let max = level_data.tile_to_world(bubble.max, grid_size);
+
+        commands.spawn((
+            LevelEntity,
+            TimeBubble {
+                area: Rect::from_corners(min, max),
+                scale: bubble.scale,
+            },
+        ));
+    }
+
+    // Give edges their own surface materials
+    for surface in &level_data.surface_materials {
+        let area = Aabb::from_corners(
+            level_data.tile_to_world(surface.min, grid_size),
+            level_data.tile_to_world(surface.max, grid_size),
+        );
+        for polygon in level
+            .polygons
+            .iter_mut()
+            .filter(|polygon| polygon.aabb.overlaps(&area))
+        {
+            let material = SurfaceMaterial {
+                friction: surface.friction.unwrap_or(polygon.material.friction),
+                restitution: surface.restitution.unwrap_or(polygon.material.restitution),
+                tag: surface.tag.clone(),
+            };
+            polygon.set_edge_material(&area, &material);
+        }
+    }
+
+    commands.insert_resource(level);
+
+    gravity.field = level_data.gravity_field(grid_size);
+    gravity.zones = level_data.gravity_zones(grid_size);
+}
+
+/// Closed outline of a rectangle between two corners
+/// Adds a moving platform's polygon to the level and builds its component
+fn spawn_moving_platform(
+    level_data: &LevelData,
+    platform: &MovingPlatformData,
+    grid_size: f32,
+    level: &mut Level,
+    color: Color,
+) -> MovingPlatform {
+    let min = level_data.tile_to_world(platform.min, grid_size);
+    let max = level_data.tile_to_world(platform.max, grid_size);
+    let waypoints = std::iter::once(Vec2::ZERO)
+        .chain(
+            platform
+                .path
+                .iter()
+                .map(|&tile| level_data.tile_to_world(tile, grid_size) - min),
+        )
+        .collect();
+
+    let pivot = platform.pivot.map_or((min + max) / 2.0, |pivot| {
+        level_data.tile_to_world(pivot, grid_size)
+    });
+
+    let base_points = rect_points(min, max);
+    level
+        .polygons
+        .push(Polygon::new(base_points.clone(), color));
+
+    MovingPlatform {
+        polygon: level.polygons.len() - 1,
+        base_points,
+        waypoints,
+        speed: platform.speed,
+        looped: platform.looped,
+        pivot,
+        angular_speed: platform.angular_speed.to_radians(),
+        distance: 0.0,
+        angle: 0.0,
+        prev_distance: 0.0,
+        prev_angle: 0.0,
+        crush: platform.crush,
+    }
+}
+
+/// Rectangles of the static surfaces: ice, bouncers, sticky surfaces and enemy-only walls
+fn surface_polygons(level_data: &LevelData, grid_size: f32) -> Vec<Polygon> {
+    let mut polygons = Vec::new();
+
+    // Ice
+    for ice in &level_data.ice {
+        let min = level_data.tile_to_world(ice.min, grid_size);
+        let max = level_data.tile_to_world(ice.max, grid_size);
+
+        let mut polygon = Polygon::new(rect_points(min, max), ICE_COLOR);
+        polygon.material.friction = ice.friction.unwrap_or(ICE_FRICTION);
+        polygons.push(polygon);
+    }
+
+    // Bouncers
+    for bouncer in &level_data.bouncers {
+        let min = level_data.tile_to_world(bouncer.min, grid_size);
+        let max = level_data.tile_to_world(bouncer.max, grid_size);
+
+        let mut polygon = Polygon::new(rect_points(min, max), BOUNCER_COLOR);
+        polygon.material.restitution = bouncer.restitution;
+        polygons.push(polygon);
+    }
+
+    // Sticky surfaces
+    for surface in &level_data.sticky_surfaces {
+        let min = level_data.tile_to_world(surface.min, grid_size);
+        let max = level_data.tile_to_world(surface.max, grid_size);
+
+        let mut polygon = Polygon::new(rect_points(min, max), STICKY_COLOR);
+        polygon.sticky = true;
+        polygons.push(polygon);
+    }
+
+    // Enemy-only walls
+    for wall in &level_data.enemy_walls {
+        let min = level_data.tile_to_world(wall.min, grid_size);
+        let max = level_data.tile_to_world(wall.max, grid_size);
+
+        let mut polygon = Polygon::new(rect_points(min, max), ENEMY_WALL_COLOR);
+        polygon.layers.filters = CollisionLayers::ENEMY;
+        polygons.push(polygon);
+    }
+
+    polygons
+}
+
+fn rect_points(min: Vec2, max: Vec2) -> Vec<Vec2> {
+    vec![
+        min,
+        Vec2::new(max.x, min.y),
+        max,
+        Vec2::new(min.x, max.y),
+        min,
+    ]
+}
+
+/// Player entity components at a position
+pub fn player_bundle(position: Vec2, config: &ControllerConfig) -> (Transform, Physics, Player) {
+    (
+        Transform::from_translation(position.extend(0.0)),
+        Physics {
+            prev_position: position,
+            velocity: Vec2::ZERO,
+            acceleration: Vec2::ZERO,
+            radius: PLAYER_RADIUS,
+            normal: Vec2::ZERO,
+            external_force: Vec2::ZERO,
+        },
+        Player {
+            jump_timer: 0.0,
+            grounded_timer: 0.0,
+            wall_timer: 0.0,
+            wall_direction: 0.0,
+            has_wall_jumped: false,
+            is_grounded: false,
+            ground_contact: false,
+            ground_edge: None,
+            sticky_contact: false,
+            touching_ceiling: false,
+            ceiling_normal: None,
+            time_scale: 1.0,
+            platform_velocity: Vec2::ZERO,
+            last_wall_normal: None,
+            wall_edge: None,
+            wall_jump_edge: None,
+            same_wall_jumps: 0,
+            wall_regrab_timer: 0.0,
+            wall_contact_speed: 0.0,
+            wall_run_timer: 0.0,
+            stun_timer: 0.0,
+            sprint_held: false,
+            is_sprinting: false,
+            stamina: config
+                .sprint
+                .stamina
+                .as_ref()
+                .map_or(0.0, |stamina| stamina.max),
+            apex_time: 0.0,
+            jump_held: false,
+            jumping: false,
+            jump_cut: false,
+            jump_held_in_jump: false,
+            air_jumps_used: 0,
+            fuel: config.jetpack.as_ref().map_or(0.0, |jetpack| jetpack.fuel),
+            jetpacking: false,
+            jump_charge: None,
+            state: MovementState::Normal,
+            rail: 0,
+            rail_distance: 0.0,
+            rail_speed: 0.0,
+            launch_timer: 0.0,
+            drop_through_timer: 0.0,
+            pound_recovery_timer: 0.0,
+            control_lock_timer: 0.0,
+        },
+    )
+}
+
+/// Input system
+pub fn s_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut should_exit: ResMut<ShouldExit>,
+    mut input_dir: ResMut<InputDir>,
+    mut player_query: Query<&mut Player>,
+) {
+    // Escape to exit - set flag for dedicated exit system to handle
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        should_exit.0 = true;
+        return;
+    }
+
+    if let Ok(mut player_data) = player_query.single_mut() {
+        let mut direction = Vec2::ZERO;
+
+        // Arrow keys to move
+        if keyboard_input.pressed(KeyCode::ArrowUp) {
+            direction.y += 1.0;
+        }
+        if keyboard_input.pressed(KeyCode::ArrowDown) {
+            direction.y -= 1.0;
+        }
+        if keyboard_input.pressed(KeyCode::ArrowLeft) {
+            direction.x -= 1.0;
+        }
+        if keyboard_input.pressed(KeyCode::ArrowRight) {
+            direction.x += 1.0;
+        }
+
+        // Shift to sprint
+        player_data.sprint_held =
+            keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+        // Space to jump
+        player_data.jump_held = keyboard_input.pressed(KeyCode::Space);
+        if keyboard_input.just_pressed(KeyCode::Space) {
+            player_data.jump_timer = MAX_JUMP_TIMER;
+        }
+
+        // Normalize direction
+        direction = direction.normalize_or_zero();
+
+        // Set direction resource
+        input_dir.dir = direction;
+    }
+}
+
+/// Movement system
+/// Implements frame-rate independent physics using delta time and semi-implicit Euler integration
+pub fn s_movement(
+    mut player_query: Query<(&mut Transform, &mut Physics, &mut Player)>,
+    input_dir: Res<InputDir>,
+    config: Res<ControllerConfig>,
+    assist: Res<AssistMode>,
+    level: Res<Level>,
+    gravity: Res<Gravity>,
+    time: Res<Time>,
+) {
+    if let Ok((mut player_transform, mut player_physics, mut player_data)) =
+        player_query.single_mut()
+    {
+        // Clamp delta time to prevent huge jumps on first frame or frame skips
+        // Maximum delta time of 1/30th second (30 FPS minimum), after the timescale bubbles
+        let dt = (time.delta_secs() * player_data.time_scale).min(1.0 / 30.0);
+
+        // External forces (wind, scripted pushes) added since the last update
+        let external_force = std::mem::take(&mut player_physics.external_force);
+        player_physics.velocity += external_force * dt;
+
+        // Work in gravity's frame: the logic below treats +Y as up, away from gravity
+        let frame = gravity.frame_at(player_transform.translation.xy());
+        let gravity_strength = GRAVITY_STRENGTH * frame.strength;
+        let input_dir = frame.input_to_local(input_dir.dir);
+        player_physics.map_vectors(|vector| frame.to_local(vector));
+
+        // Rail grinding: land on a rail from above and slide along it, jumping off anywhere
+        {
+            let player_pos = player_transform.translation.xy();
+
+            if player_data.state != MovementState::Grinding && player_physics.velocity.y <= 0.0 {
+                // Catch a rail the player is falling onto (not at its ends, which they leave by)
+                let caught = level.rails.iter().enumerate().find_map(|(index, rail)| {
+                    let (closest, distance) = rail.closest_point(player_pos);
+                    let offset = frame.to_local(player_pos - closest);
+                    (offset.length() <= player_physics.radius
+                        && offset.y >= 0.0
+                        && distance > 0.0
+                        && distance < rail.length())
+                    .then_some((index, distance))
+                });
+
+                if let Some((index, distance)) = caught {
+                    let (_, direction) = level.rails[index].point_at(distance);
+                    player_data.state = MovementState::Grinding;
+                    player_data.rail = index;
+                    player_data.rail_distance = distance;
+                    // Keep the momentum along the rail
+                    player_data.rail_speed = player_physics.velocity.dot(frame.to_local(direction));
+                }
+            }
+
+            if player_data.state == MovementState::Grinding {
+                player_data.apex_time = 0.0;
+                player_data.air_jumps_used = 0;
+
+                let rail = &level.rails[player_data.rail];
+                let (_, direction) = rail.point_at(player_data.rail_distance);
+                let direction = frame.to_local(direction);
+
+                // Gravity pulls down slopes and the input pushes along the rail
+                player_data.rail_speed = (player_data.rail_speed
+                    + (-gravity_strength * direction.y
+                        + input_dir.dot(direction) * RAIL_ACCELERATION)
+                        * dt)
+                    .clamp(-RAIL_MAX_SPEED, RAIL_MAX_SPEED);
+                player_data.rail_distance += player_data.rail_speed * dt;
+
+                if player_data.jump_timer > 0.0 {
+                    // Jump off the rail, keeping its momentum
+                    player_data.state = MovementState::Normal;
+                    player_data.jump_timer = 0.0;
+                    player_physics.velocity = direction * player_data.rail_speed;
+                    player_physics.velocity.y += JUMP_VELOCITY;
+                    player_data.jumping = true;
+                    player_data.jump_cut = false;
+                } else if !(0.0..=rail.length()).contains(&player_data.rail_distance) {
+                    // Fly off the end
+                    player_data.state = MovementState::Normal;
+                    player_physics.velocity = direction * player_data.rail_speed;
+                } else {
+                    // Ride on the side of the rail facing away from gravity
+                    let (point, direction) = rail.point_at(player_data.rail_distance);
+                    let mut up = direction.perp();
+                    if frame.to_local(up).y < 0.0 {
+                        up = -up;
+                    }
+
+                    player_physics.acceleration = Vec2::ZERO;
+                    player_physics.velocity = frame.to_local(direction) * player_data.rail_speed;
+                    player_physics.prev_position = player_pos;
+                    player_physics.map_vectors(|vector| frame.to_world(vector));
+
+                    let position = point + up * player_physics.radius;
+                    player_transform.translation.x = position.x;
+                    player_transform.translation.y = position.y;
+                    return;
+                }
+            }
+        }
+
+        // Ladder climbing: grab with up/down, let go by jumping or leaving the ladder
+        {
+            let player_pos = player_transform.translation.xy();
+            let on_ladder = level.in_volume(player_pos, VolumeKind::Ladder);
+            let grab_input = input_dir.y.abs() >= LADDER_GRAB_THRESHOLD;
+
+            if !on_ladder && player_data.state == MovementState::Climbing {
+                player_data.state = MovementState::Normal;
+            } else if on_ladder
+                && grab_input
+                && (player_physics.velocity.y <= 0.0 || player_data.grounded_timer > 0.0)
+            {
+                player_data.state = MovementState::Climbing;
+            }
+
+            if player_data.state == MovementState::Climbing {
+                player_data.apex_time = 0.0;
+                player_data.air_jumps_used = 0;
+
+                if player_data.jump_timer > 0.0 {
+                    // Jump off the ladder
+                    player_data.state = MovementState::Normal;
+                    player_data.jump_timer = 0.0;
+                    player_physics.velocity.y = JUMP_VELOCITY;
+                    player_data.jumping = true;
+                    player_data.jump_cut = false;
+                } else {
+                    // Move directly with the input, without gravity
+                    player_physics.acceleration = Vec2::ZERO;
+                    player_physics.velocity = input_dir * config.ladder_climb_speed;
+                    player_physics.prev_position = player_pos;
+                    player_physics.map_vectors(|vector| frame.to_world(vector));
+
+                    let velocity_dt = player_physics.velocity * dt;
+                    player_transform.translation.x += velocity_dt.x;
+                    player_transform.translation.y += velocity_dt.y;
+                    return;
+                }
+            }
+        }
+
+        // Swimming: enter when mostly submerged, leave once fully out of the water
+        {
+            let player_pos = player_transform.translation.xy();
+            let submerged =
+                level.submerged_fraction(player_pos, player_physics.radius, VolumeKind::Water);
+
+            if submerged <= 0.0 && player_data.state == MovementState::Swimming {
+                player_data.state = MovementState::Normal;
+            } else if submerged >= config.swim.enter_fraction {
+                player_data.state = MovementState::Swimming;
+            }
+
+            if player_data.state == MovementState::Swimming {
+                player_data.apex_time = 0.0;
+                player_data.air_jumps_used = 0;
+
+                if player_data.jump_timer > 0.0 {
+                    player_data.jump_timer = 0.0;
+                    if submerged <= config.swim.surface_fraction {
+                        // At the surface: jump out of the water
+                        player_physics.velocity.y = JUMP_VELOCITY;
+                        player_data.jumping = true;
+                        player_data.jump_cut = false;
+                    } else {
+                        // Swim stroke
+                        player_physics.velocity.y =
+                            player_physics.velocity.y.max(config.swim.stroke_velocity);
+                    }
+                }
+
+                // Reduced gravity, buoyancy from the submerged part and input acceleration
+                player_physics.acceleration = input_dir * config.swim.acceleration
+                    + Vec2::new(
+                        0.0,
+                        config.swim.buoyancy * submerged
+                            - gravity_strength * config.swim.gravity_multiplier,
+                    );
+                player_physics.velocity += player_physics.acceleration * dt;
+
+                // Drag
+                player_physics.velocity *= (1.0 - config.swim.drag * dt).max(0.0);
+                player_physics.velocity.x = player_physics
+                    .velocity
+                    .x
+                    .clamp(-config.swim.max_speed, config.swim.max_speed);
+                player_physics.velocity.y = player_physics.velocity.y.max(-config.swim.max_speed);
+
+                player_physics.prev_position = player_pos;
+                player_physics.map_vectors(|vector| frame.to_world(vector));
+                let velocity_dt = player_physics.velocity * dt;
+                player_transform.translation.x += velocity_dt.x;
+                player_transform.translation.y += velocity_dt.y;
+                return;
+            }
+        }
+
+        // Ground pound: slam straight down, ignoring input, until something stops the fall
+        if player_data.state == MovementState::GroundPounding {
+            // Collision ends it on the ground; any other surface (a wall, a steep slope) just
+            // cancels it
+            if player_physics.normal.length_squared() < EPSILON {
+                player_data.jump_timer = 0.0;
+                player_physics.acceleration = Vec2::ZERO;
+                player_physics.velocity = Vec2::new(0.0, -config.ground_pound.speed);
+
+                player_physics.prev_position = player_transform.translation.xy();
+                player_physics.map_vectors(|vector| frame.to_world(vector));
+                let velocity_dt = player_physics.velocity * dt;
+                player_transform.translation.x += velocity_dt.x;
+                player_transform.translation.y += velocity_dt.y;
+                return;
+            }
+            player_data.state = MovementState::Normal;
+        }
+
+        // Wall run: hitting a wall fast turns part of the speed into a short run up it, after
+        // which the normal wall handling takes over
+        {
+            if config.wall_run.enabled
+                && matches!(
+                    player_data.state,
+                    MovementState::Normal | MovementState::Gliding
+                )
+                && player_data.wall_contact_speed >= config.wall_run.min_speed
+            {
+                player_data.state = MovementState::WallRunning;
+                player_data.wall_run_timer = config.wall_run.duration;
+                player_physics.velocity.y = player_physics
+                    .velocity
+                    .y
+                    .max(player_data.wall_contact_speed * config.wall_run.speed_conversion);
+            }
+
+            if player_data.state == MovementState::WallRunning {
+                // Jumping (off the wall), running out of time or speed, or leaving the wall ends it
+                if player_data.jump_timer > 0.0
+                    || player_data.wall_run_timer <= 0.0
+                    || player_physics.velocity.y <= 0.0
+                    || player_physics.normal.length_squared() < EPSILON
+                {
+                    player_data.state = MovementState::Normal;
+                } else {
+                    player_physics.acceleration = Vec2::ZERO;
+                    player_physics.velocity.x = 0.0;
+                    player_physics.velocity.y -=
+                        gravity_strength * config.wall_run.gravity_multiplier * dt;
+
+                    player_physics.prev_position = player_transform.translation.xy();
+                    player_physics.map_vectors(|vector| frame.to_world(vector));
+                    let velocity_dt = player_physics.velocity * dt;
+                    player_transform.translation.x += velocity_dt.x;
+                    player_transform.translation.y += velocity_dt.y;
+                    return;
+                }
+            }
+        }
+
+        // Recovering from a ground pound landing: no steering or jumping
+        let recovering = player_data.pound_recovery_timer > 0.0;
+        let input_dir = if recovering { Vec2::ZERO } else { input_dir };
+        if recovering {
+            player_data.jump_timer = 0.0;
+        }
+
+        // Knocked back: reduced (or no) steering and no jumping until the stun wears off
+        if player_data.state == MovementState::Stunned && player_data.stun_timer <= 0.0 {
+            player_data.state = MovementState::Normal;
+        }
+        let stunned = player_data.state == MovementState::Stunned;
+        let input_dir = if stunned {
+            input_dir * config.stun_control
+        } else {
+            input_dir
+        };
+        if stunned {
+            player_data.jump_timer = 0.0;
+        }
+
+        // Use epsilon comparison for floating point values
+        let player_falling = player_physics.normal.length_squared() < EPSILON;
+        let no_input = input_dir.length_squared() < EPSILON;
+
+        // Analog deflection (1.0 for digital input); below the walk threshold the walk tier applies
+        let input_magnitude = input_dir.length().min(1.0);
+
+        // Rotate input according to the normal (compute locally, don't mutate resource)
+        let mut effective_input_dir = input_dir.normalize_or_zero();
+        if !no_input
+            && !player_falling
+            && input_dir.dot(player_physics.normal).abs() < NORMAL_DOT_THRESHOLD
+        {
+            let mut new_input_dir = Vec2::new(player_physics.normal.y, -player_physics.normal.x);
+
+            if new_input_dir.dot(input_dir) < 0.0 {
+                new_input_dir *= -1.0;
+            }
+
+            effective_input_dir = new_input_dir;
+        }
+
+        // Slopes steeper than the walkable angle (but not walls) can't be stood on
+        let min_ground_normal_y = config.max_slope_angle.to_radians().cos();
+        let player_on_steep_slope = !player_falling
+            && !player_data.sticky_contact
+            && -player_physics.normal.y < min_ground_normal_y
+            && player_physics.normal.x.abs() < NORMAL_DOT_THRESHOLD;
+
+        // If the player is on a wall and is trying to move away from it
+        let player_move_off_wall = player_physics.normal.x.abs() >= NORMAL_DOT_THRESHOLD
+            && effective_input_dir.x.abs() >= NORMAL_DOT_THRESHOLD
+            && player_physics.normal.x.signum() != effective_input_dir.x.signum();
+
+        // Sprinting
+        {
+            let player_grounded = player_data.grounded_timer > 0.0;
+            let has_stamina = config.sprint.stamina.is_none() || player_data.stamina > 0.0;
+            let wants_sprint = player_data.sprint_held && !no_input && has_stamina;
+
+            player_data.is_sprinting = match config.sprint.air_behavior {
+                SprintAirBehavior::Allow => wants_sprint,
+                SprintAirBehavior::KeepFromGround if !player_grounded => {
+                    player_data.is_sprinting && wants_sprint
+                }
+                SprintAirBehavior::GroundOnly if !player_grounded => false,
+                _ => wants_sprint,
+            };
+
+            if let Some(stamina) = &config.sprint.stamina {
+                if player_data.is_sprinting {
+                    player_data.stamina = (player_data.stamina - stamina.drain_rate * dt).max(0.0);
+                } else {
+                    player_data.stamina =
+                        (player_data.stamina + stamina.regen_rate * dt).min(stamina.max);
+                }
+            }
+        }
+
+        // Apex hang time: near the top of a jump, soften gravity and sharpen air control
+        let at_apex = player_falling
+            && player_physics.velocity.y.abs() < config.apex.velocity_threshold
+            && player_data.apex_time < config.apex.max_duration;
+        if at_apex {
+            player_data.apex_time += dt;
+        } else if !player_falling {
+            player_data.apex_time = 0.0;
+        }
+
+        // Glide: holding jump while falling slows the fall and widens the drift
+        player_data.state = if stunned {
+            MovementState::Stunned
+        } else if config.glide.enabled
+            && !player_data.jetpacking
+            && player_falling
+            && player_data.jump_held
+            && player_physics.velocity.y < 0.0
+        {
+            MovementState::Gliding
+        } else {
+            MovementState::Normal
+        };
+        let gliding = player_data.state == MovementState::Gliding;
+
+        let walking = !no_input && input_magnitude < config.walk.threshold;
+        let (max_speed, acceleration_curves) = if player_data.is_sprinting {
+            (config.sprint.max_speed, &config.sprint.acceleration)
+        } else if walking {
+            (config.walk.max_speed, &config.walk.acceleration)
+        } else {
+            (config.max_speed, &config.acceleration)
+        };
+        let (max_speed, acceleration_rates) = if player_falling {
+            (
+                config.max_air_speed.unwrap_or(max_speed),
+                acceleration_curves.air,
+            )
+        } else {
+            (max_speed, acceleration_curves.ground)
+        };
+        let max_speed = if gliding {
+            max_speed * config.glide.horizontal_speed_multiplier
+        } else {
+            max_speed
+        };
+        let turning_around = effective_input_dir.dot(player_physics.velocity) < 0.0;
+
+        // Slippery ground (ice) weakens braking and turning, not speeding up
+        let surface_friction = match player_data.ground_material(&level) {
+            Some(material) if !player_falling => material.friction,
+            _ => 1.0,
+        };
+        let acceleration_rate = if no_input || turning_around {
+            acceleration_rates.rate(no_input, turning_around) * surface_friction
+        } else {
+            acceleration_rates.rate(no_input, turning_around)
+        };
+
+        // Calculate acceleration (units: pixels/second²)
+        {
+            // Apply acceleration towards target velocity
+            // This creates smooth acceleration/deceleration
+            player_physics.acceleration =
+                (effective_input_dir * max_speed - player_physics.velocity) * acceleration_rate;
+
+            // Wall jump physics - reduce acceleration after wall jump
+            player_physics.acceleration *= match config.wall_jump.acceleration_reduction {
+                Some(reduction) if player_data.has_wall_jumped => reduction,
+                _ => 1.0,
+            };
+
+            // If the player is falling
+            if player_falling {
+                // Ignore any other acceleration in the y direction
+                player_physics.acceleration.y = 0.0;
+
+                if at_apex {
+                    player_physics.acceleration.x *= config.apex.acceleration_multiplier;
+                }
+            }
+            // Unless the player is on a wall and is trying to move away from it
+            if !player_move_off_wall {
+                // Remove the acceleration in the direction of the normal
+                // This prevents acceleration into walls
+                let acceleration_adjustment =
+                    player_physics.normal * player_physics.acceleration.dot(player_physics.normal);
+                player_physics.acceleration -= acceleration_adjustment;
+            }
+
+            // Control lockout after a boost: keep the boosted velocity, no steering or friction
+            if player_data.control_lock_timer > 0.0 {
+                player_physics.acceleration = Vec2::ZERO;
+            }
+
+            // Knocked back through the air without steering: keep the knockback velocity
+            if stunned && player_falling && no_input {
+                player_physics.acceleration = Vec2::ZERO;
+            }
+        }
+
+        // Apply gravity directly to velocity (not additive to acceleration)
+        // Gravity is a force that should be applied consistently each frame
+        {
+            // Jump cut: releasing jump while a jump is still rising shortens it
+            player_data.jump_held_in_jump =
+                player_data.jumping && (player_data.jump_held_in_jump || player_data.jump_held);
+            if player_data.jumping
+                && !player_data.jump_cut
+                && player_data.jump_held_in_jump
+                && !player_data.jump_held
+                && player_physics.velocity.y > EPSILON
+                && player_data.launch_timer <= 0.0
+            {
+                match config.jump_cut {
+                    JumpCut::Divide(divisor) => player_physics.velocity.y /= divisor,
+                    JumpCut::Velocity(max_velocity) => {
+                        player_physics.velocity.y = player_physics.velocity.y.min(max_velocity)
+                    }
+                    JumpCut::Gravity => {}
+                }
+                player_data.jump_cut = true;
+            }
+            if player_physics.velocity.y <= 0.0 {
+                player_data.jumping = false;
+                player_data.jump_cut = false;
+            }
+
+            if player_move_off_wall || player_falling || player_on_steep_slope {
+                let gravity_multiplier = if gliding {
+                    config.glide.gravity_multiplier
+                } else if at_apex {
+                    config.apex.gravity_multiplier
+                } else if player_physics.velocity.y <= 0.0 {
+                    config.gravity_multipliers.fall
+                } else if player_data.jump_cut {
+                    config.gravity_multipliers.jump_cut
+                } else {
+                    config.gravity_multipliers.rise
+                };
+
+                // Gravity goes down (negative local Y), sliding the player down steep slopes
+                player_physics.velocity.y -= gravity_strength * gravity_multiplier * dt;
+            } else {
+                // Gravity goes towards the normal (for wall/ceiling walking)
+                let gravity_normal_dir = player_physics.normal * gravity_strength * dt;
+                player_physics.velocity += gravity_normal_dir;
+            }
+        }
+
+        // Jumping
+        {
+            // Touching the ground or a wall refills air jumps
+            if player_data.grounded_timer > 0.0 || player_data.wall_timer > 0.0 {
+                player_data.air_jumps_used = 0;
+            }
+            // and the ground resets the count of jumps off the same wall
+            if player_data.grounded_timer > 0.0 {
+                player_data.same_wall_jumps = 0;
+            }
+            let air_jumps = config.air_jumps + assist.air_jumps();
+
+            // Consecutive wall jumps off the same wall are limited
+            let same_wall = player_data.wall_edge.is_some()
+                && player_data.wall_edge == player_data.wall_jump_edge;
+            let can_wall_jump = !same_wall
+                || config
+                    .wall_jump
+                    .max_same_wall_jumps
+                    .is_none_or(|max| player_data.same_wall_jumps < max);
+
+            // If the player is trying to jump
+            if player_data.jump_timer > 0.0 {
+                let on_one_way = player_data
+                    .ground_edge
+                    .is_some_and(|edge_id| level.polygons[edge_id.polygon].one_way);
+
+                // If holding down on a one-way platform
+                if on_one_way && input_dir.y <= -DROP_THROUGH_INPUT_THRESHOLD {
+                    // Drop through it
+                    player_data.drop_through_timer = DROP_THROUGH_TIME;
+                    player_data.jump_timer = 0.0;
+                    player_data.grounded_timer = 0.0;
+                }
+                // If on the ground with charge jumps
+                else if player_data.grounded_timer > 0.0 && config.charge_jump.is_some() {
+                    // Start charging, jumping on release
+                    player_data.jump_charge = Some(0.0);
+                    player_data.jump_timer = 0.0;
+                }
+                // If on the ground
+                else if player_data.grounded_timer > 0.0 {
+                    // Jump, keeping the momentum of a moving platform
+                    player_physics.velocity.y = JUMP_VELOCITY;
+                    player_physics.velocity += frame.to_local(player_data.platform_velocity);
+                    player_data.jump_timer = 0.0;
+                    player_data.grounded_timer = 0.0;
+                    player_data.jumping = true;
+                    player_data.jump_cut = false;
+                }
+                // If on a sticky ceiling
+                else if player_data.sticky_contact && player_physics.normal.y > 0.0 {
+                    // Push off it
+                    player_physics.velocity = player_physics
+                        .velocity
+                        .reject_from_normalized(player_physics.normal)
+                        - player_physics.normal * WALL_JUMP_VELOCITY_Y;
+                    player_data.jump_timer = 0.0;
+                }
+                // If on a wall that still allows a wall jump
+                else if player_data.wall_timer > 0.0 && can_wall_jump {
+                    // Wall jump
+                    player_physics.velocity.y = WALL_JUMP_VELOCITY_Y;
+                    player_physics.velocity.x = player_data.wall_direction * WALL_JUMP_VELOCITY_X;
+                    player_data.jump_timer = 0.0;
+                    player_data.wall_timer = 0.0;
+                    player_data.wall_direction = 0.0;
+                    player_data.has_wall_jumped = true;
+                    player_data.jumping = true;
+                    player_data.jump_cut = false;
+
+                    player_data.same_wall_jumps = if same_wall {
+                        player_data.same_wall_jumps + 1
+                    } else {
+                        1
+                    };
+                    player_data.wall_jump_edge = player_data.wall_edge;
+                    player_data.wall_regrab_timer = config.wall_jump.regrab_time;
+                }
+                // If holding down in the air with the ground pound available
+                else if config.ground_pound.enabled
+                    && input_dir.y <= -GROUND_POUND_INPUT_THRESHOLD
+                {
+                    // Stop and start the slam
+                    player_physics.velocity = Vec2::ZERO;
+                    player_data.state = MovementState::GroundPounding;
+                    player_data.jump_timer = 0.0;
+                }
+                // If the jetpack replaces the air jumps
+                else if config.jetpack.is_some() {
+                    // Start thrusting, while fuel is left
+                    if player_data.fuel > 0.0 {
+                        player_data.jetpacking = true;
+                        player_data.jump_timer = 0.0;
+                    }
+                }
+                // If air jumps are left
+                else if player_data.air_jumps_used < air_jumps {
+                    // Air jump
+                    player_physics.velocity.y = JUMP_VELOCITY;
+                    player_data.jump_timer = 0.0;
+                    player_data.air_jumps_used += 1;
+                    player_data.jumping = true;
+                    player_data.jump_cut = false;
+                }
+            }
+        }
+
+        // Charge jump: charge while jump stays held on the ground, jump when it's released
+        if let (Some(charge_jump), Some(charge)) = (&config.charge_jump, player_data.jump_charge) {
+            if player_data.grounded_timer <= 0.0 {
+                // Left the ground (past coyote time) before releasing: no jump
+                player_data.jump_charge = None;
+            } else if player_data.jump_held {
+                player_data.jump_charge = Some((charge + dt).min(charge_jump.charge_time));
+            } else {
+                // Jump, keeping the momentum of a moving platform
+                player_physics.velocity.y = charge_jump
+                    .min_velocity
+                    .lerp(charge_jump.max_velocity, charge / charge_jump.charge_time);
+                player_physics.velocity += frame.to_local(player_data.platform_velocity);
+                player_data.grounded_timer = 0.0;
+                player_data.jump_charge = None;
+                player_data.jumping = true;
+                player_data.jump_cut = false;
+            }
+        }
+
+        // Jetpack: thrust while jump stays held in the air, recharge on the ground
+        if let Some(jetpack) = &config.jetpack {
+            if player_data.jetpacking
+                && player_data.jump_held
+                && player_falling
+                && player_data.fuel > 0.0
+            {
+                if player_physics.velocity.y < jetpack.max_rise_speed {
+                    player_physics.velocity.y = (player_physics.velocity.y + jetpack.thrust * dt)
+                        .min(jetpack.max_rise_speed);
+                }
+                player_data.fuel = (player_data.fuel - dt).max(0.0);
+            } else {
+                player_data.jetpacking = false;
+            }
+
+            if player_data.ground_contact {
+                player_data.fuel =
+                    (player_data.fuel + jetpack.recharge_rate * dt).min(jetpack.fuel);
+            }
+        }
+
+        // Update physics using semi-implicit Euler integration
+        // 1. Update velocity: v(t+dt) = v(t) + a(t) * dt
+        // 2. Update position: x(t+dt) = x(t) + v(t+dt) * dt
+        // This is more stable than explicit Euler and preserves energy better
+        player_physics.prev_position = player_transform.translation.xy();
+
+        // Apply acceleration to velocity (scaled by delta time)
+        let acceleration_dt = player_physics.acceleration * dt;
+        player_physics.velocity += acceleration_dt;
+
+        // Clamp to terminal velocity
+        let max_fall_speed = if gliding {
+            config.glide.max_fall_speed
+        } else {
+            config.max_fall_speed
+        };
+        player_physics.velocity.y = player_physics.velocity.y.max(-max_fall_speed);
+        if let Some(max_horizontal_speed) = config.max_horizontal_speed {
+            player_physics.velocity.x = player_physics
+                .velocity
+                .x
+                .clamp(-max_horizontal_speed, max_horizontal_speed);
+        }
+
+        // Back to world space
+        player_physics.map_vectors(|vector| frame.to_world(vector));
+
+        // Update position using new velocity (scaled by delta time)
+        let velocity_dt = player_physics.velocity * dt;
+        player_transform.translation.x += velocity_dt.x;
+        player_transform.translation.y += velocity_dt.y;
+    }
+}
+
+/// Render system
+pub fn s_render(
+    mut gizmos: Gizmos,
+    player_query: Query<(&Transform, &Physics, &Player)>,
+    level: Res<Level>,
+    gravity: Res<Gravity>,
+    config: Res<ControllerConfig>,
+) {
+    if let Ok((player_transform, player_physics, player_data)) = player_query.single() {
+        // Draw player
+        let player_pos = player_transform.translation.xy();
+        let player_color = if player_data.state == MovementState::Stunned {
+            STUNNED_COLOR
+        } else {
+            Color::WHITE
+        };
+        gizmos.circle_2d(player_pos, player_physics.radius, player_color);
+
+        // Draw the jetpack fuel gauge above the player while it isn't full
+        if let Some(jetpack) = &config.jetpack {
+            if player_data.fuel < jetpack.fuel {
+                draw_gauge(
+                    &mut gizmos,
+                    player_pos + Vec2::Y * GAUGE_OFFSET,
+                    player_data.fuel / jetpack.fuel,
+                    FUEL_COLOR,
+                );
+            }
+        }
+
+        // Draw the jump charge gauge below the player while charging
+        if let (Some(charge_jump), Some(charge)) = (&config.charge_jump, player_data.jump_charge) {
+            draw_gauge(
+                &mut gizmos,
+                player_pos - Vec2::Y * GAUGE_OFFSET,
+                charge / charge_jump.charge_time,
+                CHARGE_COLOR,
+            );
+        }
+
+        // Draw level
+        for polygon in level.polygons.iter().filter(|polygon| polygon.enabled) {
+            gizmos.linestrip_2d(polygon.points.iter().copied(), polygon.color);
+        }
+
+        for arc in &level.arcs {
+            gizmos.linestrip_2d(
+                (0..=ARC_DRAW_SEGMENTS).map(|i| arc.point_at(i as f32 / ARC_DRAW_SEGMENTS as f32)),
+                ARC_COLOR,
+            );
+        }
+
+        // Draw ladders and water
+        for volume in &level.volumes {
+            let color = match volume.kind {
+                VolumeKind::Ladder => LADDER_COLOR,
+                VolumeKind::Water => WATER_COLOR,
+                VolumeKind::Hazard => HAZARD_COLOR,
+            };
+            gizmos.rect_2d(
+                Isometry2d::from_translation((volume.aabb.min + volume.aabb.max) / 2.0),
+                volume.aabb.max - volume.aabb.min,
+                color,
+            );
+        }
+
+        // Draw rails
+        for rail in &level.rails {
+            gizmos.linestrip_2d(rail.points.iter().copied(), RAIL_COLOR);
+        }
+
+        // Draw gravity zones
+        for zone in &gravity.zones {
+            gizmos.linestrip_2d(zone.points.iter().copied(), GRAVITY_ZONE_COLOR);
+        }
+    }
+}
+
+/// Horizontal bar centered on `center`, filled to `fraction` (0.0..1.0)
+fn draw_gauge(gizmos: &mut Gizmos, center: Vec2, fraction: f32, color: Color) {
+    let start = center - Vec2::X * GAUGE_WIDTH / 2.0;
+    gizmos.line_2d(start, start + Vec2::X * GAUGE_WIDTH, GAUGE_EMPTY_COLOR);
+    gizmos.line_2d(start, start + Vec2::X * GAUGE_WIDTH * fraction, color);
+}
+
+/// Timer system: Decrements all timers by delta time
+pub fn s_timers(time: Res<Time>, mut player_query: Query<&mut Player>) {
+    if let Ok(mut player_data) = player_query.single_mut() {
+        let dt = time.delta_secs() * player_data.time_scale;
+
+        if player_data.jump_timer > 0.0 {
+            player_data.jump_timer -= dt;
+            if player_data.jump_timer < 0.0 {
+                player_data.jump_timer = 0.0;
+            }
+        }
+
+        if player_data.grounded_timer > 0.0 {
+            player_data.grounded_timer -= dt;
+            if player_data.grounded_timer < 0.0 {
+                player_data.grounded_timer = 0.0;
+                player_data.is_grounded = false;
+            } else {
+                player_data.is_grounded = true;
+            }
+        } else {
+            player_data.is_grounded = false;
+        }
+
+        if player_data.launch_timer > 0.0 {
+            player_data.launch_timer = (player_data.launch_timer - dt).max(0.0);
+        }
+
+        if player_data.control_lock_timer > 0.0 {
+            player_data.control_lock_timer = (player_data.control_lock_timer - dt).max(0.0);
+        }
+
+        if player_data.drop_through_timer > 0.0 {
+            player_data.drop_through_timer = (player_data.drop_through_timer - dt).max(0.0);
+        }
+
+        if player_data.pound_recovery_timer > 0.0 {
+            player_data.pound_recovery_timer = (player_data.pound_recovery_timer - dt).max(0.0);
+        }
+
+        if player_data.wall_run_timer > 0.0 {
+            player_data.wall_run_timer = (player_data.wall_run_timer - dt).max(0.0);
+        }
+
+        if player_data.stun_timer > 0.0 {
+            player_data.stun_timer = (player_data.stun_timer - dt).max(0.0);
+        }
+
+        if player_data.wall_regrab_timer > 0.0 {
+            player_data.wall_regrab_timer = (player_data.wall_regrab_timer - dt).max(0.0);
+        }
+
+        if player_data.wall_timer > 0.0 {
+            player_data.wall_timer -= dt;
+            if player_data.wall_timer < 0.0 {
+                player_data.wall_timer = 0.0;
+                player_data.wall_direction = 0.0;
+                player_data.wall_edge = None;
+            }
+        }
+    }
+}
+
+/// Exit system: Handles clean application exit after all other systems complete
+/// This runs last in the update loop to ensure no race conditions with other systems
+pub fn s_exit(should_exit: Res<ShouldExit>, mut exit: MessageWriter<AppExit>) {
+    if should_exit.0 {
+        exit.write(AppExit::Success);
+    }
+}
@@ -0,0 +1,369 @@
+use std::f32::consts::TAU;
+
+use bevy::{math::Affine2, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    collisions::CrushedEvent,
+    death::{s_respawn, PlayerKilled, PlayerRespawned, SpawnPoint},
+    level::Polygon,
+    schedule::ControllerPhase,
+    time_bubble::{time_scale_at, TimeBubble},
+    Level, Player,
+};
+
+pub const MOVING_PLATFORM_COLOR: Color = Color::srgb(0.4, 0.7, 0.7);
+// Time step (seconds) over which point velocities are sampled along the path
+const VELOCITY_SAMPLE_TIME: f32 = 1.0 / 60.0;
+
+/// Kinematic platforms following waypoint paths and rotating about a pivot, carrying the
+/// player riding them and passing their velocity on when the player jumps off
+///
+/// Platforms (e.g. elevators) squeezing the player against other geometry either kill them or
+/// stop.
+pub struct MovingPlatformPlugin;
+
+impl Plugin for MovingPlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, s_moving_platforms.before(ControllerPhase::Input))
+            .add_systems(
+                Update,
+                s_kinematic_colliders
+                    .after(s_moving_platforms)
+                    .before(ControllerPhase::Input),
+            )
+            .add_systems(
+                Update,
+                s_crush_platforms
+                    .after(ControllerPhase::Collision)
+                    .before(s_respawn),
+            )
+            .add_systems(Update, s_reset_moving_platforms.after(s_respawn));
+    }
+}
+
+/// What a platform does when it crushes the player
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CrushResponse {
+    /// The player dies
+    #[default]
+    Kill,
+    /// The platform stops where it was until the player gets out of the way
+    Stop,
+}
+
+/// `Level` polygon moved along a path at constant speed and spun at a constant rate
+#[derive(Component)]
+pub struct MovingPlatform {
+    /// Index of the platform's polygon in `Level::polygons`
+    pub polygon: usize,
+    /// Outline at the start of the path
+    pub base_points: Vec<Vec2>,
+    /// Offsets from the outline's start position to visit in order (the first is zero)
+    pub waypoints: Vec<Vec2>,
+    /// Speed along the path (pixels/second)
+    pub speed: f32,
+    /// Whether the path returns from the last waypoint to the first (otherwise it ping-pongs)
+    pub looped: bool,
+    /// Rotation center at the start of the path (world space)
+    pub pivot: Vec2,
+    /// Rotation speed (radians/second, counterclockwise)
+    pub angular_speed: f32,
+    /// Distance (pixels) travelled since the level started or the player respawned
+    pub distance: f32,
+    /// Rotation (radians) since the level started or the player respawned
+    pub angle: f32,
+    /// `distance` and `angle` before the last update, for stopping on a crush
+    pub prev_distance: f32,
+    pub prev_angle: f32,
+    pub crush: CrushResponse,
+}
+
+impl MovingPlatform {
+    /// Path segments as (start, end) offsets, including the closing one for looped paths
+    fn segments(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let closing = self
+            .looped
+            .then(|| {
+                self.waypoints
+                    .last()
+                    .copied()
+                    .zip(self.waypoints.first().copied())
+            })
+            .flatten();
+        self.waypoints
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .chain(closing)
+    }
+
+    /// Offset from the start position after travelling `distance` along the path
+    pub fn offset_at(&self, distance: f32) -> Vec2 {
+        let length: f32 = self.segments().map(|(a, b)| a.distance(b)).sum();
+        if length <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+
+        // Looped paths wrap around; open paths run back and forth
+        let mut remaining = if self.looped {
+            distance.rem_euclid(length)
+        } else {
+            let t = distance.rem_euclid(2.0 * length);
+            if t > length {
+                2.0 * length - t
+            } else {
+                t
+            }
+        };
+
+        for (a, b) in self.segments() {
+            let segment_length = a.distance(b);
+            if remaining <= segment_length {
+                return a.lerp(b, remaining / segment_length.max(f32::EPSILON));
+            }
+            remaining -= segment_length;
+        }
+        self.waypoints.last().copied().unwrap_or_default()
+    }
+
+    /// Transform from the outline at the start of the path after travelling `distance` and
+    /// turning by `angle`
+    pub fn transform_at(&self, distance: f32, angle: f32) -> Affine2 {
+        Affine2::from_translation(self.pivot + self.offset_at(distance))
+            * Affine2::from_angle(angle)
+            * Affine2::from_translation(-self.pivot)
+    }
+
+    /// Current transform from the outline at the start of the path
+    pub fn transform(&self) -> Affine2 {
+        self.transform_at(self.distance, self.angle)
+    }
+
+    /// Velocity (pixels/second) of a point given in the start frame, moving with the platform
+    pub fn velocity_at(&self, point: Vec2) -> Vec2 {
+        let next = self.transform_at(
+            self.distance + self.speed * VELOCITY_SAMPLE_TIME,
+            self.angle + self.angular_speed * VELOCITY_SAMPLE_TIME,
+        );
+        (next.transform_point2(point) - self.transform().transform_point2(point))
+            / VELOCITY_SAMPLE_TIME
+    }
+
+    /// Outline after the transform
+    fn outline(&self, transform: Affine2) -> Vec<Vec2> {
+        self.base_points
+            .iter()
+            .map(|&point| transform.transform_point2(point))
+            .collect()
+    }
+}
+
+/// `Level` polygon following its entity's `Transform` (translation and rotation about z), for
+/// geometry gameplay code animates itself (doors, crushers, swinging props)
+///
+/// The polygon stays in the level when the entity is despawned; disable it with
+/// `Polygon::enabled`.
+#[derive(Component)]
+pub struct KinematicCollider {
+    /// Index of the collider's polygon in `Level::polygons`
+    pub polygon: usize,
+    /// Closed outline in the entity's local frame
+    pub local_points: Vec<Vec2>,
+    /// Transform the outline was last placed with
+    placed: Affine2,
+}
+
+impl KinematicCollider {
+    /// Adds the outline (closed, in the entity's local frame) to the level, placed at
+    /// `transform`
+    pub fn new(
+        level: &mut Level,
+        local_points: Vec<Vec2>,
+        transform: &Transform,
+        color: Color,
+    ) -> Self {
+        let placed = transform_2d(transform);
+        level.polygons.push(Polygon::new(
+            local_points
+                .iter()
+                .map(|&point| placed.transform_point2(point))
+                .collect(),
+            color,
+        ));
+
+        Self {
+            polygon: level.polygons.len() - 1,
+            local_points,
+            placed,
+        }
+    }
+}
+
+/// 2D part of a transform: translation and rotation about z
+fn transform_2d(transform: &Transform) -> Affine2 {
+    let (angle, _, _) = transform.rotation.to_euler(EulerRot::ZYX);
+    Affine2::from_angle_translation(angle, transform.translation.xy())
+}
+
+/// Moves platforms along their paths, carries the player standing on one and records the
+/// velocity they inherit when jumping off
+///
+/// Runs before the controller so collision resolves against this frame's geometry.
+pub fn s_moving_platforms(
+    time: Res<Time>,
+    mut level: ResMut<Level>,
+    mut player_query: Query<(&mut Transform, &mut Player)>,
+    mut platform_query: Query<&mut MovingPlatform>,
+    bubble_query: Query<&TimeBubble>,
+) {
+    let mut player = player_query.single_mut().ok();
+    if let Some((_, player_data)) = &mut player {
+        player_data.platform_velocity = Vec2::ZERO;
+    }
+
+    for mut platform in &mut platform_query {
+        // Platforms inside timescale bubbles slow down or speed up with them
+        let aabb = level.polygons[platform.polygon].aabb;
+        let center = (aabb.min + aabb.max) / 2.0;
+        let dt = time.delta_secs() * time_scale_at(&bubble_query, center);
+
+        let previous = platform.transform();
+        platform.prev_distance = platform.distance;
+        platform.prev_angle = platform.angle;
+        platform.distance += platform.speed * dt;
+        platform.angle = (platform.angle + platform.angular_speed * dt).rem_euclid(TAU);
+        let current = platform.transform();
+
+        level.polygons[platform.polygon].move_to(platform.outline(current));
+
+        // Carry the player riding the platform, tangentially on rotating ones
+        if let Some((player_transform, player_data)) = &mut player {
+            let riding = player_data
+                .ground_edge
+                .is_some_and(|edge_id| edge_id.polygon == platform.polygon);
+
+            if riding {
+                let position = player_transform.translation.xy();
+                let delta = (current * previous.inverse()).transform_point2(position) - position;
+                player_transform.translation += delta.extend(0.0);
+                if dt > 0.0 {
+                    player_data.platform_velocity = delta / dt;
+                }
+            }
+        }
+    }
+}
+
+/// Moves kinematic collider polygons to their entities' transforms and carries the player
+/// riding one, like `s_moving_platforms`
+///
+/// A collider that stopped keeps its last outline as the previous one, so its edges report no
+/// velocity to contacts.
+pub fn s_kinematic_colliders(
+    time: Res<Time>,
+    mut level: ResMut<Level>,
+    mut player_query: Query<(&mut Transform, &mut Player), Without<KinematicCollider>>,
+    mut collider_query: Query<(&Transform, &mut KinematicCollider)>,
+) {
+    let mut player = player_query.single_mut().ok();
+    let dt = time.delta_secs();
+
+    for (transform, mut collider) in &mut collider_query {
+        let current = transform_2d(transform);
+        let polygon = &mut level.polygons[collider.polygon];
+
+        if current == collider.placed {
+            if polygon.prev_points != polygon.points {
+                polygon.prev_points.clone_from(&polygon.points);
+            }
+            continue;
+        }
+
+        polygon.move_to(
+            collider
+                .local_points
+                .iter()
+                .map(|&point| current.transform_point2(point))
+                .collect(),
+        );
+
+        if let Some((player_transform, player_data)) = &mut player {
+            let riding = player_data
+                .ground_edge
+                .is_some_and(|edge_id| edge_id.polygon == collider.polygon);
+
+            if riding {
+                let position = player_transform.translation.xy();
+                let delta =
+                    (current * collider.placed.inverse()).transform_point2(position) - position;
+                player_transform.translation += delta.extend(0.0);
+                if dt > 0.0 {
+                    player_data.platform_velocity = delta / dt;
+                }
+            }
+        }
+
+        collider.placed = current;
+    }
+}
+
+/// Applies the crush response of platforms squeezing the player: kills them, or moves the
+/// platform back to where it was before this frame's update
+pub fn s_crush_platforms(
+    mut crushed_reader: MessageReader<CrushedEvent>,
+    mut killed_writer: MessageWriter<PlayerKilled>,
+    mut level: ResMut<Level>,
+    mut platform_query: Query<&mut MovingPlatform>,
+) {
+    for crushed in crushed_reader.read() {
+        for mut platform in &mut platform_query {
+            if !crushed.polygons.contains(&platform.polygon) {
+                continue;
+            }
+
+            match platform.crush {
+                CrushResponse::Kill => {
+                    killed_writer.write(PlayerKilled {
+                        position: crushed.position,
+                    });
+                }
+                CrushResponse::Stop => {
+                    platform.distance = platform.prev_distance;
+                    platform.angle = platform.prev_angle;
+                    let outline = platform.outline(platform.transform());
+                    level.polygons[platform.polygon].move_to(outline);
+                }
+            }
+        }
+    }
+}
+
+/// Sends platforms back to the start of their paths when the player respawns
+///
+/// The platform carrying the spawn point keeps going, so the player respawns where it is.
+pub fn s_reset_moving_platforms(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    spawn_point: Res<SpawnPoint>,
+    mut level: ResMut<Level>,
+    mut platform_query: Query<(Entity, &mut MovingPlatform)>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    for (entity, mut platform) in &mut platform_query {
+        if spawn_point
+            .platform
+            .is_some_and(|(spawn_platform, _)| spawn_platform == entity)
+        {
+            continue;
+        }
+
+        platform.distance = 0.0;
+        platform.angle = 0.0;
+        platform.prev_distance = 0.0;
+        platform.prev_angle = 0.0;
+        level.polygons[platform.polygon].reset_to(platform.base_points.clone());
+    }
+}
@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{GroundPoundLanding, LandingContact},
+    geometry::line_intersect,
+    schedule::ControllerSystems,
+    Level, Player,
+};
+
+/// Noise propagation: loud actions emit `NoiseEvent`s that AI can query to investigate
+pub struct NoisePlugin;
+
+impl Plugin for NoisePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<NoiseEvent>()
+            .init_resource::<NoiseConfig>()
+            .init_resource::<RecentNoises>()
+            .add_systems(Update, s_landing_noise.after(ControllerSystems))
+            .add_systems(Update, s_ground_pound_noise.after(ControllerSystems))
+            .add_systems(
+                Update,
+                s_track_noises
+                    .after(s_landing_noise)
+                    .after(s_ground_pound_noise),
+            );
+    }
+}
+
+/// A sound emitted into the world
+#[derive(Message, Clone, Copy)]
+pub struct NoiseEvent {
+    /// World position of the noise
+    pub position: Vec2,
+    /// Distance (pixels) at which the noise becomes inaudible
+    pub radius: f32,
+    /// Loudness at the source (unitless, 1.0 = normal)
+    pub loudness: f32,
+    /// What produced the noise
+    pub kind: NoiseKind,
+}
+
+/// Source action of a noise
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NoiseKind {
+    /// Player landed faster than `NoiseConfig::hard_landing_speed`
+    HardLanding,
+    /// Ground pound hit the ground
+    GroundPound,
+    /// A breakable tile shattered
+    BreakableBroken,
+}
+
+/// Noise tuning
+#[derive(Resource, Clone)]
+pub struct NoiseConfig {
+    /// Minimum downward impact speed (pixels/second) for a landing to make noise
+    pub hard_landing_speed: f32,
+    /// Audible radius of a hard landing (pixels)
+    pub landing_radius: f32,
+    /// Loudness of a hard landing
+    pub landing_loudness: f32,
+    /// Audible radius of a ground pound impact (pixels)
+    pub ground_pound_radius: f32,
+    /// Loudness of a ground pound impact
+    pub ground_pound_loudness: f32,
+    /// Audible radius of a breakable shattering (pixels)
+    pub break_radius: f32,
+    /// Loudness of a breakable shattering
+    pub break_loudness: f32,
+    /// Loudness multiplier per level edge between source and listener (`None` disables occlusion)
+    pub occlusion_factor: Option<f32>,
+    /// How long (seconds) noises stay queryable in `RecentNoises`
+    pub memory_duration: f32,
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        Self {
+            hard_landing_speed: 600.0,
+            landing_radius: 320.0,
+            landing_loudness: 1.0,
+            ground_pound_radius: 400.0,
+            ground_pound_loudness: 1.5,
+            break_radius: 480.0,
+            break_loudness: 2.0,
+            occlusion_factor: Some(0.5),
+            memory_duration: 1.0,
+        }
+    }
+}
+
+/// Noises emitted recently, kept around so AI can poll them on its own schedule
+#[derive(Resource, Default)]
+pub struct RecentNoises {
+    /// (noise, age in seconds)
+    pub noises: Vec<(NoiseEvent, f32)>,
+}
+
+impl RecentNoises {
+    /// Loudest noise audible at `listener`, with its perceived loudness
+    pub fn loudest_audible(
+        &self,
+        listener: Vec2,
+        level: &Level,
+        config: &NoiseConfig,
+    ) -> Option<(NoiseEvent, f32)> {
+        self.noises
+            .iter()
+            .map(|(noise, _)| (*noise, perceived_loudness(noise, listener, level, config)))
+            .filter(|(_, loudness)| *loudness > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Loudness of `noise` as heard from `listener`
+///
+/// Falls off linearly to zero at the noise radius, and is reduced by
+/// `occlusion_factor` for every level edge crossed on the way.
+pub fn perceived_loudness(
+    noise: &NoiseEvent,
+    listener: Vec2,
+    level: &Level,
+    config: &NoiseConfig,
+) -> f32 {
+    let distance = noise.position.distance(listener);
+    if distance >= noise.radius {
+        return 0.0;
+    }
+
+    let mut loudness = noise.loudness * (1.0 - distance / noise.radius);
+
+    if let Some(occlusion_factor) = config.occlusion_factor {
+        for polygon in level.polygons.iter().filter(|polygon| polygon.enabled) {
+            for i in 1..polygon.points.len() {
+                if line_intersect(
+                    polygon.points[i - 1],
+                    polygon.points[i],
+                    noise.position,
+                    listener,
+                )
+                .is_some()
+                {
+                    loudness *= occlusion_factor;
+                }
+            }
+        }
+    }
+
+    loudness
+}
+
+/// Emits a noise when the player lands hard
+///
+/// Reads the gravity-relative `LandingContact::impact_speed`, so landings under flipped,
+/// radial or zone gravity are as loud as ordinary ones.
+pub fn s_landing_noise(
+    player_query: Query<&Transform, With<Player>>,
+    config: Res<NoiseConfig>,
+    mut landing_reader: MessageReader<LandingContact>,
+    mut noise_writer: MessageWriter<NoiseEvent>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+
+    for landing in landing_reader.read() {
+        if landing.impact_speed >= config.hard_landing_speed {
+            noise_writer.write(NoiseEvent {
+                position: player_transform.translation.xy(),
+                radius: config.landing_radius,
+                loudness: config.landing_loudness,
+                kind: NoiseKind::HardLanding,
+            });
+        }
+    }
+}
+
+/// Emits a noise where a ground pound hits the ground
+pub fn s_ground_pound_noise(
+    config: Res<NoiseConfig>,
+    mut pound_reader: MessageReader<GroundPoundLanding>,
+    mut noise_writer: MessageWriter<NoiseEvent>,
+) {
+    for landing in pound_reader.read() {
+        noise_writer.write(NoiseEvent {
+            position: landing.position,
+            radius: config.ground_pound_radius,
+            loudness: config.ground_pound_loudness,
+            kind: NoiseKind::GroundPound,
+        });
+    }
+}
+
+/// Ages remembered noises and records newly emitted ones
+pub fn s_track_noises(
+    time: Res<Time>,
+    config: Res<NoiseConfig>,
+    mut recent_noises: ResMut<RecentNoises>,
+    mut noise_reader: MessageReader<NoiseEvent>,
+) {
+    let dt = time.delta_secs();
+
+    recent_noises.noises.retain_mut(|(_, age)| {
+        *age += dt;
+        *age < config.memory_duration
+    });
+
+    for noise in noise_reader.read() {
+        recent_noises.noises.push((*noise, 0.0));
+    }
+}
@@ -0,0 +1,174 @@
+use bevy::{input::ButtonInput, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    collisions::circle_overlaps_polygon,
+    death::{s_respawn, PlayerKilled, PlayerRespawned},
+    levels::GameState,
+    s_render,
+    schedule::ControllerPhase,
+    Level, Physics, Player,
+};
+
+pub const RED_PHASE_COLOR: Color = Color::srgb(0.9, 0.2, 0.2);
+pub const BLUE_PHASE_COLOR: Color = Color::srgb(0.2, 0.4, 1.0);
+// Alpha of the inactive phase's outlines
+const GHOST_ALPHA: f32 = 0.25;
+
+/// Red/blue phase blocks: two geometry sets of which only the active one is solid, switched
+/// with Q or on the level's rhythm timer
+///
+/// Switching while overlapping a block that becomes solid kills the player.
+pub struct PhaseBlocksPlugin;
+
+impl Plugin for PhaseBlocksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhaseSwitch>()
+            .add_systems(
+                Update,
+                s_switch_phase
+                    .before(ControllerPhase::Input)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, s_reset_phase.after(s_respawn))
+            .add_systems(Update, s_render_phase_blocks.after(s_render));
+    }
+}
+
+/// Geometry set a phase block belongs to
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseColor {
+    #[default]
+    Red,
+    Blue,
+}
+
+impl PhaseColor {
+    pub fn other(self) -> Self {
+        match self {
+            Self::Red => Self::Blue,
+            Self::Blue => Self::Red,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Red => RED_PHASE_COLOR,
+            Self::Blue => BLUE_PHASE_COLOR,
+        }
+    }
+}
+
+/// Currently solid phase and the rhythm timer (set when the level spawns)
+#[derive(Resource, Default)]
+pub struct PhaseSwitch {
+    pub active: PhaseColor,
+    /// Time (seconds) between automatic switches (`None` switches only on Q)
+    pub interval: Option<f32>,
+    /// Time (seconds) since the last switch
+    pub timer: f32,
+}
+
+/// Polygon in `Level` that is only solid while its phase is active
+#[derive(Component)]
+pub struct PhaseBlock {
+    /// Index of the polygon in `Level::polygons`
+    pub polygon: usize,
+    pub phase: PhaseColor,
+}
+
+/// Enables the polygons of the active phase's blocks and disables the others
+pub fn apply_phase<'a>(
+    level: &mut Level,
+    blocks: impl IntoIterator<Item = &'a PhaseBlock>,
+    active: PhaseColor,
+) {
+    for block in blocks {
+        level.polygons[block.polygon].enabled = block.phase == active;
+    }
+}
+
+/// Q or the rhythm timer switches the active phase
+///
+/// Runs before the controller so collision sees the new geometry. The player dies if a block
+/// turning solid overlaps them.
+pub fn s_switch_phase(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut phase_switch: ResMut<PhaseSwitch>,
+    mut level: ResMut<Level>,
+    block_query: Query<&PhaseBlock>,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+    mut killed_writer: MessageWriter<PlayerKilled>,
+) {
+    phase_switch.timer += time.delta_secs();
+
+    let rhythm = phase_switch
+        .interval
+        .is_some_and(|interval| phase_switch.timer >= interval);
+    if !rhythm && !keyboard_input.just_pressed(KeyCode::KeyQ) {
+        return;
+    }
+    if block_query.is_empty() {
+        return;
+    }
+
+    phase_switch.active = phase_switch.active.other();
+    phase_switch.timer = 0.0;
+    apply_phase(&mut level, &block_query, phase_switch.active);
+
+    if let Ok((player_transform, player_physics)) = player_query.single() {
+        let player_pos = player_transform.translation.xy();
+        let crushed = block_query
+            .iter()
+            .filter(|block| block.phase == phase_switch.active)
+            .any(|block| {
+                circle_overlaps_polygon(
+                    &level.polygons[block.polygon],
+                    player_pos,
+                    player_physics.radius,
+                )
+            });
+
+        if crushed {
+            killed_writer.write(PlayerKilled {
+                position: player_pos,
+            });
+        }
+    }
+}
+
+/// Goes back to the red phase when the player respawns
+pub fn s_reset_phase(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    mut phase_switch: ResMut<PhaseSwitch>,
+    mut level: ResMut<Level>,
+    block_query: Query<&PhaseBlock>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    phase_switch.active = PhaseColor::Red;
+    phase_switch.timer = 0.0;
+    apply_phase(&mut level, &block_query, PhaseColor::Red);
+}
+
+/// Draws the inactive phase's blocks ghosted (the active ones render as level polygons)
+pub fn s_render_phase_blocks(
+    mut gizmos: Gizmos,
+    phase_switch: Res<PhaseSwitch>,
+    level: Res<Level>,
+    block_query: Query<&PhaseBlock>,
+) {
+    for block in block_query
+        .iter()
+        .filter(|block| block.phase != phase_switch.active)
+    {
+        gizmos.linestrip_2d(
+            level.polygons[block.polygon].points.iter().copied(),
+            block.phase.color().with_alpha(GHOST_ALPHA),
+        );
+    }
+}
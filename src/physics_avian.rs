@@ -0,0 +1,402 @@
+//! avian2d collision backend (enabled by the `physics-avian` feature)
+//!
+//! Games already on avian keep a single collision world: `ControllerSchedule::avian_collision`
+//! swaps the collision phase for `s_avian_collision`, which resolves the player against the
+//! avian colliders through `SpatialQuery`, and `AvianColliders` offers the `Level` queries
+//! (raycast, shapecast, overlap_circle) against the same colliders. avian's own plugins are up
+//! to the app.
+
+use avian2d::prelude::{
+    Collider, CollisionLayers as AvianLayers, LayerMask, Position, RigidBody, Rotation, Sensor,
+    ShapeCastConfig, SpatialQuery, SpatialQueryFilter,
+};
+use bevy::{ecs::system::SystemParam, platform::time::Instant, prelude::*};
+
+use crate::{
+    collisions::{
+        combine_contact_normals, s_refresh_broad_phase, solve_penetrations, CollisionDiagnostics,
+        CollisionLayers, CollisionOutputs, ContactEvent, GroundPoundLanding, LandingContact,
+        CCD_MIN_MOTION_RATIO, TOUCH_THRESHOLD,
+    },
+    config::ControllerConfig,
+    gravity::Gravity,
+    schedule::{ControllerPhase, ControllerSchedule},
+    MovementState, Physics, Player, GROUND_NORMAL_Y_THRESHOLD, MAX_GROUNDED_TIMER,
+    MAX_WALLED_TIMER, NORMAL_DOT_THRESHOLD,
+};
+
+impl ControllerSchedule {
+    /// Resolves the player against avian colliders (`s_avian_collision`) instead of `Level`
+    pub fn avian_collision(self) -> Self {
+        self.replace(
+            ControllerPhase::Collision,
+            (s_refresh_broad_phase, s_avian_collision).chain(),
+        )
+    }
+}
+
+/// Where a ray hit an avian collider (`AvianColliders::raycast`)
+#[derive(Clone, Copy, Debug)]
+pub struct ColliderRayHit {
+    pub entity: Entity,
+    /// Hit point (world space)
+    pub point: Vec2,
+    /// Unit surface normal at `point` (world space)
+    pub normal: Vec2,
+    /// Distance (pixels) from the origin to `point`
+    pub t: f32,
+}
+
+/// Where a swept circle first touched an avian collider (`AvianColliders::shapecast`)
+#[derive(Clone, Copy, Debug)]
+pub struct ColliderShapeHit {
+    pub entity: Entity,
+    /// Center of the circle at the time of impact (world space)
+    pub position: Vec2,
+    /// Contact point on the collider (world space)
+    pub point: Vec2,
+    /// Unit direction from the contact point toward the circle's center
+    pub normal: Vec2,
+    /// Distance (pixels) the circle travelled before touching
+    pub t: f32,
+}
+
+/// Circle overlapping an avian collider (`AvianColliders::overlap_circle`)
+#[derive(Clone, Copy, Debug)]
+pub struct ColliderContact {
+    pub entity: Entity,
+    /// Closest point of the collider's surface to the circle's center (world space)
+    pub point: Vec2,
+    /// Unit direction out of the collider through `point`
+    pub normal: Vec2,
+    /// How far the circle would have to move along `normal` to clear the collider
+    pub depth: f32,
+}
+
+/// Spatial queries against the avian colliders the controller collides with
+///
+/// Sensors and colliders on dynamic bodies are left to avian. Like `Level` polygons, a collider
+/// and a query interact when each is a member of a layer the other filters for; colliders
+/// without avian `CollisionLayers` collide with every layer.
+#[derive(SystemParam)]
+pub struct AvianColliders<'w, 's> {
+    spatial_query: SpatialQuery<'w, 's>,
+    collider_query: Query<
+        'w,
+        's,
+        (
+            &'static Collider,
+            &'static Position,
+            &'static Rotation,
+            Option<&'static RigidBody>,
+            Option<&'static AvianLayers>,
+        ),
+        Without<Sensor>,
+    >,
+}
+
+impl AvianColliders<'_, '_> {
+    /// First collider the ray from `origin` along `direction` hits within `max_distance`
+    pub fn raycast(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        mask: CollisionLayers,
+    ) -> Option<ColliderRayHit> {
+        let direction = Dir2::new(direction).ok()?;
+
+        self.spatial_query
+            .cast_ray_predicate(
+                origin,
+                direction,
+                max_distance,
+                true,
+                &query_filter(mask),
+                &|entity| self.interacts(entity, mask),
+            )
+            .map(|hit| ColliderRayHit {
+                entity: hit.entity,
+                point: origin + direction * hit.distance,
+                normal: hit.normal,
+                t: hit.distance,
+            })
+    }
+
+    /// First collider a circle of `radius` swept from `origin` along `direction` touches within
+    /// `max_distance`
+    ///
+    /// Colliders the circle already overlaps at `origin` are ignored, so a cast can start in
+    /// contact with a surface.
+    pub fn shapecast(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        radius: f32,
+        max_distance: f32,
+        mask: CollisionLayers,
+    ) -> Option<ColliderShapeHit> {
+        let direction = Dir2::new(direction).ok()?;
+        let mut config = ShapeCastConfig::from_max_distance(max_distance);
+        config.ignore_origin_penetration = true;
+
+        let hit = self.spatial_query.cast_shape_predicate(
+            &Collider::circle(radius),
+            origin,
+            0.0,
+            direction,
+            &config,
+            &query_filter(mask),
+            &|entity| self.interacts(entity, mask),
+        )?;
+
+        let position = origin + direction * hit.distance;
+        let (collider, collider_position, rotation, ..) =
+            self.collider_query.get(hit.entity).ok()?;
+        let (point, _) = collider.project_point(*collider_position, *rotation, position, false);
+        Some(ColliderShapeHit {
+            entity: hit.entity,
+            position,
+            point,
+            normal: (position - point).normalize_or_zero(),
+            t: hit.distance,
+        })
+    }
+
+    /// Every collider a circle overlaps
+    pub fn overlap_circle(
+        &self,
+        center: Vec2,
+        radius: f32,
+        mask: CollisionLayers,
+    ) -> Vec<ColliderContact> {
+        self.spatial_query
+            .shape_intersections(&Collider::circle(radius), center, 0.0, &query_filter(mask))
+            .into_iter()
+            .filter(|&entity| self.interacts(entity, mask))
+            .filter_map(|entity| {
+                let (collider, position, rotation, ..) = self.collider_query.get(entity).ok()?;
+                let (point, inside) = collider.project_point(*position, *rotation, center, false);
+                let distance = point.distance(center);
+
+                // Inside the collider, the way out is through its surface
+                let to_center = (center - point).normalize_or_zero();
+                let (normal, depth) = if inside {
+                    (-to_center, radius + distance)
+                } else {
+                    (to_center, radius - distance)
+                };
+                Some(ColliderContact {
+                    entity,
+                    point,
+                    normal,
+                    depth,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the controller collides with a collider: not a sensor or on a dynamic body, and
+    /// its avian layers filter for one of `mask`'s memberships
+    fn interacts(&self, entity: Entity, mask: CollisionLayers) -> bool {
+        self.collider_query
+            .get(entity)
+            .is_ok_and(|(_, _, _, rigid_body, avian_layers)| {
+                !rigid_body.is_some_and(|rigid_body| rigid_body.is_dynamic())
+                    && avian_layers
+                        .is_none_or(|avian_layers| avian_layers.filters.0 & mask.memberships != 0)
+            })
+    }
+}
+
+/// Colliders whose avian memberships `mask` filters for
+fn query_filter(mask: CollisionLayers) -> SpatialQueryFilter {
+    SpatialQueryFilter::from_mask(LayerMask(mask.filters))
+}
+
+/// Collision phase against avian colliders, in place of `s_collision`
+///
+/// Same contact rules as `s_collision` for arc edges: a swept circle cast stops fast players,
+/// touching surfaces set the ground/wall/ceiling state, penetrations go through
+/// `solve_penetrations` and the ground snap casts down to the nearest walkable collider. Avian
+/// colliders have no polygon edges, so `ground_edge`/`wall_edge` stay `None` and
+/// `ContactEvent`s, `LandingContact`s and `GroundPoundLanding`s carry no edge or polygon. The
+/// edge-based features (`CrushedEvent`, `ContactsThisTick`, step-up, corner correction, one-way
+/// platforms and surface materials) are not reported; polygon-bound reactions (breakables,
+/// crumbling, boss hits) only follow level polygons.
+pub fn s_avian_collision(
+    mut player_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut Physics,
+        &mut Player,
+        Option<&CollisionLayers>,
+    )>,
+    colliders: AvianColliders,
+    config: Res<ControllerConfig>,
+    gravity: Res<Gravity>,
+    mut outputs: CollisionOutputs,
+) {
+    let started = Instant::now();
+    outputs.contacts.0.clear();
+    *outputs.diagnostics = CollisionDiagnostics::default();
+
+    if let Ok((entity, mut player_transform, mut player_physics, mut player_data, layers)) =
+        player_query.single_mut()
+    {
+        let layers = layers.copied().unwrap_or_default();
+        let radius = player_physics.radius;
+        let frame = gravity.frame_at(player_transform.translation.xy());
+        let mut penetrations: Vec<Vec2> = Vec::new();
+        let mut contact_normals: Vec<(Vec2, f32)> = Vec::new();
+        let mut ceiling_normals: Vec<(Vec2, f32)> = Vec::new();
+        let mut velocity = frame.to_local(player_physics.velocity);
+        let impact_speed = (-velocity.y).max(0.0);
+        let min_ground_normal_y = config.max_slope_angle.to_radians().cos();
+        let ceiling_normal_y = config.ceiling_normal_y;
+
+        let had_ground_contact = player_data.ground_contact;
+        player_data.ground_contact = false;
+        player_data.ground_edge = None;
+        player_data.sticky_contact = false;
+        player_data.wall_contact_speed = 0.0;
+
+        // Continuous collision: a fast player stops where their circle first touches a collider
+        let mut player_pos = player_transform.translation.xy();
+        let motion = player_pos - player_physics.prev_position;
+        if motion.length() > radius * CCD_MIN_MOTION_RATIO {
+            if let Some(hit) = colliders.shapecast(
+                player_physics.prev_position,
+                motion,
+                radius,
+                motion.length(),
+                layers,
+            ) {
+                player_pos = hit.position;
+                player_transform.translation = player_pos.extend(player_transform.translation.z);
+            }
+        }
+
+        let contacts = colliders.overlap_circle(player_pos, radius + TOUCH_THRESHOLD, layers);
+        outputs.diagnostics.edges_tested = contacts.len();
+
+        for contact in contacts {
+            let local_normal_dir = frame.to_local(contact.normal);
+            let penetration = contact.depth - TOUCH_THRESHOLD;
+
+            if local_normal_dir.y < ceiling_normal_y {
+                ceiling_normals.push((contact.normal, contact.depth));
+            } else {
+                contact_normals.push((contact.normal, contact.depth));
+
+                if local_normal_dir.x.abs() >= NORMAL_DOT_THRESHOLD {
+                    player_data.wall_timer = MAX_WALLED_TIMER;
+                    player_data.wall_direction = local_normal_dir.x.signum();
+                    player_data.wall_edge = None;
+                    player_data.last_wall_normal = Some(contact.normal);
+                    player_data.has_wall_jumped = false;
+                    player_data.wall_contact_speed = player_data
+                        .wall_contact_speed
+                        .max(-velocity.x * local_normal_dir.x);
+                }
+
+                if local_normal_dir.y > GROUND_NORMAL_Y_THRESHOLD
+                    && local_normal_dir.y >= min_ground_normal_y
+                {
+                    // One landing per touchdown, however many colliders the player lands on
+                    if !had_ground_contact && !player_data.ground_contact {
+                        outputs.landing_writer.write(LandingContact {
+                            polygon: None,
+                            impact_speed,
+                        });
+                    }
+
+                    if player_data.state == MovementState::GroundPounding {
+                        outputs.pound_writer.write(GroundPoundLanding {
+                            polygon: None,
+                            position: player_pos,
+                        });
+                        player_data.state = MovementState::Normal;
+                        player_data.pound_recovery_timer = config.ground_pound.recovery;
+                    }
+
+                    player_data.grounded_timer = MAX_GROUNDED_TIMER;
+                    player_data.is_grounded = true;
+                    player_data.ground_contact = true;
+                    player_data.wall_timer = 0.0;
+                    player_data.wall_direction = 0.0;
+                    player_data.wall_edge = None;
+                    player_data.has_wall_jumped = false;
+                }
+            }
+
+            if penetration >= 0.0 {
+                outputs.contact_writer.write(ContactEvent {
+                    entity,
+                    point: contact.point,
+                    normal: contact.normal,
+                    penetration,
+                    edge_id: None,
+                });
+
+                if local_normal_dir.y < ceiling_normal_y {
+                    velocity.y = velocity.y.min(0.0);
+                }
+
+                penetrations.push(local_normal_dir * penetration);
+            }
+        }
+
+        let (mut adjustment, solver_iterations) =
+            solve_penetrations(&penetrations, config.solver_iterations.max(1));
+        outputs.diagnostics.solver_iterations = solver_iterations;
+
+        // Ground snapping: stay glued to the ground when running downhill or over convex corners
+        if had_ground_contact
+            && !player_data.ground_contact
+            && velocity.y <= 0.0
+            && config.ground_snap_distance > 0.0
+        {
+            let ground = colliders
+                .shapecast(
+                    player_pos,
+                    -frame.up,
+                    radius,
+                    config.ground_snap_distance,
+                    layers,
+                )
+                .filter(|hit| {
+                    let normal_y = frame.to_local(hit.normal).y;
+                    normal_y > GROUND_NORMAL_Y_THRESHOLD && normal_y >= min_ground_normal_y
+                });
+            if let Some(hit) = ground {
+                adjustment.y -= hit.t;
+                contact_normals.push((hit.normal, radius));
+
+                player_data.grounded_timer = MAX_GROUNDED_TIMER;
+                player_data.is_grounded = true;
+                player_data.ground_contact = true;
+                player_data.wall_timer = 0.0;
+                player_data.wall_direction = 0.0;
+                player_data.wall_edge = None;
+                player_data.has_wall_jumped = false;
+            }
+        }
+
+        player_data.touching_ceiling = !ceiling_normals.is_empty();
+        player_data.ceiling_normal = player_data
+            .touching_ceiling
+            .then(|| combine_contact_normals(&ceiling_normals));
+
+        // Remove the velocity into the surfaces, as `s_collision` does
+        let new_player_normal = -combine_contact_normals(&contact_normals);
+        player_physics.normal = new_player_normal;
+        player_physics.velocity = frame.to_world(velocity);
+        let normal_speed = player_physics.velocity.dot(new_player_normal);
+        player_physics.velocity -= normal_speed * new_player_normal;
+
+        player_transform.translation += frame.to_world(adjustment).extend(0.0);
+    }
+
+    outputs.diagnostics.resolution_time = started.elapsed();
+}
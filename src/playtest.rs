@@ -0,0 +1,150 @@
+//! Auto-playtest bot (enabled by the `test_utils` feature): searches for held-key inputs that
+//! take the player from a level's spawn to its goal in a `TestWorld`
+//!
+//! The search is best-first over short input segments, rewinding the world with snapshots and
+//! skipping states it already reached. Only the level geometry and volumes are simulated (the
+//! controller phases of a `TestWorld`), so gates, hazards and other level entities are ignored.
+//!
+//! ```ignore
+//! let result = playtest(include_str!("my_level.json"), ControllerConfig::default(), &PlaytestBudget::default());
+//! assert!(result.is_some_and(|result| result.completed_in.is_some()));
+//! ```
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    config::ControllerConfig,
+    level::load_level_data,
+    levels::GOAL_RADIUS,
+    test_utils::{TestSnapshot, TestWorld},
+    GRID_SIZE, PLAYER_RADIUS,
+};
+
+// Ticks each input segment is held for
+const SEGMENT_TICKS: u32 = 8;
+// Input segments tried from every state
+const SEGMENTS: [&[KeyCode]; 6] = [
+    &[],
+    &[KeyCode::ArrowLeft],
+    &[KeyCode::ArrowRight],
+    &[KeyCode::Space],
+    &[KeyCode::ArrowLeft, KeyCode::Space],
+    &[KeyCode::ArrowRight, KeyCode::Space],
+];
+// Size (pixels) of the position cells and (pixels/second) of the velocity cells states are
+// deduplicated by
+const POSITION_CELL: f32 = 8.0;
+const VELOCITY_CELL: f32 = 120.0;
+// Priority cost (pixels) of a tick, trading path length against distance to the goal
+const TICK_COST: f32 = 0.5;
+
+/// Limits of a playtest
+pub struct PlaytestBudget {
+    /// Longest run (ticks) that counts as completing the level
+    pub max_ticks: u32,
+    /// Most states expanded before giving up
+    pub max_states: usize,
+}
+
+impl Default for PlaytestBudget {
+    fn default() -> Self {
+        Self {
+            max_ticks: 60 * 60,
+            max_states: 20_000,
+        }
+    }
+}
+
+/// Outcome of a playtest
+#[derive(Debug)]
+pub struct PlaytestResult {
+    /// Ticks the bot took to reach the goal, if it did
+    pub completed_in: Option<u32>,
+    /// States expanded by the search
+    pub states: usize,
+    /// Closest the player got to the goal (pixels)
+    pub closest: f32,
+}
+
+struct SearchNode {
+    snapshot: TestSnapshot,
+    ticks: u32,
+}
+
+/// Searches for a way through a level in the level file format (`None` if it has no goal)
+pub fn playtest(
+    json: &str,
+    config: ControllerConfig,
+    budget: &PlaytestBudget,
+) -> Option<PlaytestResult> {
+    let level_data = load_level_data(json.as_bytes());
+    let goal = level_data.tile_to_world(level_data.goal?, GRID_SIZE);
+    let reach = PLAYER_RADIUS + GOAL_RADIUS;
+
+    let mut world = TestWorld::new(level_data, config);
+    let mut nodes = vec![SearchNode {
+        snapshot: world.snapshot(),
+        ticks: 0,
+    }];
+    let mut queue = BinaryHeap::from([(Reverse(0), 0)]);
+    let mut visited = HashSet::new();
+    let mut result = PlaytestResult {
+        completed_in: None,
+        states: 0,
+        closest: world.player_position().distance(goal),
+    };
+
+    while let Some((_, index)) = queue.pop() {
+        if result.states >= budget.max_states {
+            break;
+        }
+        result.states += 1;
+
+        for held in SEGMENTS {
+            world.restore(&nodes[index].snapshot);
+            let mut ticks = nodes[index].ticks;
+
+            // Checked every tick: the player can pass through the goal mid-segment
+            for _ in 0..SEGMENT_TICKS {
+                world.step(held);
+                ticks += 1;
+                let distance = world.player_position().distance(goal);
+                result.closest = result.closest.min(distance);
+                if distance <= reach {
+                    result.completed_in = Some(ticks);
+                    return Some(result);
+                }
+            }
+
+            let key = state_key(&mut world);
+            if ticks > budget.max_ticks || !visited.insert(key) {
+                continue;
+            }
+
+            let distance = world.player_position().distance(goal);
+            let priority = ((distance + ticks as f32 * TICK_COST) * 100.0) as i64;
+            queue.push((Reverse(priority), nodes.len()));
+            nodes.push(SearchNode {
+                snapshot: world.snapshot(),
+                ticks,
+            });
+        }
+    }
+
+    Some(result)
+}
+
+/// Cell of the state space a world state falls in
+fn state_key(world: &mut TestWorld) -> (IVec2, IVec2, bool, bool) {
+    (
+        (world.player_position() / POSITION_CELL).floor().as_ivec2(),
+        (world.player_velocity() / VELOCITY_CELL).round().as_ivec2(),
+        world.is_grounded(),
+        world.is_on_wall(),
+    )
+}
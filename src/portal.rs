@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+
+use crate::{
+    geometry::{closest_point_on_segment, line_intersect},
+    grapple::{s_rope_constraint, s_swing_constraint, Grapple, Swing},
+    s_render,
+    schedule::ControllerPhase,
+    Physics, Player,
+};
+
+// Portal rendering constants
+const PORTAL_COLORS: [Color; 2] = [Color::srgb(0.2, 0.6, 1.0), Color::srgb(1.0, 0.6, 0.1)];
+// Length (pixels) of the line showing which way a portal faces
+const PORTAL_FACING_LENGTH: f32 = 8.0;
+const PORTAL_GHOST_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.4);
+
+/// Portal pairs: crossing one portal's face continues the movement out of the other, with the
+/// velocity rotated by the difference in orientation
+pub struct PortalPlugin;
+
+impl Plugin for PortalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            s_portals
+                .after(s_rope_constraint)
+                .after(s_swing_constraint)
+                .before(ControllerPhase::Collision),
+        )
+        .add_systems(Update, s_render_portals.after(s_render));
+    }
+}
+
+/// One side of a portal pair: a segment entered from the side `normal` points to
+#[derive(Clone, Copy)]
+pub struct PortalEnd {
+    pub start: Vec2,
+    pub end: Vec2,
+    /// Facing direction (normalized, world space)
+    pub normal: Vec2,
+}
+
+impl PortalEnd {
+    pub fn center(&self) -> Vec2 {
+        (self.start + self.end) / 2.0
+    }
+}
+
+/// Two linked portals
+#[derive(Component)]
+pub struct PortalPair {
+    pub ends: [PortalEnd; 2],
+}
+
+impl PortalPair {
+    /// Rotation (as a unit complex number) turning entering `from` into leaving the other end
+    fn rotation(&self, from: usize) -> Vec2 {
+        let entry = -self.ends[from].normal;
+        let exit = self.ends[1 - from].normal;
+        Vec2::new(entry.dot(exit), entry.perp_dot(exit))
+    }
+
+    /// Point relative to the `from` end mapped to the same place relative to the other end
+    pub fn map_point(&self, from: usize, point: Vec2) -> Vec2 {
+        self.ends[1 - from].center() + self.rotation(from).rotate(point - self.ends[from].center())
+    }
+
+    /// Direction through the `from` end mapped out of the other end
+    pub fn map_vector(&self, from: usize, vector: Vec2) -> Vec2 {
+        self.rotation(from).rotate(vector)
+    }
+}
+
+/// Teleports the player when their movement this frame crossed a portal's face
+///
+/// The crossing is tested on the swept path from the previous position, so fast entries can't
+/// skip over a portal. Ropes are let go of, since their anchor stays behind.
+pub fn s_portals(
+    mut commands: Commands,
+    portal_query: Query<&PortalPair>,
+    mut player_query: Query<(Entity, &mut Transform, &mut Physics), With<Player>>,
+) {
+    let Ok((player_entity, mut player_transform, mut player_physics)) = player_query.single_mut()
+    else {
+        return;
+    };
+
+    let previous_pos = player_physics.prev_position;
+    let player_pos = player_transform.translation.xy();
+
+    for portal in &portal_query {
+        for (from, portal_end) in portal.ends.iter().enumerate() {
+            let was_in_front = (previous_pos - portal_end.center()).dot(portal_end.normal) > 0.0;
+            let is_behind = (player_pos - portal_end.center()).dot(portal_end.normal) <= 0.0;
+            if !was_in_front
+                || !is_behind
+                || line_intersect(portal_end.start, portal_end.end, previous_pos, player_pos)
+                    .is_none()
+            {
+                continue;
+            }
+
+            let exit_pos = portal.map_point(from, player_pos);
+            player_transform.translation = exit_pos.extend(player_transform.translation.z);
+            player_physics.map_vectors(|vector| portal.map_vector(from, vector));
+            // Nothing to collide with between the portals
+            player_physics.prev_position = exit_pos;
+
+            commands.entity(player_entity).remove::<(Grapple, Swing)>();
+            return;
+        }
+    }
+}
+
+/// Draws portals with their facing, and a ghost of the player out of the paired portal while
+/// they overlap one
+pub fn s_render_portals(
+    mut gizmos: Gizmos,
+    portal_query: Query<&PortalPair>,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+) {
+    let player = player_query.single().ok();
+
+    for portal in &portal_query {
+        for (from, portal_end) in portal.ends.iter().enumerate() {
+            gizmos.line_2d(portal_end.start, portal_end.end, PORTAL_COLORS[from]);
+            gizmos.line_2d(
+                portal_end.center(),
+                portal_end.center() + portal_end.normal * PORTAL_FACING_LENGTH,
+                PORTAL_COLORS[from],
+            );
+
+            if let Some((player_transform, player_physics)) = player {
+                let player_pos = player_transform.translation.xy();
+                let closest =
+                    closest_point_on_segment(portal_end.start, portal_end.end, player_pos);
+                if closest.distance(player_pos) < player_physics.radius {
+                    gizmos.circle_2d(
+                        portal.map_point(from, player_pos),
+                        player_physics.radius,
+                        PORTAL_GHOST_COLOR,
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    geometry::find_projection,
+    levels::GameState,
+    s_render, s_spawn_level,
+    save::{store_save, SaveBackend, SaveData},
+    schedule::ControllerSystems,
+    Level, Physics, Player,
+};
+
+// Progression constants
+const KEY_RADIUS: f32 = 6.0;
+const KEY_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+const COIN_RADIUS: f32 = 4.0;
+const COIN_COLOR: Color = Color::srgb(1.0, 0.7, 0.0);
+pub const GATE_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+// Extra distance (pixels) beyond the player's radius that counts as touching a gate
+const GATE_TOUCH_DISTANCE: f32 = 1.0;
+
+/// Key pickups, the locked gates they open and currency coins, persisted in the save file
+pub struct ProgressionPlugin;
+
+impl Plugin for ProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Progress>()
+            .init_resource::<SaveBackend>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                s_restore_progress.after(s_spawn_level),
+            )
+            .add_systems(Update, s_collect_keys.after(ControllerSystems))
+            .add_systems(Update, s_open_gates.after(s_collect_keys))
+            .add_systems(Update, s_collect_coins.after(ControllerSystems))
+            .add_systems(Update, s_render_keys.after(s_render))
+            .add_systems(Update, s_render_coins.after(s_render));
+    }
+}
+
+/// Collectible key that opens gates with the same id
+#[derive(Component)]
+pub struct KeyPickup {
+    pub id: String,
+}
+
+/// Locked gate backed by a polygon in `Level`
+#[derive(Component)]
+pub struct Gate {
+    pub id: String,
+    /// Index of the gate polygon in `Level::polygons`
+    pub polygon: usize,
+}
+
+/// Currency pickup
+#[derive(Component)]
+pub struct Coin {
+    /// `level:index` id identifying the coin in the save file
+    pub id: String,
+}
+
+/// Progression state of the current save slot (loaded when a slot is chosen)
+#[derive(Resource, Default)]
+pub struct Progress(pub SaveData);
+
+/// The current slot's `Progress` and the `SaveBackend` it is stored through
+#[derive(SystemParam)]
+pub struct ProgressSave<'w> {
+    pub progress: ResMut<'w, Progress>,
+    pub backend: Res<'w, SaveBackend>,
+}
+
+impl ProgressSave<'_> {
+    /// Write the progress to its save slot
+    pub fn store(&self) {
+        store_save(&self.backend, &self.progress.0);
+    }
+}
+
+/// Applies the saved progression to the freshly spawned level
+pub fn s_restore_progress(
+    mut commands: Commands,
+    progress: Res<Progress>,
+    mut level: ResMut<Level>,
+    key_query: Query<(Entity, &KeyPickup)>,
+    gate_query: Query<(Entity, &Gate)>,
+    coin_query: Query<(Entity, &Coin)>,
+) {
+    for (entity, coin) in &coin_query {
+        if progress.0.collected_coins.contains(&coin.id) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (entity, key) in &key_query {
+        if progress.0.keys.contains(&key.id) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (entity, gate) in &gate_query {
+        if progress.0.opened_gates.contains(&gate.id) {
+            level.polygons[gate.polygon].enabled = false;
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Collects keys the player overlaps
+pub fn s_collect_keys(
+    mut commands: Commands,
+    mut save: ProgressSave,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+    key_query: Query<(Entity, &Transform, &KeyPickup)>,
+) {
+    if let Ok((player_transform, player_physics)) = player_query.single() {
+        let player_pos = player_transform.translation.xy();
+
+        for (entity, key_transform, key) in &key_query {
+            let distance = key_transform.translation.xy().distance(player_pos);
+
+            if distance <= player_physics.radius + KEY_RADIUS {
+                if !save.progress.0.keys.contains(&key.id) {
+                    save.progress.0.keys.push(key.id.clone());
+                }
+                commands.entity(entity).despawn();
+                save.store();
+            }
+        }
+    }
+}
+
+/// Opens locked gates the player touches while holding the matching key
+pub fn s_open_gates(
+    mut commands: Commands,
+    mut save: ProgressSave,
+    mut level: ResMut<Level>,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+    gate_query: Query<(Entity, &Gate)>,
+) {
+    if let Ok((player_transform, player_physics)) = player_query.single() {
+        let player_pos = player_transform.translation.xy();
+        let touch_distance_sq = (player_physics.radius + GATE_TOUCH_DISTANCE).powi(2);
+
+        for (entity, gate) in &gate_query {
+            if !save.progress.0.keys.contains(&gate.id) {
+                continue;
+            }
+
+            let polygon = &level.polygons[gate.polygon];
+            let touching = polygon.points.windows(2).any(|edge| {
+                let (distance_sq, _) = find_projection(edge[0], edge[1], player_pos);
+                distance_sq <= touch_distance_sq
+            });
+
+            if touching {
+                level.polygons[gate.polygon].enabled = false;
+                save.progress.0.opened_gates.push(gate.id.clone());
+                commands.entity(entity).despawn();
+                save.store();
+            }
+        }
+    }
+}
+
+/// Collects coins the player overlaps, adding them to the currency
+pub fn s_collect_coins(
+    mut commands: Commands,
+    mut save: ProgressSave,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+    coin_query: Query<(Entity, &Transform, &Coin)>,
+) {
+    if let Ok((player_transform, player_physics)) = player_query.single() {
+        let player_pos = player_transform.translation.xy();
+
+        for (entity, coin_transform, coin) in &coin_query {
+            let distance = coin_transform.translation.xy().distance(player_pos);
+
+            if distance <= player_physics.radius + COIN_RADIUS {
+                save.progress.0.currency += 1;
+                save.progress.0.collected_coins.push(coin.id.clone());
+                commands.entity(entity).despawn();
+                save.store();
+            }
+        }
+    }
+}
+
+/// Draws uncollected keys
+pub fn s_render_keys(mut gizmos: Gizmos, key_query: Query<&Transform, With<KeyPickup>>) {
+    for key_transform in &key_query {
+        gizmos.circle_2d(key_transform.translation.xy(), KEY_RADIUS, KEY_COLOR);
+    }
+}
+
+/// Draws uncollected coins
+pub fn s_render_coins(mut gizmos: Gizmos, coin_query: Query<&Transform, With<Coin>>) {
+    for coin_transform in &coin_query {
+        gizmos.circle_2d(coin_transform.translation.xy(), COIN_RADIUS, COIN_COLOR);
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{box_sweep_fraction, CollisionLayers, ContactsThisTick},
+    death::{s_respawn, PlayerRespawned},
+    gravity::Gravity,
+    rect_points,
+    schedule::ControllerPhase,
+    time_bubble::{time_scale_at, TimeBubble},
+    Aabb, Level, Player, GRAVITY_STRENGTH, MAX_FALL_SPEED, NORMAL_DOT_THRESHOLD,
+};
+
+pub const PUSHABLE_COLOR: Color = Color::srgb(0.7, 0.5, 0.3);
+// Speed (pixels/second) a block slides at while the player pushes it
+const PUSH_SPEED: f32 = 120.0;
+// Gap (pixels) kept between a block and the geometry it rests against, so resting contacts
+// don't count as overlaps
+const BLOCK_SKIN: f32 = 0.5;
+
+/// Pushable blocks: boxes the player pushes along the ground by walking into them, which fall
+/// under gravity and collide with the level polygons (other blocks included)
+///
+/// A block's outline is a `Level` polygon, so the player collides with it like any moving
+/// geometry.
+pub struct PushablePlugin;
+
+impl Plugin for PushablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, s_pushable_blocks.before(ControllerPhase::Input))
+            .add_systems(Update, s_reset_pushable_blocks.after(s_respawn));
+    }
+}
+
+/// Dynamic box whose outline is a polygon in `Level`
+#[derive(Component)]
+pub struct Pushable {
+    /// Index of the block's polygon in `Level::polygons`
+    pub polygon: usize,
+    /// Center when the level started (world space)
+    pub start: Vec2,
+    pub center: Vec2,
+    pub half_size: Vec2,
+    /// World space velocity (pixels/second)
+    pub velocity: Vec2,
+}
+
+/// Pushes blocks the grounded player walked into last tick, applies gravity and moves them
+/// as far as the level geometry lets them
+pub fn s_pushable_blocks(
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    contacts: Res<ContactsThisTick>,
+    mut level: ResMut<Level>,
+    player_query: Query<&Player>,
+    bubble_query: Query<&TimeBubble>,
+    mut block_query: Query<&mut Pushable>,
+) {
+    for mut block in &mut block_query {
+        let dt = time.delta_secs() * time_scale_at(&bubble_query, block.center);
+        let frame = gravity.frame_at(block.center);
+        let mut velocity = frame.to_local(block.velocity);
+
+        // Walking into a side of the block pushes it away from the player
+        let push = contacts.0.iter().find_map(|contact| {
+            let grounded = player_query
+                .get(contact.entity)
+                .is_ok_and(|player_data| player_data.is_grounded);
+            let local_normal = frame.to_local(contact.normal);
+            let into_block = contact.relative_velocity.dot(contact.normal) < 0.0;
+            (contact
+                .edge
+                .is_some_and(|edge| edge.polygon == block.polygon)
+                && grounded
+                && into_block
+                && local_normal.x.abs() >= NORMAL_DOT_THRESHOLD)
+                .then_some(-local_normal.x.signum())
+        });
+        velocity.x = push.map_or(0.0, |direction| direction * PUSH_SPEED);
+        velocity.y = (velocity.y - GRAVITY_STRENGTH * frame.strength * dt).max(-MAX_FALL_SPEED);
+
+        // Move along the ground, then fall, stopping each axis at the first obstacle
+        let mut center = block.center;
+        for (axis, falling) in [(frame.right, false), (frame.up, true)] {
+            let component = if falling {
+                &mut velocity.y
+            } else {
+                &mut velocity.x
+            };
+            let step = axis * *component * dt;
+            let aabb = Aabb::from_corners(center - block.half_size, center + block.half_size)
+                .expand(-BLOCK_SKIN);
+            let fraction = box_sweep_fraction(
+                &level,
+                Some(block.polygon),
+                &aabb,
+                step,
+                falling,
+                CollisionLayers::default(),
+            );
+            if fraction < 1.0 {
+                *component = 0.0;
+            }
+            center += step * fraction;
+        }
+        block.velocity = frame.to_world(velocity);
+
+        let polygon = &mut level.polygons[block.polygon];
+        if center != block.center {
+            block.center = center;
+            polygon.move_to(rect_points(
+                center - block.half_size,
+                center + block.half_size,
+            ));
+        } else {
+            // Resting blocks don't count as moving geometry
+            polygon.prev_points.clear();
+        }
+    }
+}
+
+/// Puts every block back where it started when the player respawns
+pub fn s_reset_pushable_blocks(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    mut level: ResMut<Level>,
+    mut block_query: Query<&mut Pushable>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    for mut block in &mut block_query {
+        block.center = block.start;
+        block.velocity = Vec2::ZERO;
+        level.polygons[block.polygon].reset_to(rect_points(
+            block.start - block.half_size,
+            block.start + block.half_size,
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+
+use crate::{
+    death::{s_respawn, PlayerRespawned},
+    level::Aabb,
+    schedule::ControllerPhase,
+    Level, Player,
+};
+
+pub const RISING_FLUID_COLOR: Color = Color::srgba(1.0, 0.3, 0.0, 0.6);
+
+/// Rising lava/water: a hazard volume whose surface climbs over time, starting after a delay
+/// or once the player enters a trigger area
+pub struct RisingFluidPlugin;
+
+impl Plugin for RisingFluidPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, s_rise_fluids.before(ControllerPhase::Input))
+            .add_systems(Update, s_reset_fluids.after(s_respawn))
+            .add_systems(Update, s_render_fluids.after(s_rise_fluids));
+    }
+}
+
+/// Rising fluid backed by a hazard volume in `Level`
+#[derive(Component)]
+pub struct RisingFluid {
+    /// Index of the volume in `Level::volumes`
+    pub volume: usize,
+    /// Surface height (world y) when the level starts
+    pub start_height: f32,
+    /// Highest surface height (world y)
+    pub max_height: f32,
+    /// Current surface height (world y)
+    pub height: f32,
+    /// Rising speed (pixels/second)
+    pub speed: f32,
+    /// Time (seconds) before the fluid starts rising on its own (`None` waits for the trigger)
+    pub delay: Option<f32>,
+    /// Entering this area starts the rise
+    pub trigger: Option<Rect>,
+    /// Time (seconds) since the level started or the player respawned
+    pub elapsed: f32,
+    /// Whether the fluid is rising
+    pub rising: bool,
+}
+
+/// Starts fluids on their delay or trigger, raises them and updates their hazard volumes
+pub fn s_rise_fluids(
+    time: Res<Time>,
+    mut level: ResMut<Level>,
+    player_query: Query<&Transform, With<Player>>,
+    mut fluid_query: Query<&mut RisingFluid>,
+) {
+    let dt = time.delta_secs();
+    let player_pos = player_query
+        .single()
+        .ok()
+        .map(|player_transform| player_transform.translation.xy());
+
+    for mut fluid in &mut fluid_query {
+        fluid.elapsed += dt;
+
+        let delay_over = fluid.delay.is_some_and(|delay| fluid.elapsed >= delay);
+        let triggered = fluid
+            .trigger
+            .zip(player_pos)
+            .is_some_and(|(trigger, player_pos)| trigger.contains(player_pos));
+        if delay_over || triggered {
+            fluid.rising = true;
+        }
+
+        if fluid.rising {
+            fluid.height = (fluid.height + fluid.speed * dt).min(fluid.max_height);
+        }
+
+        let volume = &mut level.volumes[fluid.volume];
+        volume.aabb.max.y = fluid.height;
+    }
+}
+
+/// Lowers fluids back to their start when the player respawns
+pub fn s_reset_fluids(
+    mut respawned_reader: MessageReader<PlayerRespawned>,
+    mut fluid_query: Query<&mut RisingFluid>,
+) {
+    if respawned_reader.read().count() == 0 {
+        return;
+    }
+
+    for mut fluid in &mut fluid_query {
+        fluid.height = fluid.start_height;
+        fluid.elapsed = 0.0;
+        fluid.rising = false;
+    }
+}
+
+/// Draws fluids as filled regions below their surface
+pub fn s_render_fluids(
+    level: Res<Level>,
+    mut fluid_query: Query<(&RisingFluid, &mut Transform, &mut Sprite)>,
+) {
+    for (fluid, mut fluid_transform, mut sprite) in &mut fluid_query {
+        let Aabb { min, max } = level.volumes[fluid.volume].aabb;
+        fluid_transform.translation = ((min + max) / 2.0).extend(-1.0);
+        sprite.custom_size = Some(max - min);
+    }
+}
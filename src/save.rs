@@ -0,0 +1,303 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use bevy::{
+    log::{error, warn},
+    prelude::Resource,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{assist::AssistMode, gamepad::StickConfig, snapshot::RunSnapshot};
+
+/// Number of save slots
+pub const SAVE_SLOTS: usize = 3;
+
+/// Pre-slot save file, migrated into slot 0 if that slot is empty
+const LEGACY_SAVE_KEY: &str = "save.json";
+
+/// Persistent progression state
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SaveData {
+    /// Slot this save is stored in (not serialized; `None` until a slot is chosen)
+    #[serde(skip)]
+    pub slot: Option<usize>,
+    /// Ids of collected keys
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// Ids of opened gates
+    #[serde(default)]
+    pub opened_gates: Vec<String>,
+    /// Unspent currency
+    #[serde(default)]
+    pub currency: u32,
+    /// Ids (`level:index`) of collected coins
+    #[serde(default)]
+    pub collected_coins: Vec<String>,
+    /// Ids of purchased shop items
+    #[serde(default)]
+    pub purchases: Vec<String>,
+    /// Best medal count per level id
+    #[serde(default)]
+    pub medals: HashMap<String, u32>,
+    /// Best completion per level id
+    #[serde(default)]
+    pub records: HashMap<String, RunRecord>,
+    /// Per-profile settings that override the defaults
+    #[serde(default)]
+    pub settings: SettingsOverrides,
+    /// Play statistics
+    #[serde(default)]
+    pub stats: SaveStats,
+    /// Autosaved level attempt in progress, if the game was closed mid-level
+    #[serde(default)]
+    pub run_snapshot: Option<RunSnapshot>,
+    /// When the save was last written (seconds since the Unix epoch, 0 if unknown)
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+impl SaveData {
+    /// Whether this save should win a sync conflict against `other`
+    ///
+    /// The most recently written save wins; equal timestamps fall back to the longer play time.
+    pub fn newer_than(&self, other: &SaveData) -> bool {
+        match self.saved_at.cmp(&other.saved_at) {
+            std::cmp::Ordering::Equal => self.stats.play_time > other.stats.play_time,
+            ordering => ordering.is_gt(),
+        }
+    }
+}
+
+/// A level completion
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct RunRecord {
+    /// Completion time (seconds)
+    pub time: f32,
+    /// Whether assist mode was enabled at any point during the attempt
+    pub assisted: bool,
+}
+
+impl RunRecord {
+    /// Whether this run should replace `other` as the best record
+    ///
+    /// Unassisted runs always beat assisted ones, so assisted times never show up as records.
+    pub fn beats(&self, other: &RunRecord) -> bool {
+        match (self.assisted, other.assisted) {
+            (false, true) => true,
+            (true, false) => false,
+            _ => self.time < other.time,
+        }
+    }
+}
+
+/// Settings saved with a profile (`None` keeps the default)
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SettingsOverrides {
+    #[serde(default)]
+    pub stick: Option<StickConfig>,
+    #[serde(default)]
+    pub assist: Option<AssistMode>,
+}
+
+/// Per-profile play statistics
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SaveStats {
+    /// Total time (seconds) spent playing levels
+    #[serde(default)]
+    pub play_time: f32,
+    /// Number of level completions
+    #[serde(default)]
+    pub completions: u32,
+}
+
+/// Storage for save files, keyed by file name
+///
+/// Implement this to plug in Steam Cloud or other custom backends, and install it by inserting a
+/// `SaveBackend` resource before the app runs.
+pub trait SaveStorage: Send + Sync {
+    /// Contents stored under `key`, or `None` if there are none
+    fn read(&self, key: &str) -> Option<String>;
+    /// Replace the contents stored under `key`
+    fn write(&self, key: &str, contents: &str) -> Result<(), String>;
+    /// Remove `key` (no-op if it doesn't exist)
+    fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Save files in the working directory
+///
+/// Writes go to a temporary file that is renamed over the target, so an interrupted write
+/// never leaves a half-written save behind.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct LocalFileStorage;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SaveStorage for LocalFileStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(key).ok()
+    }
+
+    fn write(&self, key: &str, contents: &str) -> Result<(), String> {
+        let temp_path = format!("{key}.tmp");
+        std::fs::write(&temp_path, contents)
+            .and_then(|_| std::fs::rename(&temp_path, key))
+            .map_err(|err| err.to_string())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        match std::fs::remove_file(key) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Saves kept in memory for the session (used on WASM, where there is no filesystem)
+#[derive(Default)]
+pub struct MemoryStorage(Mutex<HashMap<String, String>>);
+
+impl SaveStorage for MemoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.0.lock().ok()?.get(key).cloned()
+    }
+
+    fn write(&self, key: &str, contents: &str) -> Result<(), String> {
+        self.0
+            .lock()
+            .map_err(|err| err.to_string())?
+            .insert(key.to_string(), contents.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        self.0.lock().map_err(|err| err.to_string())?.remove(key);
+        Ok(())
+    }
+}
+
+/// Where saves are stored: a local backend plus an optional synced (cloud) backend
+///
+/// Saves are written to both; on load, the newer of the two copies wins.
+#[derive(Resource)]
+pub struct SaveBackend {
+    pub local: Box<dyn SaveStorage>,
+    pub cloud: Option<Box<dyn SaveStorage>>,
+}
+
+impl Default for SaveBackend {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let local: Box<dyn SaveStorage> = Box::new(LocalFileStorage);
+        #[cfg(target_arch = "wasm32")]
+        let local: Box<dyn SaveStorage> = Box::new(MemoryStorage::default());
+
+        Self { local, cloud: None }
+    }
+}
+
+impl SaveBackend {
+    fn storages(&self) -> impl Iterator<Item = &dyn SaveStorage> {
+        std::iter::once(self.local.as_ref()).chain(self.cloud.as_deref())
+    }
+}
+
+/// Storage key of a slot
+fn slot_key(slot: usize) -> String {
+    format!("save_{slot}.json")
+}
+
+/// Seconds since the Unix epoch (0 where the clock isn't available)
+fn now_secs() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        0
+    }
+}
+
+/// Parse a stored save, moving unreadable contents aside to `<key>.corrupt` so they aren't
+/// overwritten
+fn read_save(storage: &dyn SaveStorage, key: &str) -> Option<SaveData> {
+    let contents = storage.read(key)?;
+
+    match serde_json::from_str(&contents) {
+        Ok(save_data) => Some(save_data),
+        Err(err) => {
+            warn!("Save {key} is corrupt ({err}), moving it aside");
+            if let Err(err) = storage
+                .write(&format!("{key}.corrupt"), &contents)
+                .and_then(|_| storage.delete(key))
+            {
+                error!("Failed to move corrupt save: {err}");
+            }
+            None
+        }
+    }
+}
+
+/// Load a slot, or `None` if it is empty
+///
+/// If both the local and cloud backends hold the slot, the newer copy wins.
+pub fn load_slot(backend: &SaveBackend, slot: usize) -> Option<SaveData> {
+    let key = slot_key(slot);
+
+    let mut save_data = backend
+        .storages()
+        .filter_map(|storage| read_save(storage, &key))
+        .reduce(|best, candidate| {
+            if candidate.newer_than(&best) {
+                candidate
+            } else {
+                best
+            }
+        })
+        .or_else(|| {
+            (slot == 0)
+                .then(|| read_save(backend.local.as_ref(), LEGACY_SAVE_KEY))
+                .flatten()
+        })?;
+
+    save_data.slot = Some(slot);
+    Some(save_data)
+}
+
+/// Write a save to its slot in every backend (does nothing until a slot is chosen)
+pub fn store_save(backend: &SaveBackend, save_data: &SaveData) {
+    let Some(slot) = save_data.slot else {
+        return;
+    };
+
+    let mut save_data = save_data.clone();
+    save_data.saved_at = now_secs();
+
+    match serde_json::to_string_pretty(&save_data) {
+        Ok(contents) => {
+            for storage in backend.storages() {
+                if let Err(err) = storage.write(&slot_key(slot), &contents) {
+                    error!("Failed to write save file: {err}");
+                }
+            }
+        }
+        Err(err) => error!("Failed to serialize save data: {err}"),
+    }
+}
+
+/// Delete a slot from every backend
+pub fn delete_slot(backend: &SaveBackend, slot: usize) {
+    for storage in backend.storages() {
+        if let Err(err) = storage.delete(&slot_key(slot)) {
+            error!("Failed to delete save file: {err}");
+        }
+    }
+
+    // Don't let the legacy save resurrect a deleted slot 0
+    if slot == 0 {
+        if let Err(err) = backend.local.delete(LEGACY_SAVE_KEY) {
+            error!("Failed to delete legacy save file: {err}");
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+
+use bevy::{
+    ecs::schedule::{ScheduleConfigs, SystemSet},
+    ecs::system::ScheduleSystem,
+    prelude::*,
+};
+
+use crate::{
+    collisions::{s_collision, s_refresh_broad_phase},
+    s_input, s_movement, s_timers,
+};
+
+/// A step of the controller update, run as a system set in `Update`
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControllerPhase {
+    /// Reads devices into `InputDir` and the player's input state
+    Input,
+    /// Applies acceleration, gravity and jumps, then integrates the position
+    Movement,
+    /// Resolves penetration and refreshes the ground/wall contact state
+    Collision,
+    /// Decays the jump buffer, coyote and wall timers
+    Timers,
+}
+
+/// System set containing every controller phase, for systems that run before or after the
+/// whole controller update
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ControllerSystems;
+
+/// Default phase order
+pub const DEFAULT_PHASE_ORDER: [ControllerPhase; 4] = [
+    ControllerPhase::Input,
+    ControllerPhase::Movement,
+    ControllerPhase::Collision,
+    ControllerPhase::Timers,
+];
+
+/// Ordering constraints every schedule must keep, as (earlier, later) pairs
+///
+/// Movement reads the input written in the same frame.
+pub const REQUIRED_PHASE_ORDER: &[(ControllerPhase, ControllerPhase)] =
+    &[(ControllerPhase::Input, ControllerPhase::Movement)];
+
+/// Why a controller schedule can't be built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// A phase is missing from the phase order
+    MissingPhase(ControllerPhase),
+    /// A phase appears more than once in the phase order
+    DuplicatePhase(ControllerPhase),
+    /// The phase order breaks an ordering constraint
+    OrderViolation {
+        earlier: ControllerPhase,
+        later: ControllerPhase,
+    },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPhase(phase) => write!(f, "phase {phase:?} is missing from the order"),
+            Self::DuplicatePhase(phase) => {
+                write!(f, "phase {phase:?} appears more than once in the order")
+            }
+            Self::OrderViolation { earlier, later } => {
+                write!(f, "phase {earlier:?} must run before phase {later:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Builder for the controller update: the order of the phases and the system run in each
+///
+/// Apps can reorder phases or substitute their own system for one (e.g. a custom collision
+/// resolution) while keeping the others:
+///
+/// ```ignore
+/// ControllerSchedule::default()
+///     .replace(ControllerPhase::Collision, my_collision)
+///     .build(&mut app)?;
+/// ```
+///
+/// Other plugins order their systems against the `ControllerPhase` sets rather than the
+/// default systems, so replacing a phase keeps them in place.
+pub struct ControllerSchedule {
+    order: Vec<ControllerPhase>,
+    systems: Vec<(ControllerPhase, ScheduleConfigs<ScheduleSystem>)>,
+    constraints: Vec<(ControllerPhase, ControllerPhase)>,
+}
+
+impl Default for ControllerSchedule {
+    fn default() -> Self {
+        Self {
+            order: DEFAULT_PHASE_ORDER.to_vec(),
+            systems: vec![
+                (ControllerPhase::Input, s_input.into_configs()),
+                (ControllerPhase::Movement, s_movement.into_configs()),
+                (
+                    ControllerPhase::Collision,
+                    (s_refresh_broad_phase, s_collision).chain().into_configs(),
+                ),
+                (ControllerPhase::Timers, s_timers.into_configs()),
+            ],
+            constraints: REQUIRED_PHASE_ORDER.to_vec(),
+        }
+    }
+}
+
+impl ControllerSchedule {
+    /// Runs the phases in the given order
+    pub fn order(mut self, order: impl IntoIterator<Item = ControllerPhase>) -> Self {
+        self.order = order.into_iter().collect();
+        self
+    }
+
+    /// Runs `systems` in place of the current systems of a phase
+    pub fn replace<M>(
+        mut self,
+        phase: ControllerPhase,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> Self {
+        self.systems
+            .retain(|(system_phase, _)| *system_phase != phase);
+        self.systems.push((phase, systems.into_configs()));
+        self
+    }
+
+    /// Adds an ordering constraint: `earlier` must run before `later`
+    pub fn require(mut self, earlier: ControllerPhase, later: ControllerPhase) -> Self {
+        self.constraints.push((earlier, later));
+        self
+    }
+
+    /// Checks that every phase runs exactly once and all constraints hold
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        for phase in DEFAULT_PHASE_ORDER {
+            match self
+                .order
+                .iter()
+                .filter(|&&ordered| ordered == phase)
+                .count()
+            {
+                0 => return Err(ScheduleError::MissingPhase(phase)),
+                1 => {}
+                _ => return Err(ScheduleError::DuplicatePhase(phase)),
+            }
+        }
+
+        let position = |phase| self.order.iter().position(|&ordered| ordered == phase);
+        for &(earlier, later) in &self.constraints {
+            if position(earlier) > position(later) {
+                return Err(ScheduleError::OrderViolation { earlier, later });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates the schedule and adds the phases and their systems to `Update`
+    pub fn build(self, app: &mut App) -> Result<(), ScheduleError> {
+        self.validate()?;
+
+        for &phase in &self.order {
+            app.configure_sets(Update, phase.in_set(ControllerSystems));
+        }
+        for pair in self.order.windows(2) {
+            app.configure_sets(Update, pair[1].after(pair[0]));
+        }
+        for (phase, systems) in self.systems {
+            app.add_systems(Update, systems.in_set(phase));
+        }
+
+        Ok(())
+    }
+}
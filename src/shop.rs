@@ -0,0 +1,331 @@
+use bevy::{input::ButtonInput, math::Isometry2d, prelude::*};
+
+use crate::{
+    config::{ControllerConfig, JetpackConfig},
+    dialogue::{dialogue_closed, s_open_dialogue},
+    progression::ProgressSave,
+    s_render,
+    save::SaveData,
+    schedule::ControllerPhase,
+    Physics, Player,
+};
+
+// Shop constants
+const SHOP_SIZE: Vec2 = Vec2::new(24.0, 24.0);
+const SHOP_COLOR: Color = Color::srgb(0.3, 0.8, 0.5);
+// Extra distance (pixels) beyond the player's radius from which a shop can be used
+const SHOP_INTERACT_DISTANCE: f32 = 12.0;
+const SHOP_MENU_MARGIN: f32 = 24.0;
+const SHOP_MENU_PADDING: f32 = 12.0;
+const PURCHASE_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Shops selling controller upgrades for currency collected in the level
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveShop>()
+            // Both menus consume E/Enter before either can open, and a sign wins a tie
+            .add_systems(
+                Update,
+                s_shop_menu.before(s_open_shop).before(s_open_dialogue),
+            )
+            .add_systems(
+                Update,
+                s_open_shop
+                    .run_if(dialogue_closed)
+                    .after(s_open_dialogue)
+                    .before(ControllerPhase::Input),
+            )
+            .add_systems(Update, s_render_shops.after(s_render));
+    }
+}
+
+/// An item that can be bought in a shop
+pub struct ShopItem {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub cost: u32,
+    pub upgrade: ShopUpgrade,
+}
+
+/// Controller config change applied when an item is bought
+#[derive(Clone, Copy)]
+pub enum ShopUpgrade {
+    /// Adds to the sprint max speed (pixels/second)
+    SprintSpeed(f32),
+    /// Adds to the maximum sprint stamina (only sold while stamina is enabled)
+    MaxStamina(f32),
+    /// Adds to the corner correction distance (pixels)
+    CornerCorrection(f32),
+    /// Unlocks the glide ability
+    Glide,
+    /// Unlocks the ground pound
+    GroundPound,
+    /// Replaces the air jumps with a jetpack
+    Jetpack,
+    /// Adds an air jump (not sold once the jetpack replaces them)
+    AirJump,
+}
+
+impl ShopUpgrade {
+    /// Whether the upgrade does anything with this config (e.g. stamina upgrades need stamina)
+    pub fn is_available(&self, config: &ControllerConfig) -> bool {
+        match self {
+            ShopUpgrade::MaxStamina(_) => config.sprint.stamina.is_some(),
+            // The jetpack takes the place of air jumps
+            ShopUpgrade::AirJump => config.jetpack.is_none(),
+            _ => true,
+        }
+    }
+
+    pub fn apply(&self, config: &mut ControllerConfig) {
+        match *self {
+            ShopUpgrade::SprintSpeed(amount) => config.sprint.max_speed += amount,
+            ShopUpgrade::MaxStamina(amount) => {
+                if let Some(stamina) = &mut config.sprint.stamina {
+                    stamina.max += amount;
+                }
+            }
+            ShopUpgrade::CornerCorrection(amount) => config.corner_correction_distance += amount,
+            ShopUpgrade::Glide => config.glide.enabled = true,
+            ShopUpgrade::GroundPound => config.ground_pound.enabled = true,
+            ShopUpgrade::Jetpack => {
+                config.jetpack.get_or_insert_with(JetpackConfig::default);
+            }
+            ShopUpgrade::AirJump => config.air_jumps += 1,
+        }
+    }
+}
+
+/// Every item shops can sell, referenced by id from the level data
+pub const SHOP_CATALOG: &[ShopItem] = &[
+    ShopItem {
+        id: "sprint_boots",
+        name: "Sprint Boots (+75 sprint speed)",
+        cost: 3,
+        upgrade: ShopUpgrade::SprintSpeed(75.0),
+    },
+    ShopItem {
+        id: "stamina_flask",
+        name: "Stamina Flask (+1 stamina)",
+        cost: 2,
+        upgrade: ShopUpgrade::MaxStamina(1.0),
+    },
+    ShopItem {
+        id: "corner_grips",
+        name: "Corner Grips (+4 corner correction)",
+        cost: 2,
+        upgrade: ShopUpgrade::CornerCorrection(4.0),
+    },
+    ShopItem {
+        id: "glider",
+        name: "Glider (hold jump while falling)",
+        cost: 4,
+        upgrade: ShopUpgrade::Glide,
+    },
+    ShopItem {
+        id: "pound_boots",
+        name: "Pound Boots (down + jump in the air)",
+        cost: 3,
+        upgrade: ShopUpgrade::GroundPound,
+    },
+    ShopItem {
+        id: "jetpack",
+        name: "Jetpack (press and hold jump in the air)",
+        cost: 5,
+        upgrade: ShopUpgrade::Jetpack,
+    },
+    ShopItem {
+        id: "feather",
+        name: "Feather (+1 air jump)",
+        cost: 4,
+        upgrade: ShopUpgrade::AirJump,
+    },
+];
+
+/// Find a catalog item by id
+pub fn find_shop_item(id: &str) -> Option<&'static ShopItem> {
+    SHOP_CATALOG.iter().find(|item| item.id == id)
+}
+
+/// A shop the player can browse by interacting nearby
+#[derive(Component)]
+pub struct Shop {
+    /// Ids of the catalog items on sale
+    pub items: Vec<String>,
+}
+
+/// Currently open shop menu, if any
+#[derive(Resource, Default)]
+pub struct ActiveShop(pub Option<ShopState>);
+
+pub struct ShopState {
+    items: Vec<&'static ShopItem>,
+    /// Simulation speed to restore when the menu closes
+    previous_speed: f32,
+    /// Root UI node of the menu
+    menu: Entity,
+}
+
+/// Run condition: no shop menu is open, so signs can take E/Enter
+pub fn shop_closed(active_shop: Res<ActiveShop>) -> bool {
+    active_shop.0.is_none()
+}
+
+/// Marker for the shop menu text
+#[derive(Component)]
+pub struct ShopText;
+
+/// Re-applies purchased upgrades from a save to the controller config
+pub fn apply_purchases(save_data: &SaveData, config: &mut ControllerConfig) {
+    for item in save_data
+        .purchases
+        .iter()
+        .filter_map(|id| find_shop_item(id))
+    {
+        item.upgrade.apply(config);
+    }
+}
+
+/// Opens the menu of the closest shop in reach when the player presses E/Enter, listing the
+/// items whose upgrade does something with the current config
+pub fn s_open_shop(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    config: Res<ControllerConfig>,
+    mut active_shop: ResMut<ActiveShop>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    player_query: Query<(&Transform, &Physics), With<Player>>,
+    shop_query: Query<(&Transform, &Shop)>,
+) {
+    if active_shop.0.is_some() || !keyboard_input.any_just_pressed([KeyCode::KeyE, KeyCode::Enter])
+    {
+        return;
+    }
+
+    let Ok((player_transform, player_physics)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.xy();
+    let reach = player_physics.radius + SHOP_INTERACT_DISTANCE;
+
+    let closest_shop = shop_query
+        .iter()
+        .map(|(transform, shop)| (transform.translation.xy().distance(player_pos), shop))
+        .filter(|(distance, _)| *distance <= reach)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    if let Some((_, shop)) = closest_shop {
+        let previous_speed = virtual_time.relative_speed();
+        virtual_time.pause();
+
+        let menu = commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(SHOP_MENU_MARGIN),
+                    top: Val::Px(SHOP_MENU_MARGIN),
+                    padding: UiRect::all(Val::Px(SHOP_MENU_PADDING)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            ))
+            .with_children(|parent| {
+                parent.spawn((Text::new(""), ShopText));
+            })
+            .id();
+
+        active_shop.0 = Some(ShopState {
+            items: shop
+                .items
+                .iter()
+                .filter_map(|id| find_shop_item(id))
+                .filter(|item| item.upgrade.is_available(&config))
+                .collect(),
+            previous_speed,
+            menu,
+        });
+    }
+}
+
+/// Handles purchases in the open shop menu and keeps its text up to date
+///
+/// Number keys buy the listed items; E/Enter/Tab leaves the shop.
+/// Keys used here are consumed so they don't also trigger a jump or reopen the shop.
+pub fn s_shop_menu(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut active_shop: ResMut<ActiveShop>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut save: ProgressSave,
+    mut config: ResMut<ControllerConfig>,
+    mut text_query: Query<&mut Text, With<ShopText>>,
+) {
+    let Some(shop) = &active_shop.0 else {
+        return;
+    };
+
+    let close = keyboard_input.clear_just_pressed(KeyCode::KeyE)
+        | keyboard_input.clear_just_pressed(KeyCode::Enter)
+        | keyboard_input.clear_just_pressed(KeyCode::Tab);
+    keyboard_input.clear_just_pressed(KeyCode::Space);
+
+    if close {
+        commands.entity(shop.menu).despawn();
+        virtual_time.unpause();
+        virtual_time.set_relative_speed(shop.previous_speed);
+        active_shop.0 = None;
+        return;
+    }
+
+    for (item, key) in shop.items.iter().zip(PURCHASE_KEYS) {
+        if !keyboard_input.just_pressed(key)
+            || save.progress.0.purchases.iter().any(|id| id == item.id)
+            || save.progress.0.currency < item.cost
+            || !item.upgrade.is_available(&config)
+        {
+            continue;
+        }
+
+        save.progress.0.currency -= item.cost;
+        save.progress.0.purchases.push(item.id.to_string());
+        item.upgrade.apply(&mut config);
+        save.store();
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        let mut menu_text = format!("Shop - {} coins\n", save.progress.0.currency);
+        for (i, item) in shop.items.iter().enumerate() {
+            let status = if save.progress.0.purchases.iter().any(|id| id == item.id) {
+                "owned".to_string()
+            } else {
+                format!("{} coins", item.cost)
+            };
+            menu_text.push_str(&format!("{}. {} - {}\n", i + 1, item.name, status));
+        }
+        menu_text.push_str("E/Tab: leave");
+        text.0 = menu_text;
+    }
+}
+
+/// Draws shops as boxes
+pub fn s_render_shops(mut gizmos: Gizmos, shop_query: Query<&Transform, With<Shop>>) {
+    for shop_transform in &shop_query {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(shop_transform.translation.xy()),
+            SHOP_SIZE,
+            SHOP_COLOR,
+        );
+    }
+}
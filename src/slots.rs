@@ -0,0 +1,221 @@
+use bevy::{input::ButtonInput, prelude::*};
+
+use crate::{
+    assist::AssistMode,
+    config::ControllerConfig,
+    gamepad::StickConfig,
+    levels::{total_medals, GameState, LevelManager},
+    progression::{Progress, ProgressSave},
+    save::{delete_slot, load_slot, store_save, SaveBackend, SaveData, SAVE_SLOTS},
+    shop::apply_purchases,
+    snapshot::PendingResume,
+};
+
+// Slot menu constants
+const SLOT_MENU_MARGIN: f32 = 24.0;
+const SLOT_MENU_PADDING: f32 = 12.0;
+
+/// Save slot selection at startup, with copy and delete
+pub struct SlotsPlugin;
+
+impl Plugin for SlotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SlotMenu>()
+            .add_systems(Startup, s_capture_base_config)
+            .add_systems(OnEnter(GameState::SlotSelect), s_spawn_slot_menu)
+            .add_systems(
+                OnExit(GameState::SlotSelect),
+                (s_despawn_slot_menu, s_apply_profile),
+            )
+            .add_systems(Update, s_slot_menu.run_if(in_state(GameState::SlotSelect)));
+    }
+}
+
+/// The app's own `ControllerConfig`, captured before any profile's purchases are applied
+#[derive(Resource, Clone)]
+pub struct BaseControllerConfig(pub ControllerConfig);
+
+/// Slot menu state
+#[derive(Resource, Default)]
+pub struct SlotMenu {
+    selected: usize,
+    /// Contents of every slot (`None` for empty slots)
+    slots: Vec<Option<SaveData>>,
+    /// Whether Delete was pressed once on the selected slot
+    confirm_delete: bool,
+}
+
+impl SlotMenu {
+    fn refresh(&mut self, backend: &SaveBackend) {
+        self.slots = (0..SAVE_SLOTS)
+            .map(|slot| load_slot(backend, slot))
+            .collect();
+    }
+}
+
+/// Marker for the slot menu UI root
+#[derive(Component)]
+pub struct SlotMenuUi;
+
+/// Marker for the slot menu text
+#[derive(Component)]
+pub struct SlotMenuText;
+
+/// Captures the config the app inserted so profiles reset to it rather than the defaults
+pub fn s_capture_base_config(mut commands: Commands, config: Res<ControllerConfig>) {
+    commands.insert_resource(BaseControllerConfig(config.clone()));
+}
+
+pub fn s_spawn_slot_menu(
+    mut commands: Commands,
+    mut slot_menu: ResMut<SlotMenu>,
+    save_backend: Res<SaveBackend>,
+) {
+    slot_menu.refresh(&save_backend);
+    slot_menu.confirm_delete = false;
+
+    commands
+        .spawn((
+            SlotMenuUi,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(SLOT_MENU_MARGIN),
+                top: Val::Px(SLOT_MENU_MARGIN),
+                padding: UiRect::all(Val::Px(SLOT_MENU_PADDING)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(""), SlotMenuText));
+        });
+}
+
+pub fn s_despawn_slot_menu(mut commands: Commands, ui_query: Query<Entity, With<SlotMenuUi>>) {
+    for entity in &ui_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Slot menu: Up/Down selects a slot, Enter plays it (starting a new profile if empty),
+/// R resumes its autosaved run, C copies it into the first empty slot and Delete
+/// (pressed twice) erases it
+pub fn s_slot_menu(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut slot_menu: ResMut<SlotMenu>,
+    mut save: ProgressSave,
+    mut level_manager: ResMut<LevelManager>,
+    mut pending_resume: ResMut<PendingResume>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut text_query: Query<&mut Text, With<SlotMenuText>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) && slot_menu.selected > 0 {
+        slot_menu.selected -= 1;
+        slot_menu.confirm_delete = false;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) && slot_menu.selected + 1 < SAVE_SLOTS {
+        slot_menu.selected += 1;
+        slot_menu.confirm_delete = false;
+    }
+
+    let selected = slot_menu.selected;
+
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        save.progress.0 = slot_menu.slots[selected].clone().unwrap_or_default();
+        save.progress.0.slot = Some(selected);
+        save.store();
+        next_state.set(GameState::WorldMap);
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        let snapshot = slot_menu.slots[selected]
+            .as_ref()
+            .and_then(|save_data| save_data.run_snapshot.clone());
+        if let Some((level, snapshot)) =
+            snapshot.and_then(|snapshot| Some((snapshot.level_index()?, snapshot)))
+        {
+            save.progress.0 = slot_menu.slots[selected].clone().unwrap_or_default();
+            save.progress.0.slot = Some(selected);
+            level_manager.current = level;
+            pending_resume.0 = Some(snapshot);
+            next_state.set(GameState::Playing);
+            return;
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        let empty_slot = slot_menu.slots.iter().position(Option::is_none);
+        if let (Some(source), Some(target)) = (&slot_menu.slots[selected], empty_slot) {
+            let mut copy = source.clone();
+            copy.slot = Some(target);
+            store_save(&save.backend, &copy);
+            slot_menu.refresh(&save.backend);
+        }
+        slot_menu.confirm_delete = false;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Delete) && slot_menu.slots[selected].is_some() {
+        if slot_menu.confirm_delete {
+            delete_slot(&save.backend, selected);
+            slot_menu.refresh(&save.backend);
+            slot_menu.confirm_delete = false;
+        } else {
+            slot_menu.confirm_delete = true;
+        }
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        let mut menu_text = "Save Slots\n".to_string();
+        for (i, slot) in slot_menu.slots.iter().enumerate() {
+            let cursor = if i == selected { ">" } else { " " };
+            let summary = match slot {
+                Some(save_data) => format!(
+                    "{} medals, {} coins, {} clears, {:.0}s played",
+                    total_medals(save_data),
+                    save_data.currency,
+                    save_data.stats.completions,
+                    save_data.stats.play_time
+                ),
+                None => "Empty".to_string(),
+            };
+            let resume = match slot
+                .as_ref()
+                .and_then(|save_data| save_data.run_snapshot.as_ref())
+            {
+                Some(snapshot) => format!(" [run in progress: {}]", snapshot.level),
+                None => String::new(),
+            };
+            menu_text.push_str(&format!(
+                "{} Slot {}: {}{}\n",
+                cursor,
+                i + 1,
+                summary,
+                resume
+            ));
+        }
+
+        if slot_menu.confirm_delete {
+            menu_text.push_str("Press Delete again to erase this slot\n");
+        }
+        menu_text.push_str("Up/Down: select, Enter: play, R: resume run, C: copy, Delete: erase");
+        text.0 = menu_text;
+    }
+}
+
+/// Applies the chosen profile: purchased upgrades and settings overrides
+pub fn s_apply_profile(
+    progress: Res<Progress>,
+    base_config: Res<BaseControllerConfig>,
+    mut config: ResMut<ControllerConfig>,
+    mut stick_config: ResMut<StickConfig>,
+    mut assist: ResMut<AssistMode>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    *config = base_config.0.clone();
+    apply_purchases(&progress.0, &mut config);
+
+    *stick_config = progress.0.settings.stick.unwrap_or_default();
+    *assist = progress.0.settings.assist.clone().unwrap_or_default();
+    virtual_time.set_relative_speed(assist.speed());
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gravity::{s_reset_gravity, Gravity},
+    levels::{s_reset_level_timer, GameState, LevelManager, LEVELS},
+    progression::{s_restore_progress, ProgressSave},
+    s_exit, s_spawn_level,
+    schedule::ControllerSystems,
+    Physics, Player, ShouldExit,
+};
+
+/// Time (seconds, real time) between autosaves while playing
+const AUTOSAVE_INTERVAL: f32 = 10.0;
+
+/// Run snapshots: the level attempt in progress is autosaved periodically and on a clean exit,
+/// and can be resumed from the save slot menu
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingResume>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                s_resume_run
+                    .after(s_spawn_level)
+                    .after(s_restore_progress)
+                    .after(s_reset_level_timer)
+                    .after(s_reset_gravity),
+            )
+            .add_systems(OnExit(GameState::Playing), s_clear_snapshot)
+            .add_systems(
+                Update,
+                s_autosave
+                    .after(ControllerSystems)
+                    .before(s_exit)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Serialized state of a level attempt in progress
+///
+/// Collected items aren't part of the snapshot: they are already saved when picked up.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RunSnapshot {
+    /// Id of the level being played
+    pub level: String,
+    /// Time (seconds) spent in the attempt
+    pub elapsed: f32,
+    /// Whether assist mode was used during the attempt
+    pub assisted: bool,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub jump_timer: f32,
+    pub grounded_timer: f32,
+    pub wall_timer: f32,
+    pub wall_direction: f32,
+    #[serde(default)]
+    pub gravity_flipped: bool,
+}
+
+impl RunSnapshot {
+    pub fn capture(
+        level_manager: &LevelManager,
+        gravity: &Gravity,
+        transform: &Transform,
+        physics: &Physics,
+        player: &Player,
+    ) -> Self {
+        Self {
+            level: level_manager.current_level().id.to_string(),
+            elapsed: level_manager.elapsed,
+            assisted: level_manager.assisted,
+            position: transform.translation.xy(),
+            velocity: physics.velocity,
+            jump_timer: player.jump_timer,
+            grounded_timer: player.grounded_timer,
+            wall_timer: player.wall_timer,
+            wall_direction: player.wall_direction,
+            gravity_flipped: gravity.flipped,
+        }
+    }
+
+    /// Index of the snapshot's level in `LEVELS`, if it still exists
+    pub fn level_index(&self) -> Option<usize> {
+        LEVELS.iter().position(|level| level.id == self.level)
+    }
+}
+
+/// Snapshot to restore when the next level is spawned
+#[derive(Resource, Default)]
+pub struct PendingResume(pub Option<RunSnapshot>);
+
+/// Autosaves the run every `AUTOSAVE_INTERVAL` seconds and when the game exits cleanly
+pub fn s_autosave(
+    real_time: Res<Time<Real>>,
+    should_exit: Res<ShouldExit>,
+    level_manager: Res<LevelManager>,
+    gravity: Res<Gravity>,
+    mut save: ProgressSave,
+    player_query: Query<(&Transform, &Physics, &Player)>,
+    mut since_autosave: Local<f32>,
+) {
+    *since_autosave += real_time.delta_secs();
+    if *since_autosave < AUTOSAVE_INTERVAL && !should_exit.0 {
+        return;
+    }
+    *since_autosave = 0.0;
+
+    if let Ok((player_transform, player_physics, player_data)) = player_query.single() {
+        save.progress.0.run_snapshot = Some(RunSnapshot::capture(
+            &level_manager,
+            &gravity,
+            player_transform,
+            player_physics,
+            player_data,
+        ));
+        save.store();
+    }
+}
+
+/// Restores a pending snapshot onto the freshly spawned level
+pub fn s_resume_run(
+    mut pending_resume: ResMut<PendingResume>,
+    mut level_manager: ResMut<LevelManager>,
+    mut gravity: ResMut<Gravity>,
+    mut player_query: Query<(&mut Transform, &mut Physics, &mut Player)>,
+) {
+    let Some(snapshot) = pending_resume.0.take() else {
+        return;
+    };
+    let Ok((mut player_transform, mut player_physics, mut player_data)) = player_query.single_mut()
+    else {
+        return;
+    };
+
+    level_manager.elapsed = snapshot.elapsed;
+    level_manager.assisted |= snapshot.assisted;
+    gravity.flipped = snapshot.gravity_flipped;
+
+    player_transform.translation.x = snapshot.position.x;
+    player_transform.translation.y = snapshot.position.y;
+    player_physics.prev_position = snapshot.position;
+    player_physics.velocity = snapshot.velocity;
+    player_data.jump_timer = snapshot.jump_timer;
+    player_data.grounded_timer = snapshot.grounded_timer;
+    player_data.wall_timer = snapshot.wall_timer;
+    player_data.wall_direction = snapshot.wall_direction;
+}
+
+/// Leaving a level ends the run, so its snapshot is dropped
+pub fn s_clear_snapshot(mut save: ProgressSave) {
+    if save.progress.0.run_snapshot.take().is_some() {
+        save.store();
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{s_render, schedule::ControllerPhase, Physics, Player};
+
+const TELEPORTER_COLOR: Color = Color::srgb(0.7, 0.3, 1.0);
+// Time (seconds) after a teleport during which the pair can't be used again
+const TELEPORT_COOLDOWN: f32 = 0.5;
+
+/// Teleporters: paired areas, entering either one moves the player to the center of the other
+pub struct TeleporterPlugin;
+
+impl Plugin for TeleporterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<PlayerTeleported>()
+            .add_systems(
+                Update,
+                s_teleporters
+                    .after(ControllerPhase::Movement)
+                    .before(ControllerPhase::Collision),
+            )
+            .add_systems(Update, s_render_teleporters.after(s_render));
+    }
+}
+
+/// What happens to the player's velocity through a teleporter
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TeleportVelocity {
+    /// Kept as it was
+    #[default]
+    Preserve,
+    /// Stopped
+    Zero,
+    /// Same speed, along the given direction (world space, +y up)
+    Redirect([f32; 2]),
+}
+
+impl TeleportVelocity {
+    pub fn apply(self, velocity: Vec2) -> Vec2 {
+        match self {
+            Self::Preserve => velocity,
+            Self::Zero => Vec2::ZERO,
+            Self::Redirect(direction) => {
+                Vec2::from(direction).normalize_or_zero() * velocity.length()
+            }
+        }
+    }
+}
+
+/// The player went through a teleporter
+#[derive(Message, Clone, Copy)]
+pub struct PlayerTeleported {
+    /// Where the player was
+    pub from: Vec2,
+    /// Where the player arrived
+    pub to: Vec2,
+}
+
+/// Two linked teleport areas
+#[derive(Component)]
+pub struct TeleporterPair {
+    pub areas: [Rect; 2],
+    pub velocity: TeleportVelocity,
+    /// Time (seconds) left before the pair can be used again
+    pub cooldown: f32,
+}
+
+/// Teleports the player entering a teleport area to the center of its pair
+///
+/// Only entering counts (the previous position was outside), and arriving moves the previous
+/// position too, so the player standing in the exit area isn't sent back and collision doesn't
+/// sweep from the entrance and treat the jump as tunnelling into a polygon.
+pub fn s_teleporters(
+    time: Res<Time>,
+    mut teleporter_query: Query<&mut TeleporterPair>,
+    mut player_query: Query<(&mut Transform, &mut Physics), With<Player>>,
+    mut teleported_writer: MessageWriter<PlayerTeleported>,
+) {
+    let dt = time.delta_secs();
+    for mut teleporter in &mut teleporter_query {
+        teleporter.cooldown = (teleporter.cooldown - dt).max(0.0);
+    }
+
+    let Ok((mut player_transform, mut player_physics)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.xy();
+
+    for mut teleporter in &mut teleporter_query {
+        if teleporter.cooldown > 0.0 {
+            continue;
+        }
+
+        let entered = (0..2).find(|&from| {
+            let area = teleporter.areas[from];
+            area.contains(player_pos) && !area.contains(player_physics.prev_position)
+        });
+        let Some(from) = entered else {
+            continue;
+        };
+
+        let exit_pos = teleporter.areas[1 - from].center();
+        player_transform.translation = exit_pos.extend(player_transform.translation.z);
+        player_physics.prev_position = exit_pos;
+        player_physics.velocity = teleporter.velocity.apply(player_physics.velocity);
+        teleporter.cooldown = TELEPORT_COOLDOWN;
+
+        teleported_writer.write(PlayerTeleported {
+            from: player_pos,
+            to: exit_pos,
+        });
+        return;
+    }
+}
+
+/// Draws teleport areas and the link between them, dimmed while cooling down
+pub fn s_render_teleporters(mut gizmos: Gizmos, teleporter_query: Query<&TeleporterPair>) {
+    for teleporter in &teleporter_query {
+        let color = if teleporter.cooldown > 0.0 {
+            TELEPORTER_COLOR.with_alpha(0.4)
+        } else {
+            TELEPORTER_COLOR
+        };
+        for area in &teleporter.areas {
+            gizmos.rect_2d(
+                Isometry2d::from_translation(area.center()),
+                area.size(),
+                color,
+            );
+        }
+        gizmos.line_2d(
+            teleporter.areas[0].center(),
+            teleporter.areas[1].center(),
+            color.with_alpha(0.2),
+        );
+    }
+}
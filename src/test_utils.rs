@@ -0,0 +1,241 @@
+//! Helpers for writing movement tests against a level (enabled by the `test_utils` feature)
+//!
+//! A `TestWorld` is a headless app with just the controller phases, a level and one player,
+//! stepped at a fixed tick with scripted keyboard input:
+//!
+//! ```ignore
+//! let mut world = TestWorld::from_json(include_str!("my_level.json"), ControllerConfig::default());
+//! world.run(30, &[]);
+//! assert!(world.is_grounded());
+//! world.run(10, &[KeyCode::Space, KeyCode::ArrowRight]);
+//! assert!(world.player_velocity().y > 0.0);
+//! ```
+
+use std::time::Duration;
+
+use bevy::{input::ButtonInput, prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    ascii_level::parse_ascii_level,
+    assist::AssistMode,
+    collisions::{
+        CollisionDiagnostics, CollisionSubsteps, ContactEvent, ContactsThisTick, CrushedEvent,
+        GroundPoundLanding, LandingContact,
+    },
+    config::ControllerConfig,
+    gravity::Gravity,
+    level::{load_level_data, LevelData},
+    player_bundle,
+    schedule::ControllerSchedule,
+    InputDir, Level, Physics, Player, ShouldExit, GRID_SIZE,
+};
+
+/// Fixed time step (seconds) of one test tick
+pub const TEST_TICK: f32 = 1.0 / 60.0;
+
+/// Player state and held keys between two ticks
+///
+/// A test world only holds a static level and the player, so this is everything needed to
+/// rewind it.
+#[derive(Clone)]
+pub struct TestSnapshot {
+    transform: Transform,
+    physics: Physics,
+    player: Player,
+    held: Vec<KeyCode>,
+}
+
+/// Headless controller world for tests
+pub struct TestWorld {
+    pub app: App,
+}
+
+impl TestWorld {
+    /// World for a level in the level file format (a bare tile grid or a full level object)
+    pub fn from_json(json: &str, config: ControllerConfig) -> Self {
+        Self::new(load_level_data(json.as_bytes()), config)
+    }
+
+    /// World for an ASCII-art level (see `ascii_level`)
+    ///
+    /// Panics if the level can't be parsed.
+    pub fn from_ascii(text: &str, config: ControllerConfig) -> Self {
+        match parse_ascii_level(text) {
+            Ok(ascii_level) => Self::new(ascii_level.data, config),
+            Err(error) => panic!("invalid ASCII level: {error}"),
+        }
+    }
+
+    /// World for a tile grid (1 = solid), with the player spawned at a tile position
+    pub fn from_grid(tiles: Vec<Vec<u32>>, spawn: [f32; 2], config: ControllerConfig) -> Self {
+        Self::new(
+            LevelData {
+                tiles,
+                spawn: Some(spawn),
+                ..Default::default()
+            },
+            config,
+        )
+    }
+
+    /// World for a level with the default controller schedule
+    pub fn new(level_data: LevelData, config: ControllerConfig) -> Self {
+        Self::with_schedule(level_data, config, ControllerSchedule::default())
+    }
+
+    /// World for a level with a custom controller schedule (e.g. a replaced collision phase)
+    ///
+    /// Panics if the schedule is invalid.
+    pub fn with_schedule(
+        level_data: LevelData,
+        config: ControllerConfig,
+        schedule: ControllerSchedule,
+    ) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                TEST_TICK,
+            )))
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<AssistMode>()
+            .init_resource::<ContactsThisTick>()
+            .init_resource::<CollisionDiagnostics>()
+            .init_resource::<CollisionSubsteps>()
+            .insert_resource(InputDir { dir: Vec2::ZERO })
+            .insert_resource(ShouldExit(false))
+            .add_message::<LandingContact>()
+            .add_message::<CrushedEvent>()
+            .add_message::<GroundPoundLanding>()
+            .add_message::<ContactEvent>()
+            .insert_resource(Level::from_data(&level_data, GRID_SIZE))
+            .insert_resource(Gravity {
+                field: level_data.gravity_field(GRID_SIZE),
+                zones: level_data.gravity_zones(GRID_SIZE),
+                flipped: false,
+            });
+
+        app.world_mut()
+            .spawn(player_bundle(level_data.spawn_position(GRID_SIZE), &config));
+        app.insert_resource(config);
+
+        if let Err(error) = schedule.build(&mut app) {
+            panic!("invalid controller schedule: {error}");
+        }
+
+        Self { app }
+    }
+
+    /// Runs one tick with exactly the given keys held
+    ///
+    /// Keys held now but not on the previous tick count as just pressed, and keys no longer
+    /// held as just released.
+    pub fn step(&mut self, held: &[KeyCode]) {
+        let mut keyboard_input = self.app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        let released: Vec<KeyCode> = keyboard_input
+            .get_pressed()
+            .filter(|key| !held.contains(key))
+            .copied()
+            .collect();
+        for key in released {
+            keyboard_input.release(key);
+        }
+        for &key in held {
+            keyboard_input.press(key);
+        }
+
+        self.app.update();
+
+        self.app
+            .world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+    }
+
+    /// Runs `ticks` ticks with the given keys held
+    pub fn run(&mut self, ticks: u32, held: &[KeyCode]) {
+        for _ in 0..ticks {
+            self.step(held);
+        }
+    }
+
+    /// Runs a script of (ticks, held keys) segments in order
+    pub fn run_script(&mut self, script: &[(u32, &[KeyCode])]) {
+        for &(ticks, held) in script {
+            self.run(ticks, held);
+        }
+    }
+
+    pub fn player_position(&mut self) -> Vec2 {
+        self.player::<Transform>().translation.xy()
+    }
+
+    pub fn player_velocity(&mut self) -> Vec2 {
+        self.player::<Physics>().velocity
+    }
+
+    /// Whether the player is on the ground (including coyote time)
+    pub fn is_grounded(&mut self) -> bool {
+        self.player::<Player>().grounded_timer > 0.0
+    }
+
+    /// Whether the player is touching a wall (including wall coyote time)
+    pub fn is_on_wall(&mut self) -> bool {
+        self.player::<Player>().wall_timer > 0.0
+    }
+
+    /// Captures the state between ticks, to `restore` later
+    pub fn snapshot(&mut self) -> TestSnapshot {
+        TestSnapshot {
+            transform: *self.player::<Transform>(),
+            physics: self.player::<Physics>().clone(),
+            player: self.player::<Player>().clone(),
+            held: self
+                .app
+                .world()
+                .resource::<ButtonInput<KeyCode>>()
+                .get_pressed()
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Rewinds the world to a snapshot
+    pub fn restore(&mut self, snapshot: &TestSnapshot) {
+        let entity = self.player_entity();
+        let world = self.app.world_mut();
+        let mut player = world.entity_mut(entity);
+        *player
+            .get_mut::<Transform>()
+            .expect("player has a transform") = snapshot.transform;
+        *player.get_mut::<Physics>().expect("player has physics") = snapshot.physics.clone();
+        *player.get_mut::<Player>().expect("player has player data") = snapshot.player.clone();
+
+        let mut keyboard_input = world.resource_mut::<ButtonInput<KeyCode>>();
+        keyboard_input.release_all();
+        for &key in &snapshot.held {
+            keyboard_input.press(key);
+        }
+        keyboard_input.clear();
+    }
+
+    /// Mutable access to the controller config between ticks
+    pub fn config_mut(&mut self) -> Mut<'_, ControllerConfig> {
+        self.app.world_mut().resource_mut::<ControllerConfig>()
+    }
+
+    fn player_entity(&mut self) -> Entity {
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, With<Player>>()
+            .single(self.app.world())
+            .expect("test world has one player")
+    }
+
+    fn player<T: Component>(&mut self) -> &T {
+        let entity = self.player_entity();
+        self.app
+            .world()
+            .get::<T>(entity)
+            .expect("player has the component")
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::{s_render, schedule::ControllerPhase, Player};
+
+const SLOW_BUBBLE_COLOR: Color = Color::srgb(0.5, 0.5, 1.0);
+const HASTE_BUBBLE_COLOR: Color = Color::srgb(1.0, 0.7, 0.3);
+
+/// Timescale bubbles: areas where time runs slower (slow zones) or faster (haste zones) for
+/// whatever is inside, on top of the global virtual time speed
+pub struct TimeBubblePlugin;
+
+impl Plugin for TimeBubblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, s_time_bubbles.before(ControllerPhase::Input))
+            .add_systems(Update, s_render_time_bubbles.after(s_render));
+    }
+}
+
+/// Area of the level with its own time speed
+#[derive(Component)]
+pub struct TimeBubble {
+    pub area: Rect,
+    /// Multiplier on the delta time inside (below 1.0 slows down, above 1.0 speeds up)
+    pub scale: f32,
+}
+
+/// Combined time scale of the bubbles containing a point (overlapping bubbles multiply)
+pub fn time_scale_at<'a>(bubbles: impl IntoIterator<Item = &'a TimeBubble>, point: Vec2) -> f32 {
+    bubbles
+        .into_iter()
+        .filter(|bubble| bubble.area.contains(point))
+        .map(|bubble| bubble.scale)
+        .product()
+}
+
+/// Sets the player's time scale from the bubbles containing its center
+///
+/// The controller multiplies `Time`'s delta (already scaled by the global speed) by it before
+/// clamping, so a haste bubble can't push a step past the usual limit.
+pub fn s_time_bubbles(
+    bubble_query: Query<&TimeBubble>,
+    mut player_query: Query<(&Transform, &mut Player)>,
+) {
+    let Ok((player_transform, mut player_data)) = player_query.single_mut() else {
+        return;
+    };
+
+    player_data.time_scale = time_scale_at(&bubble_query, player_transform.translation.xy());
+}
+
+/// Debug view: bubble outlines, blue for slow and orange for haste
+pub fn s_render_time_bubbles(mut gizmos: Gizmos, bubble_query: Query<&TimeBubble>) {
+    for bubble in &bubble_query {
+        let color = if bubble.scale < 1.0 {
+            SLOW_BUBBLE_COLOR
+        } else {
+            HASTE_BUBBLE_COLOR
+        };
+        gizmos.rect_2d(
+            Isometry2d::from_translation(bubble.area.center()),
+            bubble.area.size(),
+            color,
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{aabb_overlaps_circle, point_in_polygon, CollisionLayers},
+    geometry::closest_point_on_segment,
+    level::Aabb,
+    s_render,
+    schedule::ControllerSystems,
+    Physics,
+};
+
+const TRIGGER_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+// Opacity of trigger outlines: they are invisible in play, drawn faintly for level design
+const TRIGGER_ALPHA: f32 = 0.3;
+
+/// Sensor volumes: shapes that never push bodies but report them entering and leaving, for
+/// checkpoints, kill zones, cutscene triggers and the like
+pub struct TriggerPlugin;
+
+impl Plugin for TriggerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<TriggerEnter>()
+            .add_message::<TriggerExit>()
+            .add_systems(Update, s_triggers.after(ControllerSystems))
+            .add_systems(Update, s_render_triggers.after(s_render));
+    }
+}
+
+/// World-space shape of a trigger
+#[derive(Clone, Debug)]
+pub enum TriggerShape {
+    Box(Aabb),
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    /// Closed outline (last point equal to the first)
+    Polygon(Vec<Vec2>),
+}
+
+impl TriggerShape {
+    /// Whether a circle overlaps the shape, touching its outline or lying inside it
+    pub fn overlaps_circle(&self, center: Vec2, radius: f32) -> bool {
+        match self {
+            TriggerShape::Box(aabb) => aabb_overlaps_circle(aabb, center, radius),
+            TriggerShape::Circle {
+                center: trigger_center,
+                radius: trigger_radius,
+            } => center.distance_squared(*trigger_center) < (radius + trigger_radius).powi(2),
+            TriggerShape::Polygon(points) => {
+                point_in_polygon(points, center)
+                    || points.windows(2).any(|edge| {
+                        closest_point_on_segment(edge[0], edge[1], center).distance_squared(center)
+                            < radius.powi(2)
+                    })
+            }
+        }
+    }
+}
+
+/// Sensor volume reporting the `Physics` bodies that enter and leave it
+#[derive(Component, Clone, Debug)]
+pub struct Trigger {
+    /// Name gameplay code matches on (e.g. the cutscene to start)
+    pub id: String,
+    pub shape: TriggerShape,
+    /// Only bodies whose `CollisionLayers` interact with these set it off
+    pub layers: CollisionLayers,
+    /// Bodies inside as of the last `s_triggers`
+    pub occupants: Vec<Entity>,
+}
+
+impl Trigger {
+    pub fn new(id: impl Into<String>, shape: TriggerShape) -> Self {
+        Self {
+            id: id.into(),
+            shape,
+            layers: CollisionLayers::default(),
+            occupants: Vec::new(),
+        }
+    }
+}
+
+/// A body started overlapping a trigger
+#[derive(Message, Clone, Copy, Debug)]
+pub struct TriggerEnter {
+    pub trigger: Entity,
+    pub entity: Entity,
+}
+
+/// A body stopped overlapping a trigger (or was despawned inside it)
+#[derive(Message, Clone, Copy, Debug)]
+pub struct TriggerExit {
+    pub trigger: Entity,
+    pub entity: Entity,
+}
+
+/// Updates each trigger's occupants from the body circles, writing enter/exit messages for the
+/// changes
+pub fn s_triggers(
+    mut trigger_query: Query<(Entity, &mut Trigger)>,
+    body_query: Query<(Entity, &Transform, &Physics, Option<&CollisionLayers>)>,
+    mut enter_writer: MessageWriter<TriggerEnter>,
+    mut exit_writer: MessageWriter<TriggerExit>,
+) {
+    for (trigger_entity, mut trigger) in &mut trigger_query {
+        let inside: Vec<Entity> = body_query
+            .iter()
+            .filter(|(_, transform, physics, layers)| {
+                trigger
+                    .layers
+                    .interacts_with(&layers.copied().unwrap_or_default())
+                    && trigger
+                        .shape
+                        .overlaps_circle(transform.translation.xy(), physics.radius)
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+
+        for &entity in inside.iter().filter(|e| !trigger.occupants.contains(e)) {
+            enter_writer.write(TriggerEnter {
+                trigger: trigger_entity,
+                entity,
+            });
+        }
+        for &entity in trigger.occupants.iter().filter(|e| !inside.contains(e)) {
+            exit_writer.write(TriggerExit {
+                trigger: trigger_entity,
+                entity,
+            });
+        }
+
+        trigger.occupants = inside;
+    }
+}
+
+/// Draws trigger outlines
+pub fn s_render_triggers(mut gizmos: Gizmos, trigger_query: Query<&Trigger>) {
+    let color = TRIGGER_COLOR.with_alpha(TRIGGER_ALPHA);
+    for trigger in &trigger_query {
+        match &trigger.shape {
+            TriggerShape::Box(aabb) => {
+                gizmos.rect_2d(
+                    Isometry2d::from_translation((aabb.min + aabb.max) / 2.0),
+                    aabb.max - aabb.min,
+                    color,
+                );
+            }
+            TriggerShape::Circle { center, radius } => {
+                gizmos.circle_2d(*center, *radius, color);
+            }
+            TriggerShape::Polygon(points) => {
+                gizmos.linestrip_2d(points.iter().copied(), color);
+            }
+        }
+    }
+}
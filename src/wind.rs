@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use crate::{s_render, schedule::ControllerPhase, Physics, Player};
+
+// Force zone rendering constants
+const FORCE_ZONE_COLOR: Color = Color::srgb(0.4, 0.8, 0.8);
+// Arrow length (pixels) per unit of acceleration (pixels/second²)
+const FORCE_ARROW_SCALE: f32 = 0.05;
+// Spacing (pixels) between arrows inside a zone
+const FORCE_ARROW_SPACING: f32 = 48.0;
+
+/// Wind and force-field zones: constant accelerations applied while the player is inside
+/// (updrafts, wind tunnels), added on top of the normal movement
+pub struct WindPlugin;
+
+impl Plugin for WindPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            s_apply_force_zones
+                .after(ControllerPhase::Input)
+                .before(ControllerPhase::Movement),
+        )
+        .add_systems(Update, s_render_force_zones.after(s_render));
+    }
+}
+
+/// Area of the level that pushes the player
+#[derive(Component)]
+pub struct ForceZone {
+    pub area: Rect,
+    /// Acceleration applied inside (pixels/second², world space)
+    pub acceleration: Vec2,
+}
+
+/// Adds the acceleration of every zone containing the player as an external force
+///
+/// Overlapping zones add up.
+pub fn s_apply_force_zones(
+    zone_query: Query<&ForceZone>,
+    mut player_query: Query<(&Transform, &mut Physics), With<Player>>,
+) {
+    let Ok((player_transform, mut player_physics)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.xy();
+
+    let acceleration: Vec2 = zone_query
+        .iter()
+        .filter(|zone| zone.area.contains(player_pos))
+        .map(|zone| zone.acceleration)
+        .sum();
+
+    player_physics.add_force(acceleration);
+}
+
+/// Debug view: zone outlines with arrows along the push, scaled by its strength
+pub fn s_render_force_zones(mut gizmos: Gizmos, zone_query: Query<&ForceZone>) {
+    for zone in &zone_query {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(zone.area.center()),
+            zone.area.size(),
+            FORCE_ZONE_COLOR,
+        );
+
+        let arrow = zone.acceleration * FORCE_ARROW_SCALE;
+        let columns = (zone.area.width() / FORCE_ARROW_SPACING).floor().max(1.0) as usize;
+        let rows = (zone.area.height() / FORCE_ARROW_SPACING).floor().max(1.0) as usize;
+        for column in 0..columns {
+            for row in 0..rows {
+                let cell = Vec2::new(
+                    (column as f32 + 0.5) / columns as f32,
+                    (row as f32 + 0.5) / rows as f32,
+                );
+                let center = zone.area.min + cell * zone.area.size();
+                gizmos.arrow_2d(center - arrow / 2.0, center + arrow / 2.0, FORCE_ZONE_COLOR);
+            }
+        }
+    }
+}
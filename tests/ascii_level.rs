@@ -0,0 +1,119 @@
+//! Parsing of the ASCII-art level format in `ascii_level`
+//!
+//! `cargo test --test ascii_level`
+
+use bevy::math::Vec2;
+use bevy_advanced_cc::{
+    ascii_level::{parse_ascii_level, AsciiLevelError},
+    Level, GRID_SIZE,
+};
+
+const TOLERANCE: f32 = 1e-4;
+
+fn parse_error(text: &str) -> AsciiLevelError {
+    match parse_ascii_level(text) {
+        Ok(_) => panic!("expected {text:?} to be rejected"),
+        Err(error) => error,
+    }
+}
+
+#[test]
+fn blank_text_is_empty() {
+    assert_eq!(parse_error(""), AsciiLevelError::Empty);
+    assert_eq!(parse_error("\n   \n\t\n"), AsciiLevelError::Empty);
+}
+
+#[test]
+fn level_without_spawn_is_rejected() {
+    assert_eq!(parse_error("#..#\n####"), AsciiLevelError::MissingSpawn);
+}
+
+#[test]
+fn level_with_two_spawns_is_rejected() {
+    assert_eq!(parse_error("P..P\n####"), AsciiLevelError::MultipleSpawns);
+}
+
+#[test]
+fn unknown_tile_reports_its_cell() {
+    assert_eq!(
+        parse_error("#.P#\n#x.#\n####"),
+        AsciiLevelError::UnknownTile {
+            row: 1,
+            column: 1,
+            character: 'x',
+        }
+    );
+}
+
+#[test]
+fn common_indent_and_surrounding_blank_lines_are_stripped() {
+    let level = parse_ascii_level(
+        "
+
+            #..P
+            ####
+
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(level.data.tiles, vec![vec![1, 0, 0, 0], vec![1, 1, 1, 1]]);
+    assert_eq!(level.data.spawn, Some([3.5, 0.5]));
+}
+
+#[test]
+fn short_rows_are_padded_with_empty_tiles() {
+    let level = parse_ascii_level(
+        "
+        P
+        #
+        ######
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        level.data.tiles,
+        vec![
+            vec![0, 0, 0, 0, 0, 0],
+            vec![1, 0, 0, 0, 0, 0],
+            vec![1, 1, 1, 1, 1, 1],
+        ]
+    );
+}
+
+#[test]
+fn dash_is_a_one_way_platform_over_the_top_quarter_of_its_cell() {
+    let level = parse_ascii_level(
+        "
+        P...
+        .-..
+        ####
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(level.one_way_platforms, vec![[1.5, 1.5]]);
+    let [area] = level.data.one_way_platforms.as_slice() else {
+        panic!("expected one one-way platform area");
+    };
+    assert_eq!(area.min, [1.0, 1.0]);
+    assert_eq!(area.max, [2.0, 1.25]);
+
+    // The collision world gets a one-way polygon spanning the cell's width and a quarter of
+    // its height, flush with its top
+    let cell_top_left = level.data.tile_to_world([1.0, 1.0], GRID_SIZE);
+    let world = Level::from_data(&level.data, GRID_SIZE);
+    let platforms: Vec<_> = world
+        .polygons
+        .iter()
+        .filter(|polygon| polygon.one_way)
+        .collect();
+    assert_eq!(platforms.len(), 1);
+
+    let aabb = platforms[0].aabb;
+    let expected_min = cell_top_left - Vec2::new(0.0, GRID_SIZE / 4.0);
+    let expected_max = cell_top_left + Vec2::new(GRID_SIZE, 0.0);
+    assert!(aabb.min.distance(expected_min) < TOLERANCE, "{aabb:?}");
+    assert!(aabb.max.distance(expected_max) < TOLERANCE, "{aabb:?}");
+}
@@ -0,0 +1,164 @@
+//! Degenerate and edge cases of the segment geometry helpers in `geometry`
+//!
+//! `cargo test --test geometry`
+
+use bevy::math::Vec2;
+use bevy_advanced_cc::geometry::{
+    closest_point_on_segment, cross_product, find_projection, line_intersect,
+    side_of_line_detection,
+};
+
+const TOLERANCE: f32 = 1e-4;
+
+fn assert_close(actual: Vec2, expected: Vec2) {
+    assert!(
+        actual.distance(expected) < TOLERANCE,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn projection_inside_segment_is_perpendicular() {
+    let (distance_sq, projection) =
+        find_projection(Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(4.0, 3.0));
+    assert_close(projection, Vec2::new(4.0, 0.0));
+    assert!((distance_sq - 9.0).abs() < TOLERANCE);
+}
+
+#[test]
+fn projection_before_start_is_the_start() {
+    let (distance_sq, projection) =
+        find_projection(Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(-3.0, 4.0));
+    assert_close(projection, Vec2::ZERO);
+    assert!((distance_sq - 25.0).abs() < TOLERANCE);
+}
+
+#[test]
+fn projection_past_end_is_the_end() {
+    let (distance_sq, projection) =
+        find_projection(Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(13.0, -4.0));
+    assert_close(projection, Vec2::new(10.0, 0.0));
+    assert!((distance_sq - 25.0).abs() < TOLERANCE);
+}
+
+#[test]
+fn projection_onto_zero_length_segment_is_the_point() {
+    let point = Vec2::new(2.0, 2.0);
+    let (distance_sq, projection) = find_projection(point, point, Vec2::new(5.0, 6.0));
+    assert_close(projection, point);
+    assert!((distance_sq - 25.0).abs() < TOLERANCE);
+    assert_close(
+        closest_point_on_segment(point, point, Vec2::new(-1.0, 0.0)),
+        point,
+    );
+}
+
+#[test]
+fn crossing_segments_intersect() {
+    let hit = line_intersect(
+        Vec2::new(-1.0, 0.0),
+        Vec2::new(1.0, 0.0),
+        Vec2::new(0.0, -1.0),
+        Vec2::new(0.0, 1.0),
+    );
+    assert_close(hit.expect("segments cross"), Vec2::ZERO);
+}
+
+#[test]
+fn segments_touching_at_an_end_point_intersect() {
+    let hit = line_intersect(
+        Vec2::ZERO,
+        Vec2::new(1.0, 0.0),
+        Vec2::new(1.0, 0.0),
+        Vec2::new(1.0, 1.0),
+    );
+    assert_close(hit.expect("segments touch"), Vec2::new(1.0, 0.0));
+}
+
+#[test]
+fn segments_short_of_each_other_miss() {
+    let hit = line_intersect(
+        Vec2::ZERO,
+        Vec2::new(1.0, 0.0),
+        Vec2::new(2.0, -1.0),
+        Vec2::new(2.0, 1.0),
+    );
+    assert!(hit.is_none());
+}
+
+#[test]
+fn parallel_segments_miss() {
+    let hit = line_intersect(
+        Vec2::ZERO,
+        Vec2::new(1.0, 0.0),
+        Vec2::new(0.0, 1.0),
+        Vec2::new(1.0, 1.0),
+    );
+    assert!(hit.is_none());
+}
+
+#[test]
+fn overlapping_collinear_segments_meet_at_first_shared_point() {
+    let hit = line_intersect(
+        Vec2::ZERO,
+        Vec2::new(10.0, 0.0),
+        Vec2::new(12.0, 0.0),
+        Vec2::new(4.0, 0.0),
+    );
+    assert_close(hit.expect("segments overlap"), Vec2::new(4.0, 0.0));
+
+    let hit = line_intersect(
+        Vec2::new(5.0, 0.0),
+        Vec2::new(10.0, 0.0),
+        Vec2::ZERO,
+        Vec2::new(8.0, 0.0),
+    );
+    assert_close(hit.expect("segments overlap"), Vec2::new(5.0, 0.0));
+}
+
+#[test]
+fn disjoint_collinear_segments_miss() {
+    let hit = line_intersect(
+        Vec2::ZERO,
+        Vec2::new(1.0, 0.0),
+        Vec2::new(2.0, 0.0),
+        Vec2::new(3.0, 0.0),
+    );
+    assert!(hit.is_none());
+}
+
+#[test]
+fn zero_length_segments_act_as_points() {
+    let point = Vec2::new(0.5, 0.0);
+    let on = line_intersect(point, point, Vec2::ZERO, Vec2::new(1.0, 0.0));
+    assert_close(on.expect("point on segment"), point);
+
+    let on = line_intersect(Vec2::ZERO, Vec2::new(1.0, 0.0), point, point);
+    assert_close(on.expect("point on segment"), point);
+
+    let off = line_intersect(
+        Vec2::new(0.5, 1.0),
+        Vec2::new(0.5, 1.0),
+        Vec2::ZERO,
+        Vec2::X,
+    );
+    assert!(off.is_none());
+}
+
+#[test]
+fn side_of_line_is_left_positive() {
+    let (start, end) = (Vec2::ZERO, Vec2::new(1.0, 0.0));
+    assert_eq!(side_of_line_detection(start, end, Vec2::new(0.5, 1.0)), 1.0);
+    assert_eq!(
+        side_of_line_detection(start, end, Vec2::new(0.5, -1.0)),
+        -1.0
+    );
+    assert_eq!(side_of_line_detection(start, end, Vec2::new(2.0, 0.0)), 1.0);
+}
+
+#[test]
+fn cross_product_sign_follows_rotation() {
+    assert_eq!(cross_product(Vec2::X, Vec2::Y), 1.0);
+    assert_eq!(cross_product(Vec2::Y, Vec2::X), -1.0);
+    assert_eq!(cross_product(Vec2::X, Vec2::new(3.0, 0.0)), 0.0);
+}
@@ -0,0 +1,53 @@
+//! Playtests every level in `assets/levels` with the auto-playtest bot and fails if one can no
+//! longer be completed within the tick budget
+//!
+//! `cargo test --features test_utils --test level_playtest`
+
+use std::{fs, path::Path, process::ExitCode};
+
+use bevy_advanced_cc::{
+    config::ControllerConfig,
+    playtest::{playtest, PlaytestBudget},
+};
+
+fn main() -> ExitCode {
+    let levels_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/levels");
+    let mut paths: Vec<_> = fs::read_dir(&levels_dir)
+        .expect("levels directory exists")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+
+    let budget = PlaytestBudget::default();
+    let mut failed = 0;
+    for path in &paths {
+        let json = fs::read_to_string(path).expect("level file is readable");
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let Some(result) = playtest(&json, ControllerConfig::default(), &budget) else {
+            println!("{name}: no goal, skipped");
+            continue;
+        };
+
+        match result.completed_in {
+            Some(ticks) => println!("{name}: completed in {ticks} ticks"),
+            None => {
+                failed += 1;
+                println!(
+                    "{name}: FAILED after {} states, closest {:.0} px from the goal",
+                    result.states, result.closest
+                );
+            }
+        }
+    }
+
+    if failed > 0 {
+        println!("{failed} of {} levels can't be completed", paths.len());
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
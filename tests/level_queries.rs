@@ -0,0 +1,139 @@
+//! `Level` spatial queries against arc edges (`circles` and `arcs` level data)
+//!
+//! `cargo test --test level_queries`
+
+use bevy::math::Vec2;
+use bevy_advanced_cc::{
+    collisions::CollisionLayers,
+    level::{ArcData, CircleData, LevelData},
+    Level, GRID_SIZE,
+};
+
+const TOLERANCE: f32 = 1e-3;
+// Radius (pixels) of the pillar in `pillar_level`
+const PILLAR_RADIUS: f32 = 2.0 * GRID_SIZE;
+
+fn assert_close(actual: Vec2, expected: Vec2) {
+    assert!(
+        actual.distance(expected) < TOLERANCE,
+        "expected {expected}, got {actual}"
+    );
+}
+
+fn empty_level_data() -> LevelData {
+    LevelData {
+        tiles: vec![vec![0; 20]; 20],
+        ..Default::default()
+    }
+}
+
+/// An empty level with a round pillar in the middle, and the pillar's center
+fn pillar_level() -> (Level, Vec2) {
+    let mut level_data = empty_level_data();
+    level_data.circles.push(CircleData {
+        center: [10.0, 10.0],
+        radius: PILLAR_RADIUS / GRID_SIZE,
+    });
+    let center = level_data.tile_to_world([10.0, 10.0], GRID_SIZE);
+
+    (Level::from_data(&level_data, GRID_SIZE), center)
+}
+
+#[test]
+fn raycast_stops_at_a_pillar() {
+    let (level, center) = pillar_level();
+    let origin = center - Vec2::new(200.0, 0.0);
+
+    let hit = level
+        .raycast(origin, Vec2::X, 400.0, CollisionLayers::default())
+        .expect("the ray hits the pillar");
+    assert_close(hit.point, center - Vec2::new(PILLAR_RADIUS, 0.0));
+    assert_close(hit.normal, -Vec2::X);
+    assert!(hit.edge.is_none());
+    assert!((hit.t - (200.0 - PILLAR_RADIUS)).abs() < TOLERANCE);
+}
+
+#[test]
+fn raycast_passes_the_open_part_of_an_arc() {
+    let mut level_data = empty_level_data();
+    // Upper half of a circle
+    level_data.arcs.push(ArcData {
+        center: [10.0, 10.0],
+        radius: 2.0,
+        start_angle: 0.0,
+        end_angle: 180.0,
+        convex: true,
+    });
+    let center = level_data.tile_to_world([10.0, 10.0], GRID_SIZE);
+    let level = Level::from_data(&level_data, GRID_SIZE);
+
+    // Below the center the ray only crosses the missing lower half
+    let below = center - Vec2::new(200.0, GRID_SIZE);
+    assert!(level
+        .raycast(below, Vec2::X, 400.0, CollisionLayers::default())
+        .is_none());
+
+    let above = center + Vec2::new(-200.0, GRID_SIZE);
+    assert!(level
+        .raycast(above, Vec2::X, 400.0, CollisionLayers::default())
+        .is_some());
+}
+
+#[test]
+fn shapecast_stops_a_circle_against_a_pillar() {
+    let (level, center) = pillar_level();
+    let radius = 8.0;
+    let origin = center + Vec2::new(0.0, 200.0);
+
+    let hit = level
+        .shapecast(origin, -Vec2::Y, radius, 400.0, CollisionLayers::default())
+        .expect("the circle hits the pillar");
+    assert_close(
+        hit.position,
+        center + Vec2::new(0.0, PILLAR_RADIUS + radius),
+    );
+    assert_close(hit.point, center + Vec2::new(0.0, PILLAR_RADIUS));
+    assert_close(hit.normal, Vec2::Y);
+    assert!(hit.edge.is_none());
+}
+
+#[test]
+fn overlap_circle_reports_a_pillar_with_its_depth() {
+    let (level, center) = pillar_level();
+    let radius = 8.0;
+    let circle = center + Vec2::new(PILLAR_RADIUS + radius - 3.0, 0.0);
+
+    let contacts = level.overlap_circle(circle, radius, CollisionLayers::default());
+    let [contact] = contacts.as_slice() else {
+        panic!("expected one contact, got {contacts:?}");
+    };
+    assert_close(contact.normal, Vec2::X);
+    assert!((contact.depth - 3.0).abs() < TOLERANCE);
+    assert!(contact.edge.is_none());
+}
+
+#[test]
+fn pillar_contains_points_inside_it() {
+    let (level, center) = pillar_level();
+
+    assert!(level.contains_point(center, CollisionLayers::default()));
+    assert!(!level.contains_point(
+        center + Vec2::new(PILLAR_RADIUS + 1.0, 0.0),
+        CollisionLayers::default()
+    ));
+    assert!(!level.contains_point(center, CollisionLayers::none()));
+}
+
+#[test]
+fn closest_point_inside_a_pillar_points_out_of_it() {
+    let (level, center) = pillar_level();
+    let point = center + Vec2::new(0.0, 10.0);
+
+    let (on_edge, normal, distance, edge) = level
+        .closest_point(point, CollisionLayers::default())
+        .expect("the pillar is in the level");
+    assert_close(on_edge, center + Vec2::new(0.0, PILLAR_RADIUS));
+    assert_close(normal, Vec2::Y);
+    assert!((distance - (PILLAR_RADIUS - 10.0)).abs() < TOLERANCE);
+    assert!(edge.is_none());
+}
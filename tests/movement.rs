@@ -0,0 +1,126 @@
+//! Controller behavior in headless `TestWorld`s
+//!
+//! `cargo test --features test_utils --test movement`
+
+use bevy::{ecs::message::Messages, prelude::*};
+use bevy_advanced_cc::{
+    collisions::LandingContact,
+    config::ControllerConfig,
+    level::{IceData, LevelData},
+    test_utils::TestWorld,
+    GRID_SIZE,
+};
+
+const WIDTH: usize = 24;
+const FLOOR_ROW: usize = 8;
+// Ticks the player gets to cross the level before the test gives up
+const MAX_TICKS: u32 = 600;
+// Ticks the player gets to land after spawning
+const SETTLE_TICKS: u32 = 120;
+// Ticks of jump trajectory compared after a restore
+const JUMP_TICKS: usize = 40;
+
+/// Empty grid with a solid floor row, the player standing on its left end
+fn floor_level(solid_columns: impl Fn(usize) -> bool) -> LevelData {
+    let mut tiles = vec![vec![0; WIDTH]; FLOOR_ROW + 2];
+    for (column, tile) in tiles[FLOOR_ROW].iter_mut().enumerate() {
+        *tile = u32::from(solid_columns(column));
+    }
+
+    LevelData {
+        tiles,
+        spawn: Some([2.5, FLOOR_ROW as f32 - 0.5]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn running_across_tile_to_ice_seams_touches_no_wall() {
+    // Tiles, then an ice strip flush with them, then tiles again
+    let mut level_data = floor_level(|column| !(8..16).contains(&column));
+    level_data.ice.push(IceData {
+        min: [8.0, FLOOR_ROW as f32],
+        max: [16.0, FLOOR_ROW as f32 + 1.0],
+        friction: None,
+    });
+    let finish_x = level_data.tile_to_world([20.0, 0.0], GRID_SIZE).x;
+    let mut world = TestWorld::new(level_data, ControllerConfig::default());
+
+    world.run(30, &[]);
+    assert!(world.is_grounded());
+
+    let mut ticks = 0;
+    while world.player_position().x < finish_x {
+        assert!(ticks < MAX_TICKS, "the player never crossed the ice");
+        world.step(&[KeyCode::ArrowRight]);
+        assert!(
+            !world.is_on_wall(),
+            "wall contact at x = {}",
+            world.player_position().x
+        );
+        assert!(world.is_grounded());
+        ticks += 1;
+    }
+}
+
+#[test]
+fn landing_on_a_seam_reports_one_landing() {
+    // Drop onto the corner where the tile floor meets a flush ice strip
+    let mut level_data = floor_level(|column| column < 8);
+    level_data.ice.push(IceData {
+        min: [8.0, FLOOR_ROW as f32],
+        max: [16.0, FLOOR_ROW as f32 + 1.0],
+        friction: None,
+    });
+    level_data.spawn = Some([8.0, FLOOR_ROW as f32 - 3.0]);
+    let mut world = TestWorld::new(level_data, ControllerConfig::default());
+
+    let mut landings = 0;
+    for _ in 0..SETTLE_TICKS {
+        world.step(&[]);
+        landings += world
+            .app
+            .world_mut()
+            .resource_mut::<Messages<LandingContact>>()
+            .drain()
+            .count();
+    }
+    assert!(world.is_grounded());
+    assert_eq!(landings, 1);
+}
+
+#[test]
+fn jump_leaves_the_ground_and_restore_replays_it() {
+    let mut world = TestWorld::new(floor_level(|_| true), ControllerConfig::default());
+
+    let mut ticks = 0;
+    while !world.is_grounded() {
+        assert!(ticks < SETTLE_TICKS, "the player never landed");
+        world.step(&[]);
+        ticks += 1;
+    }
+    let before_jump = world.snapshot();
+    let start = world.player_position();
+
+    let jump = |world: &mut TestWorld| -> Vec<Vec2> {
+        (0..JUMP_TICKS)
+            .map(|_| {
+                world.step(&[KeyCode::Space, KeyCode::ArrowRight]);
+                world.player_position()
+            })
+            .collect()
+    };
+
+    world.step(&[KeyCode::Space]);
+    assert!(world.player_velocity().y > 0.0);
+    assert!(world.player_position().y > start.y);
+
+    world.restore(&before_jump);
+    assert_eq!(world.player_position(), start);
+    let first = jump(&mut world);
+    assert!(first.iter().any(|position| position.y > start.y));
+
+    world.restore(&before_jump);
+    let second = jump(&mut world);
+    assert_eq!(first, second);
+}
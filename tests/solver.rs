@@ -0,0 +1,141 @@
+//! Contact resolution helpers in `collisions`: the penetration solver, contact normal
+//! weighting and the swept circle test used for continuous collision
+//!
+//! `cargo test --test solver`
+
+use bevy::math::Vec2;
+use bevy_advanced_cc::{
+    collisions::{combine_contact_normals, solve_penetrations, swept_circle_time_of_impact},
+    config::ControllerConfig,
+};
+
+const TOLERANCE: f32 = 1e-3;
+// Radius (pixels) of the swept circle, matching a small player
+const RADIUS: f32 = 8.0;
+
+fn assert_close(actual: Vec2, expected: Vec2) {
+    assert!(
+        actual.distance(expected) < TOLERANCE,
+        "expected {expected}, got {actual}"
+    );
+}
+
+/// Whether `correction` pushes at least as far as every penetration along its direction
+fn resolves_all(correction: Vec2, penetrations: &[Vec2]) -> bool {
+    penetrations.iter().all(|penetration| {
+        correction.dot(penetration.normalize()) >= penetration.length() - TOLERANCE
+    })
+}
+
+#[test]
+fn corner_penetrations_converge_within_the_iteration_count() {
+    let max_iterations = ControllerConfig::default().solver_iterations;
+    // Sunk 2px into a floor and 3px into a wall to the right
+    let penetrations = [Vec2::new(0.0, 2.0), Vec2::new(-3.0, 0.0)];
+
+    let (correction, iterations) = solve_penetrations(&penetrations, max_iterations);
+    assert_close(correction, Vec2::new(-3.0, 2.0));
+    assert!(
+        iterations < max_iterations,
+        "took {iterations} of {max_iterations} passes"
+    );
+}
+
+#[test]
+fn v_shaped_pit_clears_both_slopes() {
+    let max_iterations = ControllerConfig::default().solver_iterations;
+    let penetrations = [Vec2::new(-0.6, 0.8) * 2.0, Vec2::new(0.6, 0.8) * 2.0];
+
+    let (correction, iterations) = solve_penetrations(&penetrations, max_iterations);
+    assert!(resolves_all(correction, &penetrations), "{correction}");
+    assert!(
+        iterations < max_iterations,
+        "took {iterations} of {max_iterations} passes"
+    );
+}
+
+#[test]
+fn parallel_penetrations_resolve_to_the_deepest() {
+    let (correction, _) = solve_penetrations(&[Vec2::new(0.0, 1.0), Vec2::new(0.0, 3.0)], 8);
+    assert_close(correction, Vec2::new(0.0, 3.0));
+}
+
+#[test]
+fn uneven_corner_normal_leans_towards_the_deeper_contact() {
+    // 3px into the floor, 1px into a wall on the left
+    let normal = combine_contact_normals(&[(Vec2::Y, 3.0), (Vec2::X, 1.0)]);
+    assert_close(normal, Vec2::new(1.0, 3.0).normalize());
+}
+
+#[test]
+fn grazing_contact_barely_tilts_the_normal() {
+    let normal = combine_contact_normals(&[(Vec2::Y, 4.0), (Vec2::X, 0.0)]);
+    assert!(normal.y > 0.99, "{normal}");
+    assert!(normal.x > 0.0, "{normal}");
+}
+
+#[test]
+fn near_parallel_normals_count_once() {
+    let tilted = Vec2::from_angle(0.05).rotate(Vec2::Y);
+    let normal = combine_contact_normals(&[(Vec2::Y, 1.0), (tilted, 2.0), (Vec2::X, 2.0)]);
+    assert_close(normal, (tilted + Vec2::X).normalize());
+}
+
+#[test]
+fn fast_circle_does_not_tunnel_through_a_thin_wall() {
+    // A 2px thick wall, 50px ahead of a circle covering 200px in one tick
+    let faces = [
+        (Vec2::new(50.0, -20.0), Vec2::new(50.0, 20.0)),
+        (Vec2::new(52.0, -20.0), Vec2::new(52.0, 20.0)),
+    ];
+    let motion = Vec2::new(200.0, 0.0);
+
+    let toi = faces
+        .iter()
+        .filter_map(|&(edge_start, edge_end)| {
+            swept_circle_time_of_impact(Vec2::ZERO, motion, RADIUS, edge_start, edge_end)
+        })
+        .reduce(f32::min)
+        .expect("the circle hits the wall");
+    assert!(
+        (toi - (50.0 - RADIUS) / motion.x).abs() < TOLERANCE,
+        "{toi}"
+    );
+}
+
+#[test]
+fn swept_circle_hits_a_segment_end_point() {
+    // Passes just above the top of a vertical edge
+    let toi = swept_circle_time_of_impact(
+        Vec2::new(0.0, 24.0),
+        Vec2::new(200.0, 0.0),
+        RADIUS,
+        Vec2::new(50.0, -20.0),
+        Vec2::new(50.0, 20.0),
+    )
+    .expect("the circle clips the corner");
+    let center = Vec2::new(200.0 * toi, 24.0);
+    assert!((center.distance(Vec2::new(50.0, 20.0)) - RADIUS).abs() < TOLERANCE);
+}
+
+#[test]
+fn swept_circle_ignores_edges_it_moves_away_from_or_already_touches() {
+    let edge = (Vec2::new(50.0, -20.0), Vec2::new(50.0, 20.0));
+
+    assert!(swept_circle_time_of_impact(
+        Vec2::ZERO,
+        Vec2::new(-200.0, 0.0),
+        RADIUS,
+        edge.0,
+        edge.1
+    )
+    .is_none());
+    assert!(swept_circle_time_of_impact(
+        Vec2::new(50.0 - RADIUS + 1.0, 0.0),
+        Vec2::new(200.0, 0.0),
+        RADIUS,
+        edge.0,
+        edge.1
+    )
+    .is_none());
+}